@@ -13,8 +13,10 @@ pub fn main() {
         .buffers
         .iter()
         .map(|buffer| {
-            let bytes = match utils::parse_uri(&buffer.uri) {
-                utils::UriData::Bytes(bytes) => bytes,
+            let bytes = match buffer.uri.as_deref().map(utils::parse_uri) {
+                Some(utils::UriData::Bytes(bytes)) => bytes,
+                // EXT_meshopt_compression fallback buffer
+                None => vec![],
                 _ => unimplemented!(),
             };
             bytes
@@ -1,6 +1,6 @@
 use miniquad::*;
 
-use glam::{vec3, Quat, Vec3};
+use glam::Mat4;
 
 pub mod shader {
     use miniquad::*;
@@ -55,11 +55,12 @@ pub struct NodeData {
 }
 
 pub struct Node {
+    /// Index into `gltf.nodes`.
+    pub node: usize,
     pub name: String,
     pub data: Vec<NodeData>,
-    pub translation: Vec3,
-    pub rotation: Quat,
-    pub scale: Vec3,
+    /// World transform.
+    pub transform: Mat4,
 }
 
 pub struct Model {
@@ -67,21 +68,24 @@ pub struct Model {
 }
 
 pub fn load_gltf(ctx: &mut miniquad::Context, json: &str) -> Model {
-    use nanogltf::{utils, Gltf};
+    use nanogltf::{accessor::AccessorView, meshopt, scene, utils, Gltf};
 
-    let gltf = Gltf::from_json(json).unwrap();
+    let mut gltf = Gltf::from_json(json).unwrap();
     //println!("{:#?}", gltf);
-    let buffers = gltf
+    let mut buffers = gltf
         .buffers
         .iter()
         .map(|buffer| {
-            let bytes = match utils::parse_uri(&buffer.uri) {
-                utils::UriData::Bytes(bytes) => bytes,
+            let bytes = match buffer.uri.as_deref().map(utils::parse_uri) {
+                Some(utils::UriData::Bytes(bytes)) => bytes,
+                // EXT_meshopt_compression fallback buffer
+                None => vec![],
                 _ => unimplemented!(),
             };
             bytes
         })
         .collect::<Vec<_>>();
+    meshopt::decompress(&mut gltf, &mut buffers);
 
     assert!(gltf.scenes.len() == 1);
 
@@ -106,157 +110,148 @@ pub fn load_gltf(ctx: &mut miniquad::Context, json: &str) -> Model {
         textures.push(texture);
     }
 
-    let mut nodes = vec![];
-    assert!(gltf.scenes.len() == 1);
-    let scene = &gltf.scenes[0];
-    for node in &scene.nodes {
-        let node = &gltf.nodes[*node];
-        if node.children.len() != 0 {
-            continue;
-        }
-        let translation = node
-            .translation
-            .map_or(Vec3::ZERO, |t| vec3(t[0] as f32, t[1] as f32, t[2] as f32));
-        let rotation = node.rotation.map_or(Quat::IDENTITY, |t| {
-            Quat::from_xyzw(t[0] as f32, t[1] as f32, t[2] as f32, t[3] as f32)
-        });
-        let scale = node.scale.map_or(vec3(1., 1., 1.), |t| {
-            vec3(t[0] as f32, t[1] as f32, t[2] as f32)
-        });
-        let mesh = node.mesh.unwrap();
-        let mesh = &gltf.meshes[mesh];
-        let mut bindings = Vec::new();
+    let mut nodes: Vec<Node> = vec![];
+    for item in scene::flatten_scene(&gltf, gltf.scene.unwrap_or(0)) {
+        let node = &gltf.nodes[item.node];
+        let primitive = &gltf.meshes[item.mesh].primitives[item.primitive];
+        let material = &gltf.materials[primitive.material.unwrap()];
+        let color = material.pbr_metallic_roughness.base_color_factor;
+        let base_color_texture = &material.pbr_metallic_roughness.base_color_texture;
+        let base_color_texture = base_color_texture.as_ref().map(|t| textures[t.index]);
+        let metallic_roughness_texture = material
+            .pbr_metallic_roughness
+            .metallic_roughness_texture
+            .as_ref()
+            .map(|t| textures[t.index]);
+        let emissive_texture = material
+            .emissive_texture
+            .as_ref()
+            .map(|t| textures[t.index]);
+        let occlusion_texture = material
+            .occlusion_texture
+            .as_ref()
+            .map(|t| textures[t.index]);
+        let normal_texture = material.normal_texture.as_ref().map(|t| textures[t.index]);
+        let color = [
+            color[0] as f32,
+            color[1] as f32,
+            color[2] as f32,
+            color[3] as f32,
+        ];
 
-        for primitive in &mesh.primitives {
-            let material = &gltf.materials[primitive.material.unwrap()];
-            let color = material.pbr_metallic_roughness.base_color_factor;
-            let base_color_texture = &material.pbr_metallic_roughness.base_color_texture;
-            let base_color_texture = base_color_texture.as_ref().map(|t| textures[t.index]);
-            let metallic_roughness_texture = material
-                .pbr_metallic_roughness
-                .metallic_roughness_texture
-                .as_ref()
-                .map(|t| textures[t.index]);
-            let emissive_texture = material
-                .emissive_texture
-                .as_ref()
-                .map(|t| textures[t.index]);
-            let occlusion_texture = material
-                .occlusion_texture
-                .as_ref()
-                .map(|t| textures[t.index]);
-            let normal_texture = material.normal_texture.as_ref().map(|t| textures[t.index]);
-            let color = [
-                color[0] as f32,
-                color[1] as f32,
-                color[2] as f32,
-                color[3] as f32,
-            ];
+        let indices = AccessorView::<u16>::new(&gltf, &buffers, primitive.indices.unwrap())
+            .unwrap()
+            .to_slice();
+        let vertices =
+            AccessorView::<[f32; 3]>::new(&gltf, &buffers, primitive.attributes["POSITION"])
+                .unwrap()
+                .to_slice();
+        let uvs =
+            AccessorView::<[f32; 2]>::new(&gltf, &buffers, primitive.attributes["TEXCOORD_0"])
+                .unwrap()
+                .to_slice();
+        let normals =
+            AccessorView::<[f32; 3]>::new(&gltf, &buffers, primitive.attributes["NORMAL"])
+                .unwrap()
+                .to_slice();
 
-            let indices = utils::attribute_bytes(&gltf, primitive.indices.unwrap());
-            let indices = &buffers[indices.0][indices.1..indices.1 + indices.2];
-            let vertices = utils::attribute_bytes(&gltf, primitive.attributes["POSITION"]);
-            let vertices = &buffers[vertices.0][vertices.1..vertices.1 + vertices.2];
-            let uvs = utils::attribute_bytes(&gltf, primitive.attributes["TEXCOORD_0"]);
-            let uvs = &buffers[uvs.0][uvs.1..uvs.1 + uvs.2];
-            let normals = utils::attribute_bytes(&gltf, primitive.attributes["NORMAL"]);
-            let normals = &buffers[normals.0][normals.1..normals.1 + normals.2];
+        let vertex_buffer = ctx.new_buffer(
+            BufferType::VertexBuffer,
+            BufferUsage::Immutable,
+            BufferSource::slice(&vertices),
+        );
+        let normals_buffer = ctx.new_buffer(
+            BufferType::VertexBuffer,
+            BufferUsage::Immutable,
+            BufferSource::slice(&normals),
+        );
+        let uvs_buffer = ctx.new_buffer(
+            BufferType::VertexBuffer,
+            BufferUsage::Immutable,
+            BufferSource::slice(&uvs),
+        );
+        let index_buffer = ctx.new_buffer(
+            BufferType::IndexBuffer,
+            BufferUsage::Immutable,
+            BufferSource::slice(&indices),
+        );
 
-            let vertex_buffer =
-                ctx.new_buffer(BufferType::VertexBuffer, BufferUsage::Immutable, unsafe {
-                    BufferSource::pointer(vertices.as_ptr(), vertices.len(), 4 * 3)
-                });
-            let normals_buffer =
-                ctx.new_buffer(BufferType::VertexBuffer, BufferUsage::Immutable, unsafe {
-                    BufferSource::pointer(normals.as_ptr(), normals.len(), 4 * 3)
-                });
-            let uvs_buffer =
-                ctx.new_buffer(BufferType::VertexBuffer, BufferUsage::Immutable, unsafe {
-                    BufferSource::pointer(uvs.as_ptr(), uvs.len(), 4 * 2)
-                });
-            let index_buffer =
-                ctx.new_buffer(BufferType::IndexBuffer, BufferUsage::Immutable, unsafe {
-                    BufferSource::pointer(indices.as_ptr(), indices.len(), 2)
-                });
+        // Note on shaders post processing.
+        // Usually, glsl version 100 and metal would cover everything, and no post-processing would be required.
+        // But if you are looking at this code - there is a good chance you are intrested in PBR shaders
+        // and those shaders are really easier to implelement with dfDx and glTextureCube.
+        // On linux/glx and webgl1 it is possible to get those functions in version 100 shader through extensions and keep everything simple.
+        // But on mobile EGL and Mac OpenGL shader should be at least #version 300/330es.
+        //
+        // shadermagic is a VERY questionable shader compiler. But it works for this very shader.
+        // and through studying its output you can choose more appropriate cross-compiler
+        // or decide wich platforms to sacrifice and hand-write just some of those shaders.
+        let shader = shadermagic::transform(
+            shader::FRAGMENT,
+            shader::VERTEX,
+            &shader::meta(),
+            &shadermagic::Options {
+                defines: vec![
+                    "HAS_NORMAL_MAP".to_string(),
+                    "HAS_METALLIC_ROUGHNESS_MAP".to_string(),
+                ],
 
-            // Note on shaders post processing.
-            // Usually, glsl version 100 and metal would cover everything, and no post-processing would be required.
-            // But if you are looking at this code - there is a good chance you are intrested in PBR shaders
-            // and those shaders are really easier to implelement with dfDx and glTextureCube.
-            // On linux/glx and webgl1 it is possible to get those functions in version 100 shader through extensions and keep everything simple.
-            // But on mobile EGL and Mac OpenGL shader should be at least #version 300/330es.
-            //
-            // shadermagic is a VERY questionable shader compiler. But it works for this very shader.
-            // and through studying its output you can choose more appropriate cross-compiler
-            // or decide wich platforms to sacrifice and hand-write just some of those shaders.
-            let shader = shadermagic::transform(
-                shader::FRAGMENT,
-                shader::VERTEX,
-                &shader::meta(),
-                &shadermagic::Options {
-                    defines: vec![
-                        "HAS_NORMAL_MAP".to_string(),
-                        "HAS_METALLIC_ROUGHNESS_MAP".to_string(),
-                    ],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let source = shadermagic::choose_appropriate_shader(&shader, &ctx.info());
+        let shader = ctx
+            .new_shader(source, shader::meta())
+            .unwrap_or_else(|e| panic!("Failed to load shader: {}", e));
 
-                    ..Default::default()
-                },
-            )
-            .unwrap();
-            let source = shadermagic::choose_appropriate_shader(&shader, &ctx.info());
-            let shader = ctx
-                .new_shader(source, shader::meta())
-                .unwrap_or_else(|e| panic!("Failed to load shader: {}", e));
+        let pipeline = ctx.new_pipeline_with_params(
+            &[
+                BufferLayout::default(),
+                BufferLayout::default(),
+                BufferLayout::default(),
+            ],
+            &[
+                VertexAttribute::with_buffer("in_position", VertexFormat::Float3, 0),
+                VertexAttribute::with_buffer("in_uv", VertexFormat::Float2, 1),
+                VertexAttribute::with_buffer("in_normal", VertexFormat::Float3, 2),
+            ],
+            shader,
+            PipelineParams {
+                depth_test: Comparison::LessOrEqual,
+                depth_write: true,
+                ..Default::default()
+            },
+        );
 
-            let pipeline = ctx.new_pipeline_with_params(
-                &[
-                    BufferLayout::default(),
-                    BufferLayout::default(),
-                    BufferLayout::default(),
-                ],
-                &[
-                    VertexAttribute::with_buffer("in_position", VertexFormat::Float3, 0),
-                    VertexAttribute::with_buffer("in_uv", VertexFormat::Float2, 1),
-                    VertexAttribute::with_buffer("in_normal", VertexFormat::Float3, 2),
-                ],
-                shader,
-                PipelineParams {
-                    depth_test: Comparison::LessOrEqual,
-                    depth_write: true,
-                    ..Default::default()
-                },
-            );
+        let binding = NodeData {
+            pipeline,
+            color,
+            material: [
+                material.pbr_metallic_roughness.metallic_factor as f32,
+                material.pbr_metallic_roughness.roughness_factor as f32,
+                0.,
+                0.,
+            ],
+            vertex_buffers: vec![vertex_buffer, uvs_buffer, normals_buffer],
+            index_buffer,
+            base_color_texture,
+            emissive_texture,
+            occlusion_texture,
+            normal_texture,
+            metallic_roughness_texture,
+        };
 
-            bindings.push(NodeData {
-                pipeline,
-                color,
-                material: [
-                    material.pbr_metallic_roughness.metallic_factor as f32,
-                    material.pbr_metallic_roughness.roughness_factor as f32,
-                    0.,
-                    0.,
-                ],
-                vertex_buffers: vec![vertex_buffer, uvs_buffer, normals_buffer],
-                index_buffer,
-                base_color_texture,
-                emissive_texture,
-                occlusion_texture,
-                normal_texture,
-                metallic_roughness_texture,
-            });
+        // primitives of the same node come one after another
+        match nodes.last_mut() {
+            Some(last) if last.node == item.node => last.data.push(binding),
+            _ => nodes.push(Node {
+                node: item.node,
+                name: node.name.clone().unwrap_or("unnamed".to_string()),
+                data: vec![binding],
+                transform: Mat4::from_cols_array(&item.world),
+            }),
         }
-
-        nodes.push(Node {
-            name: node
-                .name
-                .clone()
-                .unwrap_or("unnamed".to_string())
-                .to_owned(),
-            data: bindings,
-            translation,
-            rotation,
-            scale,
-        });
     }
 
     Model { nodes }
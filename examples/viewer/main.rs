@@ -131,9 +131,7 @@ impl EventHandler for Stage {
 
                 let projection = proj * view;
 
-                let model = Mat4::from_translation(node.translation)
-                    * Mat4::from_quat(node.rotation)
-                    * Mat4::from_scale(node.scale);
+                let model = node.transform;
                 let model_inverse = model.inverse();
                 self.ctx
                     .apply_uniforms(UniformsSource::table(&loader::shader::Uniforms {
@@ -0,0 +1,566 @@
+//! Reading accessors data into plain rust vectors.
+//!
+//! The readers follow the accessor rules from the spec: byteStride, matrix column padding,
+//! normalized integers, sparse substitution and accessors without a bufferView.
+//! KHR_mesh_quantization attributes need nothing extra, byte and short components
+//! are dequantized by the same normalization rules. EXT_meshopt_compression buffer views
+//! are decoded on every read, `meshopt::decompress` decodes them once for all.
+//! KHR_draco_mesh_compression primitives need `draco::decompress`, behind the `draco`
+//! feature.
+//!
+//! `AccessorView` borrows the elements straight from the buffers instead, for the
+//! accessors stored the way the caller wants them. It can't borrow compressed views.
+//!
+//! `buffers` are the already loaded gltf buffers, in the same order as `gltf.buffers`.
+
+use std::borrow::Cow;
+
+use crate::gltf::{Accessor, ComponentType, Primitive, PrimitiveMode};
+use crate::{meshopt, Gltf};
+
+/// Bytes of the given buffer view, EXT_meshopt_compression views are decoded.
+///
+/// Will panic if the view is out of the buffers bounds or its compressed data is malformed.
+pub fn buffer_view_bytes<'a>(gltf: &Gltf, buffers: &'a [Vec<u8>], view: usize) -> Cow<'a, [u8]> {
+    let index = view;
+    let view = &gltf.buffer_views[view];
+    if view.meshopt_compression().is_some() {
+        let bytes = meshopt::decode_buffer_view(gltf, buffers, index);
+        return Cow::Owned(
+            bytes.unwrap_or_else(|| panic!("Malformed compressed bufferView {index}")),
+        );
+    }
+    Cow::Borrowed(&buffers[view.buffer][view.byte_offset..view.byte_offset + view.byte_length])
+}
+
+/// Size of a single element in bytes.
+/// Includes the padding the spec requires for MAT2/MAT3 columns of byte and short components.
+pub fn element_size(accessor: &Accessor) -> usize {
+    let rows = rows(accessor);
+    accessor.components() / rows * column_stride(accessor, rows)
+}
+
+fn rows(accessor: &Accessor) -> usize {
+    match accessor.type_.as_deref() {
+        Some("MAT2") => 2,
+        Some("MAT3") => 3,
+        Some("MAT4") => 4,
+        _ => accessor.components(),
+    }
+}
+
+fn column_stride(accessor: &Accessor, rows: usize) -> usize {
+    let size = rows * accessor.component_type.byte_size();
+    if rows == accessor.components() {
+        size
+    } else {
+        size.div_ceil(4) * 4
+    }
+}
+
+/// Byte offset of each component inside an element.
+fn component_offsets(accessor: &Accessor) -> Vec<usize> {
+    let rows = rows(accessor);
+    let column_stride = column_stride(accessor, rows);
+    let size = accessor.component_type.byte_size();
+    (0..accessor.components())
+        .map(|c| c / rows * column_stride + c % rows * size)
+        .collect()
+}
+
+/// Read a single component as f32, normalized integers are mapped into [0, 1] or [-1, 1].
+pub fn read_component(bytes: &[u8], component_type: &ComponentType, normalized: bool) -> f32 {
+    use ComponentType::*;
+
+    match component_type {
+        Byte => {
+            let x = bytes[0] as i8 as f32;
+            if normalized {
+                (x / 127.0).max(-1.0)
+            } else {
+                x
+            }
+        }
+        UnsignedByte => {
+            let x = bytes[0] as f32;
+            if normalized {
+                x / 255.0
+            } else {
+                x
+            }
+        }
+        Short => {
+            let x = i16::from_le_bytes([bytes[0], bytes[1]]) as f32;
+            if normalized {
+                (x / 32767.0).max(-1.0)
+            } else {
+                x
+            }
+        }
+        UnsignedShort => {
+            let x = u16::from_le_bytes([bytes[0], bytes[1]]) as f32;
+            if normalized {
+                x / 65535.0
+            } else {
+                x
+            }
+        }
+        UnsignedInt => {
+            let x = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+            if normalized {
+                (x as f64 / u32::MAX as f64) as f32
+            } else {
+                x as f32
+            }
+        }
+        Float => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+    }
+}
+
+/// Convert a raw value, as stored in `Accessor::min`/`max`, the same way `read_component` does.
+pub fn normalize_value(value: f64, component_type: &ComponentType, normalized: bool) -> f32 {
+    use ComponentType::*;
+
+    if !normalized {
+        return value as f32;
+    }
+    match component_type {
+        Byte => (value / 127.0).max(-1.0) as f32,
+        UnsignedByte => (value / 255.0) as f32,
+        Short => (value / 32767.0).max(-1.0) as f32,
+        UnsignedShort => (value / 65535.0) as f32,
+        UnsignedInt => (value / u32::MAX as f64) as f32,
+        Float => value as f32,
+    }
+}
+
+/// Read a single component as an integer, floats are truncated.
+pub fn read_component_u32(bytes: &[u8], component_type: &ComponentType) -> u32 {
+    use ComponentType::*;
+
+    match component_type {
+        Byte => bytes[0] as i8 as u32,
+        UnsignedByte => bytes[0] as u32,
+        Short => i16::from_le_bytes([bytes[0], bytes[1]]) as u32,
+        UnsignedShort => u16::from_le_bytes([bytes[0], bytes[1]]) as u32,
+        UnsignedInt => u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+        Float => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as u32,
+    }
+}
+
+fn read<T: Copy + Default>(
+    gltf: &Gltf,
+    buffers: &[Vec<u8>],
+    accessor: usize,
+    f: impl Fn(&[u8], &ComponentType) -> T,
+) -> Vec<T> {
+    let accessor = &gltf.accessors[accessor];
+    let offsets = component_offsets(accessor);
+    let n = offsets.len();
+    let size = element_size(accessor);
+    let mut res = vec![T::default(); accessor.count * n];
+
+    if let Some(view) = accessor.buffer_view {
+        let stride = gltf.buffer_views[view].byte_stride.unwrap_or(size);
+        let bytes = buffer_view_bytes(gltf, buffers, view);
+        let bytes = &bytes[accessor.byte_offset..];
+        for i in 0..accessor.count {
+            for (c, offset) in offsets.iter().enumerate() {
+                res[i * n + c] = f(&bytes[i * stride + offset..], &accessor.component_type);
+            }
+        }
+    }
+
+    if let Some(sparse) = &accessor.sparse {
+        let indices = &sparse.indices;
+        let index_size = indices.component_type.byte_size();
+        let index_bytes = buffer_view_bytes(gltf, buffers, indices.buffer_view);
+        let index_bytes = &index_bytes[indices.byte_offset..];
+        let values = &sparse.values;
+        let value_bytes = buffer_view_bytes(gltf, buffers, values.buffer_view);
+        let value_bytes = &value_bytes[values.byte_offset..];
+        for k in 0..sparse.count {
+            let i = read_component_u32(&index_bytes[k * index_size..], &indices.component_type)
+                as usize;
+            for (c, offset) in offsets.iter().enumerate() {
+                res[i * n + c] = f(&value_bytes[k * size + offset..], &accessor.component_type);
+            }
+        }
+    }
+
+    res
+}
+
+/// All the accessor's components as f32, `accessor.components()` values per element.
+///
+/// Will panic if the accessor or its views are out of the buffers bounds.
+pub fn read_f32(gltf: &Gltf, buffers: &[Vec<u8>], accessor: usize) -> Vec<f32> {
+    let normalized = gltf.accessors[accessor].normalized;
+    read(gltf, buffers, accessor, |bytes, ty| {
+        read_component(bytes, ty, normalized)
+    })
+}
+
+/// All the accessor's components as u32, `accessor.components()` values per element.
+/// Intended for indices, JOINTS_n and other integer data.
+pub fn read_u32(gltf: &Gltf, buffers: &[Vec<u8>], accessor: usize) -> Vec<u32> {
+    read(gltf, buffers, accessor, read_component_u32)
+}
+
+fn read_array<const N: usize>(gltf: &Gltf, buffers: &[Vec<u8>], accessor: usize) -> Vec<[f32; N]> {
+    let components = gltf.accessors[accessor].components();
+    assert!(
+        components == N,
+        "Accessor {accessor} has {components} components, expected {N}"
+    );
+    read_f32(gltf, buffers, accessor)
+        .chunks_exact(N)
+        .map(|c| c.try_into().unwrap())
+        .collect()
+}
+
+/// Read VEC2 accessor, TEXCOORD_n for example.
+pub fn read_vec2(gltf: &Gltf, buffers: &[Vec<u8>], accessor: usize) -> Vec<[f32; 2]> {
+    read_array(gltf, buffers, accessor)
+}
+
+/// Read VEC3 accessor, POSITION or NORMAL for example.
+pub fn read_vec3(gltf: &Gltf, buffers: &[Vec<u8>], accessor: usize) -> Vec<[f32; 3]> {
+    read_array(gltf, buffers, accessor)
+}
+
+/// Read VEC4 accessor, TANGENT or COLOR_n for example.
+pub fn read_vec4(gltf: &Gltf, buffers: &[Vec<u8>], accessor: usize) -> Vec<[f32; 4]> {
+    read_array(gltf, buffers, accessor)
+}
+
+/// Amount of vertices in the primitive, the count of its POSITION accessor.
+pub fn vertex_count(gltf: &Gltf, primitive: &Primitive) -> usize {
+    primitive
+        .attributes
+        .get("POSITION")
+        .map_or(0, |position| gltf.accessors[*position].count)
+}
+
+/// Primitive's index buffer, or 0..vertex_count for non-indexed primitives.
+pub fn primitive_indices(gltf: &Gltf, buffers: &[Vec<u8>], primitive: &Primitive) -> Vec<u32> {
+    match primitive.indices {
+        Some(indices) => read_u32(gltf, buffers, indices),
+        None => (0..vertex_count(gltf, primitive) as u32).collect(),
+    }
+}
+
+/// Primitive's indices as a triangle list, with strips and fans unrolled.
+/// None for points and lines.
+pub fn triangle_indices(
+    gltf: &Gltf,
+    buffers: &[Vec<u8>],
+    primitive: &Primitive,
+) -> Option<Vec<u32>> {
+    let indices = primitive_indices(gltf, buffers, primitive);
+
+    match primitive.mode {
+        None | Some(PrimitiveMode::Triangles) => Some(indices[..indices.len() / 3 * 3].to_vec()),
+        Some(PrimitiveMode::TriangleStrip) => Some(
+            (0..indices.len().saturating_sub(2))
+                .flat_map(|i| {
+                    let odd = i % 2;
+                    [indices[i], indices[i + 1 + odd], indices[i + 2 - odd]]
+                })
+                .collect(),
+        ),
+        Some(PrimitiveMode::TriangleFan) => Some(
+            (0..indices.len().saturating_sub(2))
+                .flat_map(|i| [indices[i + 1], indices[i + 2], indices[0]])
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+/// All the accessor's components in their stored form: integers are not normalized.
+/// This is the form `Accessor::min`/`max` use.
+pub fn read_raw(gltf: &Gltf, buffers: &[Vec<u8>], accessor: usize) -> Vec<f64> {
+    read(gltf, buffers, accessor, |bytes, ty| match ty {
+        ComponentType::Byte => bytes[0] as i8 as f64,
+        ComponentType::Short => i16::from_le_bytes([bytes[0], bytes[1]]) as f64,
+        ComponentType::Float => read_component(bytes, ty, false) as f64,
+        _ => read_component_u32(bytes, ty) as f64,
+    })
+}
+
+/// Per-component (min, max) of the actual accessor data, sparse substitution included.
+/// Values are in the stored, not normalized form, ready to be written into `Accessor::min`/`max`.
+/// None for an empty accessor.
+pub fn compute_min_max(
+    gltf: &Gltf,
+    buffers: &[Vec<u8>],
+    accessor: usize,
+) -> Option<(Vec<f64>, Vec<f64>)> {
+    let n = gltf.accessors[accessor].components();
+    let data = read_raw(gltf, buffers, accessor);
+    let mut elements = data.chunks_exact(n);
+    let first = elements.next()?;
+    let mut min = first.to_vec();
+    let mut max = first.to_vec();
+    for element in elements {
+        for c in 0..n {
+            min[c] = min[c].min(element[c]);
+            max[c] = max[c].max(element[c]);
+        }
+    }
+    Some((min, max))
+}
+
+#[derive(Debug, PartialEq)]
+pub struct MinMaxMismatch {
+    pub accessor: usize,
+    pub declared_min: Option<Vec<f64>>,
+    pub declared_max: Option<Vec<f64>>,
+    pub actual_min: Vec<f64>,
+    pub actual_max: Vec<f64>,
+}
+
+/// Accessors that should have `min`/`max`: the ones that already declare them and
+/// POSITION attributes (including morph targets), where the spec requires them.
+fn min_max_accessors(gltf: &Gltf) -> Vec<usize> {
+    let mut res: Vec<usize> = (0..gltf.accessors.len())
+        .filter(|a| gltf.accessors[*a].min.is_some() || gltf.accessors[*a].max.is_some())
+        .collect();
+    for primitive in gltf.meshes.iter().flat_map(|mesh| &mesh.primitives) {
+        let targets = primitive.targets.iter().flatten();
+        for attributes in std::iter::once(&primitive.attributes).chain(targets) {
+            if let Some(position) = attributes.get("POSITION") {
+                res.push(*position);
+            }
+        }
+    }
+    res.sort_unstable();
+    res.dedup();
+    res
+}
+
+fn same_values(
+    component_type: &ComponentType,
+    declared: &Option<Vec<f64>>,
+    actual: &[f64],
+) -> bool {
+    let Some(declared) = declared else {
+        return false;
+    };
+    declared.len() == actual.len()
+        && declared.iter().zip(actual).all(|(declared, actual)| {
+            // float data is f32, json values are f64 - compare with f32 precision
+            match component_type {
+                ComponentType::Float => *declared as f32 == *actual as f32,
+                _ => declared == actual,
+            }
+        })
+}
+
+/// Compare declared `Accessor::min`/`max` against the data.
+/// Reports accessors with wrong values and POSITION accessors missing them.
+pub fn verify_min_max(gltf: &Gltf, buffers: &[Vec<u8>]) -> Vec<MinMaxMismatch> {
+    min_max_accessors(gltf)
+        .into_iter()
+        .filter_map(|accessor| {
+            let (min, max) = compute_min_max(gltf, buffers, accessor)?;
+            let a = &gltf.accessors[accessor];
+            if same_values(&a.component_type, &a.min, &min)
+                && same_values(&a.component_type, &a.max, &max)
+            {
+                return None;
+            }
+            Some(MinMaxMismatch {
+                accessor,
+                declared_min: a.min.clone(),
+                declared_max: a.max.clone(),
+                actual_min: min,
+                actual_max: max,
+            })
+        })
+        .collect()
+}
+
+/// Rewrite `min`/`max` of every accessor `verify_min_max` would complain about.
+/// Returns the list of fixed accessors.
+///
+/// This only fixes the in-memory `Accessor`s: nanogltf has no writer, serializing the
+/// fixed document is up to the caller.
+pub fn recompute_min_max(gltf: &mut Gltf, buffers: &[Vec<u8>]) -> Vec<usize> {
+    let mismatches = verify_min_max(gltf, buffers);
+    for mismatch in &mismatches {
+        let accessor = &mut gltf.accessors[mismatch.accessor];
+        accessor.min = Some(mismatch.actual_min.clone());
+        accessor.max = Some(mismatch.actual_max.clone());
+    }
+    mismatches
+        .iter()
+        .map(|mismatch| mismatch.accessor)
+        .collect()
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Component an `AccessorView` can borrow. Implemented for every type of
+/// `ComponentType`, all of them valid for any bit pattern.
+pub trait Component: sealed::Sealed + Copy + 'static {
+    const TYPE: ComponentType;
+
+    fn from_le_bytes(bytes: &[u8]) -> Self;
+}
+
+macro_rules! impl_component {
+    ($($ty:ty => $component_type:ident),*) => {
+        $(
+            impl sealed::Sealed for $ty {}
+            impl Component for $ty {
+                const TYPE: ComponentType = ComponentType::$component_type;
+
+                fn from_le_bytes(bytes: &[u8]) -> $ty {
+                    <$ty>::from_le_bytes(bytes[..size_of::<$ty>()].try_into().unwrap())
+                }
+            }
+        )*
+    };
+}
+
+impl_component!(
+    i8 => Byte,
+    u8 => UnsignedByte,
+    i16 => Short,
+    u16 => UnsignedShort,
+    u32 => UnsignedInt,
+    f32 => Float
+);
+
+/// Accessor element: a single component for SCALAR accessors,
+/// `[C; N]` for vectors and column-major matrices.
+pub trait Element: sealed::Sealed + Copy + 'static {
+    type Component: Component;
+    const COMPONENTS: usize;
+
+    fn from_le_bytes(bytes: &[u8]) -> Self;
+}
+
+impl<C: Component> Element for C {
+    type Component = C;
+    const COMPONENTS: usize = 1;
+
+    fn from_le_bytes(bytes: &[u8]) -> C {
+        C::from_le_bytes(bytes)
+    }
+}
+
+impl<C: Component, const N: usize> sealed::Sealed for [C; N] {}
+impl<C: Component, const N: usize> Element for [C; N] {
+    type Component = C;
+    const COMPONENTS: usize = N;
+
+    fn from_le_bytes(bytes: &[u8]) -> [C; N] {
+        std::array::from_fn(|i| C::from_le_bytes(&bytes[i * size_of::<C>()..]))
+    }
+}
+
+/// Accessor elements borrowed straight from the buffer, without copying.
+///
+/// Values are in the stored form: normalized integers are not converted into floats.
+#[derive(Debug, Clone, Copy)]
+pub struct AccessorView<'a, T> {
+    bytes: &'a [u8],
+    stride: usize,
+    count: usize,
+    _element: std::marker::PhantomData<T>,
+}
+
+impl<'a, T: Element> AccessorView<'a, T> {
+    /// None when the accessor can't be borrowed as `T`: its component type or amount
+    /// of components differs, its elements are padded (MAT2 and MAT3 of bytes or shorts),
+    /// it is sparse, has no buffer view, the data is out of the buffer bounds or still
+    /// compressed with EXT_meshopt_compression, see `meshopt::decompress`.
+    /// Use `read_f32` and friends for those.
+    pub fn new(gltf: &Gltf, buffers: &'a [Vec<u8>], accessor: usize) -> Option<Self> {
+        let a = gltf.accessors.get(accessor)?;
+        let size = T::COMPONENTS * size_of::<T::Component>();
+        if a.component_type != T::Component::TYPE
+            || a.checked_components() != Some(T::COMPONENTS)
+            || element_size(a) != size
+            || a.sparse.is_some()
+        {
+            return None;
+        }
+        let view = gltf.buffer_views.get(a.buffer_view?)?;
+        if view.meshopt_compression().is_some() {
+            return None;
+        }
+        let stride = view.byte_stride.unwrap_or(size);
+        // the offsets and the count are untrusted, overflows make the view None
+        let start = view.byte_offset.checked_add(a.byte_offset)?;
+        let length = match a.count {
+            0 => 0,
+            count => (count - 1).checked_mul(stride)?.checked_add(size)?,
+        };
+        if stride < size || a.byte_offset.checked_add(length)? > view.byte_length {
+            return None;
+        }
+        Some(AccessorView {
+            bytes: buffers
+                .get(view.buffer)?
+                .get(start..start.checked_add(length)?)?,
+            stride,
+            count: a.count,
+            _element: std::marker::PhantomData,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Distance between the elements in bytes.
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// The borrowed bytes, from the first element to the end of the last one.
+    /// Ready for a GPU upload with `stride`.
+    pub fn bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    pub fn get(&self, i: usize) -> Option<T> {
+        (i < self.count).then(|| T::from_le_bytes(&self.bytes[i * self.stride..]))
+    }
+
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = T> + 'a {
+        let (bytes, stride) = (self.bytes, self.stride);
+        (0..self.count).map(move |i| T::from_le_bytes(&bytes[i * stride..]))
+    }
+
+    /// The elements as a slice of `T`, when they are tightly packed, the bytes are
+    /// aligned for `T` and the target is little endian, like glTF data.
+    pub fn as_slice(&self) -> Option<&'a [T]> {
+        let packed = self.stride == size_of::<T>();
+        let aligned = (self.bytes.as_ptr() as *const T).is_aligned();
+        if !packed || !aligned || cfg!(target_endian = "big") {
+            return None;
+        }
+        // SAFETY: the bytes are in bounds, aligned and exactly `count` elements long.
+        // `T` is a component or an array of them, every bit pattern is a valid value.
+        Some(unsafe { std::slice::from_raw_parts(self.bytes.as_ptr() as *const T, self.count) })
+    }
+
+    /// `as_slice` when possible, a copy of the elements otherwise.
+    pub fn to_slice(&self) -> Cow<'a, [T]> {
+        match self.as_slice() {
+            Some(slice) => Cow::Borrowed(slice),
+            None => Cow::Owned(self.iter().collect()),
+        }
+    }
+}
@@ -0,0 +1,474 @@
+//! Animation sampling with node TRS/weights and KHR_animation_pointer channel targets.
+//!
+//! Channel targets are resolved against the parsed document into a `Property`:
+//! core node and mesh targets, plus KHR_animation_pointer JSON pointers like
+//! "/materials/2/pbrMetallicRoughness/baseColorFactor". Pointers into properties
+//! nanogltf has no typed field for, like cameras or most material extensions,
+//! resolve to `Property::Other` and are still sampled.
+//!
+//! `evaluate` samples every channel of an animation at a time, `apply` writes the
+//! resulting updates back into the document.
+
+use crate::gltf::{Animation, AnimationChannel, AnimationSampler, Light};
+use crate::math;
+use crate::{accessor, lights, Gltf};
+
+/// Animated property of the document, indices are into the corresponding root arrays.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Property {
+    NodeTranslation(usize),
+    NodeRotation(usize),
+    NodeScale(usize),
+    NodeWeights(usize),
+    MeshWeights(usize),
+    BaseColorFactor(usize),
+    MetallicFactor(usize),
+    RoughnessFactor(usize),
+    EmissiveFactor(usize),
+    AlphaCutoff(usize),
+    NormalTextureScale(usize),
+    OcclusionTextureStrength(usize),
+    /// KHR_materials_emissive_strength.
+    EmissiveStrength(usize),
+    /// KHR_materials_ior.
+    Ior(usize),
+    /// Index into the root KHR_lights_punctual `lights`.
+    LightColor(usize),
+    LightIntensity(usize),
+    LightRange(usize),
+    SpotInnerConeAngle(usize),
+    SpotOuterConeAngle(usize),
+    /// Valid looking pointer without a typed property, kept as is.
+    Other(String),
+}
+
+impl Property {
+    /// Resolve a KHR_animation_pointer JSON pointer.
+    /// None if it points into an object missing from the document. Pointers into root
+    /// arrays nanogltf does not parse, like cameras, can't be checked and are kept as `Other`.
+    pub fn from_pointer(gltf: &Gltf, pointer: &str) -> Option<Property> {
+        let segments = pointer
+            .strip_prefix('/')?
+            .split('/')
+            .map(|s| s.replace("~1", "/").replace("~0", "~"))
+            .collect::<Vec<_>>();
+        let segments = segments.iter().map(String::as_str).collect::<Vec<_>>();
+        let index = |s: &str, len: usize| s.parse::<usize>().ok().filter(|&i| i < len);
+        let other = || Some(Property::Other(pointer.to_string()));
+
+        match segments.as_slice() {
+            ["nodes", node, rest @ ..] => {
+                let node = index(node, gltf.nodes.len())?;
+                match rest {
+                    ["translation"] => Some(Property::NodeTranslation(node)),
+                    ["rotation"] => Some(Property::NodeRotation(node)),
+                    ["scale"] => Some(Property::NodeScale(node)),
+                    ["weights"] => Some(Property::NodeWeights(node)),
+                    _ => other(),
+                }
+            }
+            ["meshes", mesh, rest @ ..] => {
+                let mesh = index(mesh, gltf.meshes.len())?;
+                match rest {
+                    ["weights"] => Some(Property::MeshWeights(mesh)),
+                    _ => other(),
+                }
+            }
+            ["materials", m, rest @ ..] => {
+                let m = index(m, gltf.materials.len())?;
+                let material = &gltf.materials[m];
+                match rest {
+                    ["pbrMetallicRoughness", "baseColorFactor"] => {
+                        Some(Property::BaseColorFactor(m))
+                    }
+                    ["pbrMetallicRoughness", "metallicFactor"] => Some(Property::MetallicFactor(m)),
+                    ["pbrMetallicRoughness", "roughnessFactor"] => {
+                        Some(Property::RoughnessFactor(m))
+                    }
+                    ["emissiveFactor"] => Some(Property::EmissiveFactor(m)),
+                    ["alphaCutoff"] => Some(Property::AlphaCutoff(m)),
+                    ["normalTexture", "scale"] => {
+                        material.normal_texture.as_ref()?;
+                        Some(Property::NormalTextureScale(m))
+                    }
+                    ["occlusionTexture", "strength"] => {
+                        material.occlusion_texture.as_ref()?;
+                        Some(Property::OcclusionTextureStrength(m))
+                    }
+                    ["extensions", "KHR_materials_emissive_strength", "emissiveStrength"] => {
+                        material.extensions.as_ref()?.emissive_strength.as_ref()?;
+                        Some(Property::EmissiveStrength(m))
+                    }
+                    ["extensions", "KHR_materials_ior", "ior"] => {
+                        material.extensions.as_ref()?.ior.as_ref()?;
+                        Some(Property::Ior(m))
+                    }
+                    _ => other(),
+                }
+            }
+            ["extensions", "KHR_lights_punctual", "lights", light, rest @ ..] => {
+                let lights = lights::lights(gltf);
+                let l = index(light, lights.len())?;
+                let spot = matches!(lights[l], Light::Spot { .. });
+                match rest {
+                    ["color"] => Some(Property::LightColor(l)),
+                    ["intensity"] => Some(Property::LightIntensity(l)),
+                    ["range"] if !matches!(lights[l], Light::Directional { .. }) => {
+                        Some(Property::LightRange(l))
+                    }
+                    ["spot", "innerConeAngle"] if spot => Some(Property::SpotInnerConeAngle(l)),
+                    ["spot", "outerConeAngle"] if spot => Some(Property::SpotOuterConeAngle(l)),
+                    _ => None,
+                }
+            }
+            [array, i, ..] if root_array_len(gltf, array).is_some() => {
+                index(i, root_array_len(gltf, array)?)?;
+                other()
+            }
+            [_, ..] => other(),
+            [] => None,
+        }
+    }
+
+    /// JSON pointer of the property, the one KHR_animation_pointer would use.
+    pub fn pointer(&self) -> String {
+        match self {
+            Property::NodeTranslation(i) => format!("/nodes/{i}/translation"),
+            Property::NodeRotation(i) => format!("/nodes/{i}/rotation"),
+            Property::NodeScale(i) => format!("/nodes/{i}/scale"),
+            Property::NodeWeights(i) => format!("/nodes/{i}/weights"),
+            Property::MeshWeights(i) => format!("/meshes/{i}/weights"),
+            Property::BaseColorFactor(i) => {
+                format!("/materials/{i}/pbrMetallicRoughness/baseColorFactor")
+            }
+            Property::MetallicFactor(i) => {
+                format!("/materials/{i}/pbrMetallicRoughness/metallicFactor")
+            }
+            Property::RoughnessFactor(i) => {
+                format!("/materials/{i}/pbrMetallicRoughness/roughnessFactor")
+            }
+            Property::EmissiveFactor(i) => format!("/materials/{i}/emissiveFactor"),
+            Property::AlphaCutoff(i) => format!("/materials/{i}/alphaCutoff"),
+            Property::NormalTextureScale(i) => format!("/materials/{i}/normalTexture/scale"),
+            Property::OcclusionTextureStrength(i) => {
+                format!("/materials/{i}/occlusionTexture/strength")
+            }
+            Property::EmissiveStrength(i) => format!(
+                "/materials/{i}/extensions/KHR_materials_emissive_strength/emissiveStrength"
+            ),
+            Property::Ior(i) => format!("/materials/{i}/extensions/KHR_materials_ior/ior"),
+            Property::LightColor(i) => format!("/extensions/KHR_lights_punctual/lights/{i}/color"),
+            Property::LightIntensity(i) => {
+                format!("/extensions/KHR_lights_punctual/lights/{i}/intensity")
+            }
+            Property::LightRange(i) => format!("/extensions/KHR_lights_punctual/lights/{i}/range"),
+            Property::SpotInnerConeAngle(i) => {
+                format!("/extensions/KHR_lights_punctual/lights/{i}/spot/innerConeAngle")
+            }
+            Property::SpotOuterConeAngle(i) => {
+                format!("/extensions/KHR_lights_punctual/lights/{i}/spot/outerConeAngle")
+            }
+            Property::Other(pointer) => pointer.clone(),
+        }
+    }
+}
+
+/// Length of the root array parsed into `Gltf`, None for other names.
+fn root_array_len(gltf: &Gltf, name: &str) -> Option<usize> {
+    Some(match name {
+        "accessors" => gltf.accessors.len(),
+        "animations" => gltf.animations.len(),
+        "buffers" => gltf.buffers.len(),
+        "bufferViews" => gltf.buffer_views.len(),
+        "images" => gltf.images.len(),
+        "materials" => gltf.materials.len(),
+        "meshes" => gltf.meshes.len(),
+        "nodes" => gltf.nodes.len(),
+        "samplers" => gltf.samplers.len(),
+        "scenes" => gltf.scenes.len(),
+        "textures" => gltf.textures.len(),
+        _ => return None,
+    })
+}
+
+/// Property the channel animates. None for unknown paths and pointers into
+/// missing objects, those channels should be ignored.
+pub fn channel_target(gltf: &Gltf, channel: &AnimationChannel) -> Option<Property> {
+    let target = &channel.target;
+    if target.path == "pointer" {
+        return Property::from_pointer(gltf, target.pointer()?);
+    }
+    let node = target.node.filter(|&node| node < gltf.nodes.len())?;
+    match target.path.as_str() {
+        "translation" => Some(Property::NodeTranslation(node)),
+        "rotation" => Some(Property::NodeRotation(node)),
+        "scale" => Some(Property::NodeScale(node)),
+        "weights" => Some(Property::NodeWeights(node)),
+        _ => None,
+    }
+}
+
+/// Sampled value of a channel's property.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PropertyUpdate {
+    pub property: Property,
+    /// Components of the property: 4 for a rotation or a base color,
+    /// one per morph target for weights, 1 for scalar factors.
+    pub value: Vec<f32>,
+}
+
+/// Sample the keyframes at `time`, clamped to the keyframe range, NaN gives the first one.
+/// `rotation` samplers are interpolated with slerp and stay normalized.
+/// Empty for samplers without keyframes.
+pub fn sample(
+    gltf: &Gltf,
+    buffers: &[Vec<u8>],
+    sampler: &AnimationSampler,
+    time: f32,
+    rotation: bool,
+) -> Vec<f32> {
+    let times = accessor::read_f32(gltf, buffers, sampler.input);
+    let values = accessor::read_f32(gltf, buffers, sampler.output);
+    let cubic = sampler.interpolation == "CUBICSPLINE";
+    let elements = if cubic { 3 } else { 1 };
+    if times.is_empty() || values.len() < times.len() * elements {
+        return vec![];
+    }
+    let n = values.len() / (times.len() * elements);
+    // keyframe value, cubic spline keyframes are (in-tangent, value, out-tangent)
+    let element = |key: usize, offset: usize| {
+        let start = (key * elements + offset) * n;
+        &values[start..start + n]
+    };
+    let key = |key: usize| element(key, cubic as usize);
+
+    let last = times.len() - 1;
+    // NaN is sampled as the first keyframe
+    if time.is_nan() || time <= times[0] {
+        return key(0).to_vec();
+    }
+    if time >= times[last] {
+        return key(last).to_vec();
+    }
+    // keyframe times should increase, the clamp keeps unsorted ones in bounds
+    let k = times
+        .partition_point(|&t| t <= time)
+        .saturating_sub(1)
+        .min(last - 1);
+    let dt = times[k + 1] - times[k];
+    let t = if dt > 0.0 {
+        (time - times[k]) / dt
+    } else {
+        0.0
+    };
+
+    match sampler.interpolation.as_str() {
+        "STEP" => key(k).to_vec(),
+        "CUBICSPLINE" => {
+            let (p0, m0) = (key(k), element(k, 2));
+            let (p1, m1) = (key(k + 1), element(k + 1, 0));
+            let (t2, t3) = (t * t, t * t * t);
+            let value = (0..n)
+                .map(|i| {
+                    (2.0 * t3 - 3.0 * t2 + 1.0) * p0[i]
+                        + (t3 - 2.0 * t2 + t) * dt * m0[i]
+                        + (-2.0 * t3 + 3.0 * t2) * p1[i]
+                        + (t3 - t2) * dt * m1[i]
+                })
+                .collect::<Vec<_>>();
+            if rotation && n == 4 {
+                let len = value.iter().map(|c| c * c).sum::<f32>().sqrt();
+                return value.iter().map(|c| c / len).collect();
+            }
+            value
+        }
+        _ if rotation && n == 4 => {
+            let (a, b) = (key(k), key(k + 1));
+            math::slerp([a[0], a[1], a[2], a[3]], [b[0], b[1], b[2], b[3]], t).to_vec()
+        }
+        _ => {
+            let (a, b) = (key(k), key(k + 1));
+            (0..n).map(|i| a[i] + (b[i] - a[i]) * t).collect()
+        }
+    }
+}
+
+/// Last keyframe time of the animation.
+pub fn duration(gltf: &Gltf, buffers: &[Vec<u8>], animation: &Animation) -> f32 {
+    animation
+        .samplers
+        .iter()
+        .filter_map(|sampler| {
+            let times = accessor::read_f32(gltf, buffers, sampler.input);
+            times.last().copied()
+        })
+        .fold(0.0, f32::max)
+}
+
+/// Sample every channel of the animation at `time`.
+/// Channels with unresolved targets or missing samplers are skipped.
+pub fn evaluate(
+    gltf: &Gltf,
+    buffers: &[Vec<u8>],
+    animation: &Animation,
+    time: f32,
+) -> Vec<PropertyUpdate> {
+    animation
+        .channels
+        .iter()
+        .filter_map(|channel| {
+            let property = channel_target(gltf, channel)?;
+            let sampler = animation.samplers.get(channel.sampler)?;
+            let rotation = matches!(property, Property::NodeRotation(_));
+            let value = sample(gltf, buffers, sampler, time, rotation);
+            (!value.is_empty()).then_some(PropertyUpdate { property, value })
+        })
+        .collect()
+}
+
+/// Write the updates into the document, `Property::Other` ones and values with
+/// the wrong amount of components are ignored.
+pub fn apply(gltf: &mut Gltf, updates: &[PropertyUpdate]) {
+    for update in updates {
+        let value = &update.value;
+        let scalar = (value.len() == 1).then(|| value[0] as f64);
+        match update.property {
+            Property::NodeTranslation(i) => {
+                if let Some(v) = array(value) {
+                    gltf.nodes[i].translation = Some(v);
+                }
+            }
+            Property::NodeRotation(i) => {
+                if let Some(v) = array(value) {
+                    gltf.nodes[i].rotation = Some(v);
+                }
+            }
+            Property::NodeScale(i) => {
+                if let Some(v) = array(value) {
+                    gltf.nodes[i].scale = Some(v);
+                }
+            }
+            Property::NodeWeights(i) => {
+                gltf.nodes[i].weights = Some(value.iter().map(|&w| w as f64).collect());
+            }
+            Property::MeshWeights(i) => {
+                gltf.meshes[i].weights = Some(value.iter().map(|&w| w as f64).collect());
+            }
+            Property::BaseColorFactor(i) => {
+                if let Some(v) = array(value) {
+                    gltf.materials[i].pbr_metallic_roughness.base_color_factor = v;
+                }
+            }
+            Property::MetallicFactor(i) => {
+                if let Some(v) = scalar {
+                    gltf.materials[i].pbr_metallic_roughness.metallic_factor = v;
+                }
+            }
+            Property::RoughnessFactor(i) => {
+                if let Some(v) = scalar {
+                    gltf.materials[i].pbr_metallic_roughness.roughness_factor = v;
+                }
+            }
+            Property::EmissiveFactor(i) => {
+                if let Some(v) = array(value) {
+                    gltf.materials[i].emissive_factor = v;
+                }
+            }
+            Property::AlphaCutoff(i) => {
+                if let Some(v) = scalar {
+                    gltf.materials[i].alpha_cutoff = v;
+                }
+            }
+            Property::NormalTextureScale(i) => {
+                if let (Some(v), Some(texture)) = (scalar, &mut gltf.materials[i].normal_texture) {
+                    texture.scale = v;
+                }
+            }
+            Property::OcclusionTextureStrength(i) => {
+                if let (Some(v), Some(texture)) = (scalar, &mut gltf.materials[i].occlusion_texture)
+                {
+                    texture.strength = v;
+                }
+            }
+            Property::EmissiveStrength(i) => {
+                let extension = gltf.materials[i]
+                    .extensions
+                    .as_mut()
+                    .and_then(|e| e.emissive_strength.as_mut());
+                if let (Some(v), Some(extension)) = (scalar, extension) {
+                    extension.emissive_strength = v;
+                }
+            }
+            Property::Ior(i) => {
+                let extension = gltf.materials[i]
+                    .extensions
+                    .as_mut()
+                    .and_then(|e| e.ior.as_mut());
+                if let (Some(v), Some(extension)) = (scalar, extension) {
+                    extension.ior = v;
+                }
+            }
+            Property::LightColor(i) => {
+                if let (Some(v), Some(light)) = (array(value), light_mut(gltf, i)) {
+                    match light {
+                        Light::Directional { color, .. }
+                        | Light::Point { color, .. }
+                        | Light::Spot { color, .. } => *color = v,
+                    }
+                }
+            }
+            Property::LightIntensity(i) => {
+                if let (Some(v), Some(light)) = (scalar, light_mut(gltf, i)) {
+                    match light {
+                        Light::Directional { intensity, .. }
+                        | Light::Point { intensity, .. }
+                        | Light::Spot { intensity, .. } => *intensity = v,
+                    }
+                }
+            }
+            Property::LightRange(i) => {
+                if let (Some(v), Some(Light::Point { range, .. } | Light::Spot { range, .. })) =
+                    (scalar, light_mut(gltf, i))
+                {
+                    *range = Some(v);
+                }
+            }
+            Property::SpotInnerConeAngle(i) => {
+                if let (
+                    Some(v),
+                    Some(Light::Spot {
+                        inner_cone_angle, ..
+                    }),
+                ) = (scalar, light_mut(gltf, i))
+                {
+                    *inner_cone_angle = v;
+                }
+            }
+            Property::SpotOuterConeAngle(i) => {
+                if let (
+                    Some(v),
+                    Some(Light::Spot {
+                        outer_cone_angle, ..
+                    }),
+                ) = (scalar, light_mut(gltf, i))
+                {
+                    *outer_cone_angle = v;
+                }
+            }
+            Property::Other(_) => {}
+        }
+    }
+}
+
+fn array<const N: usize>(value: &[f32]) -> Option<[f64; N]> {
+    (value.len() == N).then(|| std::array::from_fn(|i| value[i] as f64))
+}
+
+fn light_mut(gltf: &mut Gltf, light: usize) -> Option<&mut Light> {
+    gltf.extensions
+        .as_mut()?
+        .khr_lights_punctual
+        .as_mut()?
+        .lights
+        .get_mut(light)
+}
@@ -0,0 +1,194 @@
+//! Axis aligned bounding boxes and bounding spheres.
+//!
+//! Primitive bounds come from POSITION's `min`/`max` when they are present and from the
+//! vertex data otherwise. Morph targets grow the box by their displacement extents,
+//! assuming weights in [0, 1]. Skinning is not taken into account.
+
+use crate::math::{self, Mat4, Vec3};
+use crate::{accessor, gltf::Primitive, scene, Gltf};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Aabb {
+    pub min: [f32; 3],
+    pub max: [f32; 3],
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Sphere {
+    pub center: [f32; 3],
+    pub radius: f32,
+}
+
+impl Aabb {
+    /// None for an empty point set.
+    pub fn from_points(points: &[[f32; 3]]) -> Option<Aabb> {
+        let first = *points.first()?;
+        Some(points.iter().fold(
+            Aabb {
+                min: first,
+                max: first,
+            },
+            |aabb, p| aabb.union(&Aabb { min: *p, max: *p }),
+        ))
+    }
+
+    pub fn union(&self, other: &Aabb) -> Aabb {
+        Aabb {
+            min: [0, 1, 2].map(|i| self.min[i].min(other.min[i])),
+            max: [0, 1, 2].map(|i| self.max[i].max(other.max[i])),
+        }
+    }
+
+    pub fn center(&self) -> [f32; 3] {
+        math::scale(math::add(self.min, self.max), 0.5)
+    }
+
+    /// Half of the box size.
+    pub fn extents(&self) -> [f32; 3] {
+        math::scale(math::sub(self.max, self.min), 0.5)
+    }
+
+    /// Box containing this box transformed by the given column-major matrix.
+    pub fn transform(&self, m: &Mat4) -> Aabb {
+        let center = math::transform_point(m, self.center());
+        let e = self.extents();
+        let extents = [0, 1, 2].map(|i| (0..3).map(|j| m[j * 4 + i].abs() * e[j]).sum::<f32>());
+        Aabb {
+            min: math::sub(center, extents),
+            max: math::add(center, extents),
+        }
+    }
+
+    pub fn bounding_sphere(&self) -> Sphere {
+        Sphere {
+            center: self.center(),
+            radius: math::length(self.extents()),
+        }
+    }
+}
+
+impl Sphere {
+    /// Ritter's bounding sphere, usually tighter than the box's sphere.
+    pub fn from_points(points: &[[f32; 3]]) -> Option<Sphere> {
+        let first = *points.first()?;
+        let farthest = |from: Vec3| {
+            *points
+                .iter()
+                .max_by(|a, b| {
+                    let a = math::length(math::sub(**a, from));
+                    let b = math::length(math::sub(**b, from));
+                    a.total_cmp(&b)
+                })
+                .unwrap()
+        };
+        let a = farthest(first);
+        let b = farthest(a);
+        let mut sphere = Sphere {
+            center: math::scale(math::add(a, b), 0.5),
+            radius: math::length(math::sub(b, a)) * 0.5,
+        };
+        for p in points {
+            let d = math::length(math::sub(*p, sphere.center));
+            if d > sphere.radius {
+                let radius = (sphere.radius + d) * 0.5;
+                let shift = math::scale(math::sub(*p, sphere.center), (radius - sphere.radius) / d);
+                sphere = Sphere {
+                    center: math::add(sphere.center, shift),
+                    radius,
+                };
+            }
+        }
+        Some(sphere)
+    }
+}
+
+/// Bounds of a VEC3 accessor, from its `min`/`max` or from the data.
+pub fn accessor_aabb(gltf: &Gltf, buffers: &[Vec<u8>], accessor: usize) -> Option<Aabb> {
+    let a = &gltf.accessors[accessor];
+    match (&a.min, &a.max) {
+        (Some(min), Some(max)) if min.len() == 3 && max.len() == 3 => {
+            let value = |v: &[f64], i: usize| {
+                accessor::normalize_value(v[i], &a.component_type, a.normalized)
+            };
+            Some(Aabb {
+                min: [0, 1, 2].map(|i| value(min, i)),
+                max: [0, 1, 2].map(|i| value(max, i)),
+            })
+        }
+        _ => Aabb::from_points(&accessor::read_vec3(gltf, buffers, accessor)),
+    }
+}
+
+/// Local space bounds of the primitive, including its morph targets extents.
+pub fn primitive_aabb(gltf: &Gltf, buffers: &[Vec<u8>], primitive: &Primitive) -> Option<Aabb> {
+    let mut aabb = accessor_aabb(gltf, buffers, *primitive.attributes.get("POSITION")?)?;
+
+    for target in primitive.targets.iter().flatten() {
+        if let Some(displacement) = target.get("POSITION") {
+            if let Some(d) = accessor_aabb(gltf, buffers, *displacement) {
+                aabb.min = math::add(aabb.min, d.min.map(|x| x.min(0.0)));
+                aabb.max = math::add(aabb.max, d.max.map(|x| x.max(0.0)));
+            }
+        }
+    }
+    Some(aabb)
+}
+
+/// Local space bounds of all the mesh primitives.
+pub fn mesh_aabb(gltf: &Gltf, buffers: &[Vec<u8>], mesh: usize) -> Option<Aabb> {
+    gltf.meshes[mesh]
+        .primitives
+        .iter()
+        .filter_map(|primitive| primitive_aabb(gltf, buffers, primitive))
+        .reduce(|a, b| a.union(&b))
+}
+
+/// World space bounds of every node together with all its descendants,
+/// indexed the same way as `gltf.nodes`. None for subtrees without meshes.
+pub fn node_bounds(gltf: &Gltf, buffers: &[Vec<u8>]) -> Vec<Option<Aabb>> {
+    let meshes: Vec<Option<Aabb>> = (0..gltf.meshes.len())
+        .map(|mesh| mesh_aabb(gltf, buffers, mesh))
+        .collect();
+    let world = scene::world_transforms(gltf);
+    let mut bounds: Vec<Option<Aabb>> = gltf
+        .nodes
+        .iter()
+        .zip(&world)
+        .map(|(node, transform)| {
+            node.mesh
+                .and_then(|mesh| meshes[mesh])
+                .map(|aabb| aabb.transform(transform))
+        })
+        .collect();
+
+    // children are always after their parents in `order`,
+    // so walking it backwards accumulates the whole subtree into each node
+    let parents = scene::parents(gltf);
+    let roots: Vec<usize> = (0..gltf.nodes.len())
+        .filter(|node| parents[*node].is_none())
+        .collect();
+    for node in scene::subtree_nodes(gltf, &roots).into_iter().rev() {
+        if let (Some(parent), Some(aabb)) = (parents[node], bounds[node]) {
+            bounds[parent] = Some(bounds[parent].map_or(aabb, |b| b.union(&aabb)));
+        }
+    }
+    bounds
+}
+
+/// World space bounds of everything in the scene.
+pub fn scene_aabb(gltf: &Gltf, buffers: &[Vec<u8>], scene: usize) -> Option<Aabb> {
+    let bounds = node_bounds(gltf, buffers);
+    gltf.scenes[scene]
+        .nodes
+        .iter()
+        .filter_map(|node| bounds[*node])
+        .reduce(|a, b| a.union(&b))
+}
+
+/// Bounding sphere of every node subtree, see `node_bounds`.
+pub fn node_spheres(gltf: &Gltf, buffers: &[Vec<u8>]) -> Vec<Option<Sphere>> {
+    node_bounds(gltf, buffers)
+        .iter()
+        .map(|aabb| aabb.map(|aabb| aabb.bounding_sphere()))
+        .collect()
+}
@@ -0,0 +1,280 @@
+//! KHR_draco_mesh_compression decoder.
+//!
+//! A port of the Draco reference mesh decoder for bitstream version 2.2, the one written by
+//! Draco 1.4 and later: sequential and edgebreaker (standard and valence) connectivity,
+//! the generic, integer, quantization and octahedral normal attribute decoders with all the
+//! non deprecated prediction schemes. Point clouds are not supported, the extension is for
+//! triangle meshes only.
+//!
+//! All decoders return None on malformed input instead of panicking.
+
+mod attributes;
+mod buffer;
+mod connectivity;
+mod prediction;
+mod rans;
+mod traversal;
+
+use std::collections::HashMap;
+
+use crate::gltf::{Accessor, BufferView, ComponentType, Primitive};
+use crate::Gltf;
+
+pub use attributes::{Attribute, AttributeType};
+use buffer::Buffer;
+
+const VERSION_MAJOR: u8 = 2;
+const VERSION_MINOR: u8 = 2;
+
+const ENCODER_TRIANGULAR_MESH: u8 = 1;
+
+const METHOD_SEQUENTIAL: u8 = 0;
+const METHOD_EDGEBREAKER: u8 = 1;
+
+const FLAG_METADATA: u16 = 0x8000;
+
+/// Decoded draco mesh. Faces index points, a point is a unique combination of the
+/// attribute values, the same way glTF vertices are.
+#[derive(Debug)]
+pub struct Mesh {
+    pub faces: Vec<[u32; 3]>,
+    pub num_points: usize,
+    pub attributes: Vec<Attribute>,
+}
+
+impl Mesh {
+    pub fn attribute(&self, unique_id: u32) -> Option<&Attribute> {
+        self.attributes.iter().find(|a| a.unique_id == unique_id)
+    }
+}
+
+/// Decode the draco bitstream.
+pub fn decode(data: &[u8]) -> Option<Mesh> {
+    let mut buffer = Buffer::new(data);
+    if buffer.bytes(5)? != b"DRACO" {
+        return None;
+    }
+    let major = buffer.u8()?;
+    let minor = buffer.u8()?;
+    if (major, minor) != (VERSION_MAJOR, VERSION_MINOR) {
+        return None;
+    }
+    if buffer.u8()? != ENCODER_TRIANGULAR_MESH {
+        return None;
+    }
+    let method = buffer.u8()?;
+    let flags = buffer.u16()?;
+    if flags & FLAG_METADATA != 0 {
+        skip_metadata(&mut buffer)?;
+    }
+
+    let connectivity = match method {
+        METHOD_SEQUENTIAL => connectivity::decode_sequential(&mut buffer)?,
+        METHOD_EDGEBREAKER => connectivity::decode_edgebreaker(&mut buffer)?,
+        _ => return None,
+    };
+    let attributes = attributes::decode(&mut buffer, &connectivity, method == METHOD_EDGEBREAKER)?;
+    Some(Mesh {
+        faces: connectivity.faces,
+        num_points: connectivity.num_points,
+        attributes,
+    })
+}
+
+/// Metadata is not used by glTF, attribute semantics come from the extension instead.
+fn skip_metadata(buffer: &mut Buffer) -> Option<()> {
+    let num_attribute_metadata = buffer.varint()?;
+    for _ in 0..num_attribute_metadata {
+        let _unique_id = buffer.varint()?;
+        skip_metadata_block(buffer)?;
+    }
+    skip_metadata_block(buffer)
+}
+
+fn skip_metadata_block(buffer: &mut Buffer) -> Option<()> {
+    // Nested metadata is stored depth first, each one prefixed with its name.
+    // Count the blocks left instead of recursing.
+    let mut pending = 1usize;
+    let mut named = false;
+    while pending > 0 {
+        pending -= 1;
+        if named {
+            let name_length = buffer.u8()? as usize;
+            buffer.advance(name_length)?;
+        }
+        named = true;
+        let num_entries = buffer.varint()?;
+        for _ in 0..num_entries {
+            let name_length = buffer.u8()? as usize;
+            buffer.advance(name_length)?;
+            let data_size = buffer.varint()? as usize;
+            if data_size == 0 {
+                return None;
+            }
+            buffer.advance(data_size)?;
+        }
+        let num_sub_metadata = buffer.varint()? as usize;
+        if num_sub_metadata > buffer.remaining_size() {
+            return None;
+        }
+        pending += num_sub_metadata;
+    }
+    Some(())
+}
+
+/// Decompressed primitive, one entry per point.
+#[derive(Debug)]
+pub struct DecodedPrimitive {
+    /// Triangle list indices.
+    pub indices: Vec<u32>,
+    /// Attribute semantic to its values, `components()` of the primitive's accessor per point,
+    /// in the stored form: integers are not normalized.
+    pub attributes: HashMap<String, Vec<f64>>,
+}
+
+/// Decode the primitive's KHR_draco_mesh_compression data into the attributes of
+/// the extension's attribute map. None if the primitive is not compressed or the
+/// compressed data is malformed.
+pub fn decode_primitive(
+    gltf: &Gltf,
+    buffers: &[Vec<u8>],
+    primitive: &Primitive,
+) -> Option<DecodedPrimitive> {
+    let compression = primitive.draco_mesh_compression()?;
+    let view = gltf.buffer_views.get(compression.buffer_view)?;
+    let end = view.byte_offset.checked_add(view.byte_length)?;
+    let data = buffers.get(view.buffer)?.get(view.byte_offset..end)?;
+    let mesh = decode(data)?;
+
+    let mut attributes = HashMap::new();
+    for (semantic, &unique_id) in &compression.attributes {
+        let attribute = mesh.attribute(unique_id)?;
+        if let Some(&accessor) = primitive.attributes.get(semantic) {
+            let accessor = gltf.accessors.get(accessor)?;
+            if accessor.count != mesh.num_points
+                || accessor.checked_components() != Some(attribute.num_components)
+            {
+                return None;
+            }
+        }
+        attributes.insert(semantic.clone(), attribute.point_values());
+    }
+    Some(DecodedPrimitive {
+        indices: mesh.faces.iter().flatten().copied().collect(),
+        attributes,
+    })
+}
+
+/// Decode all the compressed primitives and point their accessors at the decoded data,
+/// stored in a new buffer appended to `buffers`. The extension is removed from the
+/// decoded primitives, so the `accessor` readers work on them as usual. Primitives without
+/// `indices` get a new accessor for the decoded faces.
+///
+/// Returns the amount of decoded primitives, the ones failing to decode are left as is.
+pub fn decompress(gltf: &mut Gltf, buffers: &mut Vec<Vec<u8>>) -> usize {
+    let buffer = buffers.len();
+    let mut data = vec![];
+    let mut decoded = 0;
+    for m in 0..gltf.meshes.len() {
+        for p in 0..gltf.meshes[m].primitives.len() {
+            let primitive = &gltf.meshes[m].primitives[p];
+            let Some(primitive_data) = decode_primitive(gltf, buffers, primitive) else {
+                continue;
+            };
+            let mut streams = vec![];
+            let indices = match primitive.indices {
+                Some(indices)
+                    if gltf
+                        .accessors
+                        .get(indices)
+                        .is_none_or(|a| a.count != primitive_data.indices.len()) =>
+                {
+                    continue;
+                }
+                Some(indices) => indices,
+                // the compressed faces still index the points, give them an accessor
+                None => {
+                    let max = primitive_data.indices.iter().max().copied().unwrap_or(0);
+                    gltf.accessors.push(Accessor {
+                        buffer_view: None,
+                        byte_offset: 0,
+                        component_type: if max < u16::MAX as u32 {
+                            ComponentType::UnsignedShort
+                        } else {
+                            ComponentType::UnsignedInt
+                        },
+                        normalized: false,
+                        count: primitive_data.indices.len(),
+                        max: None,
+                        min: None,
+                        sparse: None,
+                        name: None,
+                        type_: Some("SCALAR".to_string()),
+                        custom_extensions: None,
+                    });
+                    gltf.meshes[m].primitives[p].indices = Some(gltf.accessors.len() - 1);
+                    gltf.accessors.len() - 1
+                }
+            };
+            let values = primitive_data.indices.iter().map(|&i| i as f64);
+            streams.push((indices, values.collect::<Vec<_>>()));
+            let primitive = &gltf.meshes[m].primitives[p];
+            for (semantic, values) in primitive_data.attributes {
+                if let Some(&accessor) = primitive.attributes.get(&semantic) {
+                    streams.push((accessor, values));
+                }
+            }
+
+            for (accessor, values) in streams {
+                let component_type = &gltf.accessors[accessor].component_type;
+                let byte_offset = data.len();
+                for value in values {
+                    write_component(&mut data, value, component_type);
+                }
+                data.resize(data.len().div_ceil(4) * 4, 0);
+                gltf.buffer_views.push(BufferView {
+                    buffer,
+                    byte_offset,
+                    byte_length: data.len() - byte_offset,
+                    byte_stride: None,
+                    target: None,
+                    name: None,
+                    extensions: None,
+                    custom_extensions: None,
+                });
+                let accessor = &mut gltf.accessors[accessor];
+                accessor.buffer_view = Some(gltf.buffer_views.len() - 1);
+                accessor.byte_offset = 0;
+                accessor.sparse = None;
+            }
+            if let Some(extensions) = &mut gltf.meshes[m].primitives[p].extensions {
+                extensions.khr_draco_mesh_compression = None;
+            }
+            decoded += 1;
+        }
+    }
+    if decoded > 0 {
+        gltf.buffers.push(crate::gltf::Buffer {
+            uri: None,
+            byte_length: data.len(),
+            name: None,
+            extensions: None,
+            custom_extensions: None,
+        });
+        buffers.push(data);
+    }
+    decoded
+}
+
+fn write_component(data: &mut Vec<u8>, value: f64, component_type: &ComponentType) {
+    use ComponentType::*;
+
+    match component_type {
+        Byte => data.push(value as i8 as u8),
+        UnsignedByte => data.push(value as u8),
+        Short => data.extend_from_slice(&(value as i16).to_le_bytes()),
+        UnsignedShort => data.extend_from_slice(&(value as u16).to_le_bytes()),
+        UnsignedInt => data.extend_from_slice(&(value as u32).to_le_bytes()),
+        Float => data.extend_from_slice(&(value as f32).to_le_bytes()),
+    }
+}
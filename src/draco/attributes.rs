@@ -0,0 +1,417 @@
+//! Attribute decoders: the portable integer values are decoded for all attributes of a
+//! decoder first, then converted into the attribute data types.
+
+use super::buffer::Buffer;
+use super::connectivity::{Connectivity, Table};
+use super::prediction::{
+    MeshData, Octahedron, Positions, Prediction, PREDICTION_NONE, TRANSFORM_NORMAL_OCTAHEDRON,
+    TRANSFORM_NORMAL_OCTAHEDRON_CANONICALIZED, TRANSFORM_WRAP,
+};
+use super::rans::decode_symbols;
+use super::traversal::{self, EncodingData};
+
+const MESH_VERTEX_ATTRIBUTE: u8 = 0;
+const MESH_CORNER_ATTRIBUTE: u8 = 1;
+
+const TRAVERSAL_DEPTH_FIRST: u8 = 0;
+const TRAVERSAL_PREDICTION_DEGREE: u8 = 1;
+
+const DECODER_GENERIC: u8 = 0;
+const DECODER_INTEGER: u8 = 1;
+const DECODER_QUANTIZATION: u8 = 2;
+const DECODER_NORMALS: u8 = 3;
+
+const DT_INT8: u8 = 1;
+const DT_UINT8: u8 = 2;
+const DT_INT16: u8 = 3;
+const DT_UINT16: u8 = 4;
+const DT_INT32: u8 = 5;
+const DT_UINT32: u8 = 6;
+const DT_INT64: u8 = 7;
+const DT_UINT64: u8 = 8;
+const DT_FLOAT32: u8 = 9;
+const DT_FLOAT64: u8 = 10;
+const DT_BOOL: u8 = 11;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AttributeType {
+    Position,
+    Normal,
+    Color,
+    TexCoord,
+    Generic,
+    Tangent,
+    Material,
+    Joints,
+    Weights,
+}
+
+impl AttributeType {
+    fn from_u8(n: u8) -> Option<AttributeType> {
+        use AttributeType::*;
+
+        Some(match n {
+            0 => Position,
+            1 => Normal,
+            2 => Color,
+            3 => TexCoord,
+            4 => Generic,
+            5 => Tangent,
+            6 => Material,
+            7 => Joints,
+            8 => Weights,
+            _ => return None,
+        })
+    }
+}
+
+/// Decoded attribute, values are shared between the points with the same attribute data.
+#[derive(Debug)]
+pub struct Attribute {
+    pub attribute_type: AttributeType,
+    /// Id the extension's attribute map refers to.
+    pub unique_id: u32,
+    pub num_components: usize,
+    pub normalized: bool,
+    /// `num_components` values per unique value, in their stored form:
+    /// integers are not normalized.
+    pub values: Vec<f64>,
+    /// Index into `values` for each point.
+    pub point_to_value: Vec<u32>,
+}
+
+impl Attribute {
+    /// `num_components` values for each point.
+    pub fn point_values(&self) -> Vec<f64> {
+        let n = self.num_components;
+        self.point_to_value
+            .iter()
+            .flat_map(|&value| &self.values[value as usize * n..value as usize * n + n])
+            .copied()
+            .collect()
+    }
+}
+
+struct Header {
+    attribute_type: AttributeType,
+    data_type: u8,
+    num_components: usize,
+    normalized: bool,
+    unique_id: u32,
+    decoder: u8,
+}
+
+fn data_type_size(data_type: u8) -> usize {
+    match data_type {
+        DT_INT8 | DT_UINT8 | DT_BOOL => 1,
+        DT_INT16 | DT_UINT16 => 2,
+        DT_INT32 | DT_UINT32 | DT_FLOAT32 => 4,
+        _ => 8,
+    }
+}
+
+/// Decode all the attributes following the connectivity.
+/// Attribute decoders are identified by their traversal for edgebreaker meshes only,
+/// sequential meshes store the values in point order.
+pub fn decode(
+    buffer: &mut Buffer,
+    connectivity: &Connectivity,
+    edgebreaker: bool,
+) -> Option<Vec<Attribute>> {
+    let num_decoders = buffer.u8()? as usize;
+    let mut identifiers = vec![];
+    for _ in 0..num_decoders {
+        if edgebreaker {
+            identifiers.push(Some((buffer.i8()?, buffer.u8()?, buffer.u8()?)));
+        } else {
+            identifiers.push(None);
+        }
+    }
+
+    let mut decoders = vec![];
+    for _ in 0..num_decoders {
+        let num_attributes = buffer.varint()? as usize;
+        if num_attributes == 0 || num_attributes > 5 * buffer.remaining_size() {
+            return None;
+        }
+        let mut headers = vec![];
+        for _ in 0..num_attributes {
+            let attribute_type = AttributeType::from_u8(buffer.u8()?)?;
+            let data_type = buffer.u8()?;
+            let num_components = buffer.u8()? as usize;
+            let normalized = buffer.u8()? != 0;
+            let unique_id = buffer.varint()?;
+            if !(DT_INT8..=DT_BOOL).contains(&data_type) || num_components == 0 {
+                return None;
+            }
+            headers.push(Header {
+                attribute_type,
+                data_type,
+                num_components,
+                normalized,
+                unique_id,
+                decoder: 0,
+            });
+        }
+        for header in &mut headers {
+            header.decoder = buffer.u8()?;
+            let valid = match header.decoder {
+                DECODER_GENERIC => true,
+                DECODER_INTEGER => header.data_type <= DT_UINT32,
+                DECODER_QUANTIZATION => header.data_type == DT_FLOAT32,
+                DECODER_NORMALS => header.data_type == DT_FLOAT32 && header.num_components == 3,
+                _ => false,
+            };
+            if !valid {
+                return None;
+            }
+        }
+        decoders.push(headers);
+    }
+
+    let mut attributes = vec![];
+    // Portable values and their point mapping, for the schemes predicting from positions.
+    let mut positions: Option<(Vec<i32>, Vec<u32>)> = None;
+    for (headers, identifier) in decoders.iter().zip(identifiers) {
+        let seam_table;
+        let (table, encoding): (Option<&dyn Table>, EncodingData) = match identifier {
+            None => {
+                let encoding = EncodingData {
+                    value_to_point: (0..connectivity.num_points as u32).collect(),
+                    ..Default::default()
+                };
+                (None, encoding)
+            }
+            Some((att_data_id, decoder_type, traversal_method)) => {
+                let corner_table = connectivity.corner_table.as_ref()?;
+                let faces = &connectivity.faces;
+                match (decoder_type, traversal_method) {
+                    (MESH_VERTEX_ATTRIBUTE, TRAVERSAL_DEPTH_FIRST) => (
+                        Some(corner_table),
+                        traversal::depth_first(corner_table, faces)?,
+                    ),
+                    (MESH_VERTEX_ATTRIBUTE, TRAVERSAL_PREDICTION_DEGREE) => (
+                        Some(corner_table),
+                        traversal::max_prediction_degree(corner_table, faces)?,
+                    ),
+                    (MESH_CORNER_ATTRIBUTE, TRAVERSAL_DEPTH_FIRST) => {
+                        let data = connectivity
+                            .attribute_data
+                            .get(usize::try_from(att_data_id).ok()?)?;
+                        seam_table = data.view(corner_table);
+                        let encoding = traversal::depth_first(&seam_table, faces)?;
+                        (Some(&seam_table), encoding)
+                    }
+                    _ => return None,
+                }
+            }
+        };
+        let point_to_value = match table {
+            None => encoding.value_to_point.clone(),
+            Some(table) => {
+                let mut point_to_value = vec![0; connectivity.num_points];
+                for (f, face) in connectivity.faces.iter().enumerate() {
+                    for (p, &point) in face.iter().enumerate() {
+                        let vertex = table.vertex((3 * f + p) as u32);
+                        let value = *encoding.vertex_to_value.get(vertex as usize)?;
+                        *point_to_value.get_mut(point as usize)? = value;
+                    }
+                }
+                point_to_value
+            }
+        };
+        let num_values = encoding.value_to_point.len();
+
+        let mut portables = vec![];
+        for header in headers {
+            let portable = match header.decoder {
+                DECODER_GENERIC => {
+                    let size = data_type_size(header.data_type) * header.num_components;
+                    let bytes = buffer.bytes(size.checked_mul(num_values)?)?;
+                    Portable::Raw(bytes)
+                }
+                _ => {
+                    let mesh = table.map(|table| MeshData {
+                        table,
+                        encoding: &encoding,
+                    });
+                    let parent = positions.as_ref().map(|(values, map)| Positions {
+                        values,
+                        point_to_value: map,
+                    });
+                    let values = decode_integer_values(buffer, header, num_values, mesh, parent)?;
+                    if header.attribute_type == AttributeType::Position
+                        && header.num_components == 3
+                        && positions.is_none()
+                    {
+                        positions = Some((values.clone(), point_to_value.clone()));
+                    }
+                    Portable::Integer(values)
+                }
+            };
+            portables.push(portable);
+        }
+
+        let mut transforms = vec![];
+        for header in headers {
+            transforms.push(match header.decoder {
+                DECODER_QUANTIZATION => {
+                    let mut min = vec![];
+                    for _ in 0..header.num_components {
+                        min.push(buffer.f32()?);
+                    }
+                    let range = buffer.f32()?;
+                    let bits = buffer.u8()? as u32;
+                    if !(1..=30).contains(&bits) {
+                        return None;
+                    }
+                    PortableTransform::Quantization { min, range, bits }
+                }
+                DECODER_NORMALS => {
+                    PortableTransform::Octahedron(Octahedron::new(buffer.u8()? as u32)?)
+                }
+                _ => PortableTransform::None,
+            });
+        }
+
+        for ((header, portable), transform) in headers.iter().zip(portables).zip(transforms) {
+            let values = match (portable, transform) {
+                (Portable::Raw(bytes), _) => raw_values(bytes, header.data_type),
+                (Portable::Integer(values), PortableTransform::None) => values
+                    .iter()
+                    .map(|&v| cast_integer(v, header.data_type))
+                    .collect(),
+                (
+                    Portable::Integer(values),
+                    PortableTransform::Quantization { min, range, bits },
+                ) => {
+                    let delta = range / ((1u32 << bits) - 1) as f32;
+                    values
+                        .iter()
+                        .enumerate()
+                        .map(|(i, &v)| (v as f32 * delta + min[i % min.len()]) as f64)
+                        .collect()
+                }
+                (Portable::Integer(values), PortableTransform::Octahedron(octahedron)) => values
+                    .chunks_exact(2)
+                    .flat_map(|st| octahedron.unit_vector(st[0], st[1]))
+                    .map(|v| v as f64)
+                    .collect(),
+            };
+            attributes.push(Attribute {
+                attribute_type: header.attribute_type,
+                unique_id: header.unique_id,
+                num_components: header.num_components,
+                normalized: header.normalized,
+                values,
+                point_to_value: point_to_value.clone(),
+            });
+        }
+    }
+    Some(attributes)
+}
+
+enum Portable<'a> {
+    /// Values stored as is by the generic decoder.
+    Raw(&'a [u8]),
+    Integer(Vec<i32>),
+}
+
+enum PortableTransform {
+    None,
+    Quantization {
+        min: Vec<f32>,
+        range: f32,
+        bits: u32,
+    },
+    Octahedron(Octahedron),
+}
+
+fn decode_integer_values(
+    buffer: &mut Buffer,
+    header: &Header,
+    num_values: usize,
+    mesh: Option<MeshData>,
+    positions: Option<Positions>,
+) -> Option<Vec<i32>> {
+    // Normals are predicted and stored as two octahedral coordinates.
+    let num_components = match header.decoder {
+        DECODER_NORMALS => 2,
+        _ => header.num_components,
+    };
+    let method = buffer.i8()?;
+    let transform_type = match method {
+        PREDICTION_NONE => 0,
+        _ => buffer.i8()?,
+    };
+    let valid_transform = match (method, header.decoder) {
+        (PREDICTION_NONE, _) => true,
+        (_, DECODER_NORMALS) => {
+            transform_type == TRANSFORM_NORMAL_OCTAHEDRON
+                || transform_type == TRANSFORM_NORMAL_OCTAHEDRON_CANONICALIZED
+        }
+        _ => transform_type == TRANSFORM_WRAP,
+    };
+    if !valid_transform {
+        return None;
+    }
+    let positions = match Prediction::needs_positions(method) {
+        true => positions,
+        false => None,
+    };
+
+    let count = num_values.checked_mul(num_components)?;
+    let symbols = if buffer.u8()? != 0 {
+        decode_symbols(count, num_components, buffer)?
+    } else {
+        let num_bytes = buffer.u8()? as usize;
+        if num_bytes > 4 {
+            return None;
+        }
+        if count.checked_mul(num_bytes)? > buffer.remaining_size() {
+            return None;
+        }
+        (0..count)
+            .map(|_| buffer.uint(num_bytes))
+            .collect::<Option<Vec<_>>>()?
+    };
+    let prediction = Prediction {
+        method,
+        transform_type,
+        mesh,
+        positions,
+    };
+    prediction.decode(buffer, &symbols, num_components)
+}
+
+fn cast_integer(v: i32, data_type: u8) -> f64 {
+    match data_type {
+        DT_INT8 => v as i8 as f64,
+        DT_UINT8 => v as u8 as f64,
+        DT_INT16 => v as i16 as f64,
+        DT_UINT16 => v as u16 as f64,
+        DT_UINT32 => v as u32 as f64,
+        _ => v as f64,
+    }
+}
+
+fn raw_values(bytes: &[u8], data_type: u8) -> Vec<f64> {
+    let size = data_type_size(data_type);
+    bytes
+        .chunks_exact(size)
+        .map(|b| match data_type {
+            DT_INT8 => b[0] as i8 as f64,
+            DT_UINT8 => b[0] as f64,
+            DT_BOOL => (b[0] != 0) as u8 as f64,
+            DT_INT16 => i16::from_le_bytes([b[0], b[1]]) as f64,
+            DT_UINT16 => u16::from_le_bytes([b[0], b[1]]) as f64,
+            DT_INT32 => i32::from_le_bytes(b.try_into().unwrap()) as f64,
+            DT_UINT32 => u32::from_le_bytes(b.try_into().unwrap()) as f64,
+            DT_INT64 => i64::from_le_bytes(b.try_into().unwrap()) as f64,
+            DT_UINT64 => u64::from_le_bytes(b.try_into().unwrap()) as f64,
+            DT_FLOAT32 => f32::from_le_bytes(b.try_into().unwrap()) as f64,
+            DT_FLOAT64 => f64::from_le_bytes(b.try_into().unwrap()),
+            _ => 0.0,
+        })
+        .collect()
+}
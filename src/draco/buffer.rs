@@ -0,0 +1,120 @@
+//! Byte and bit reading over the draco bitstream.
+
+#[derive(Clone, Copy)]
+pub struct Buffer<'a> {
+    data: &'a [u8],
+    pos: usize,
+    /// Bit position relative to `pos` while bit decoding is active.
+    bit: Option<usize>,
+}
+
+impl<'a> Buffer<'a> {
+    pub fn new(data: &'a [u8]) -> Buffer<'a> {
+        Buffer {
+            data,
+            pos: 0,
+            bit: None,
+        }
+    }
+
+    pub fn remaining(&self) -> &'a [u8] {
+        &self.data[self.pos.min(self.data.len())..]
+    }
+
+    pub fn remaining_size(&self) -> usize {
+        self.data.len().saturating_sub(self.pos)
+    }
+
+    pub fn advance(&mut self, bytes: usize) -> Option<()> {
+        if bytes > self.remaining_size() {
+            return None;
+        }
+        self.pos += bytes;
+        Some(())
+    }
+
+    pub fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        let bytes = self.data.get(self.pos..self.pos.checked_add(len)?)?;
+        self.pos += len;
+        Some(bytes)
+    }
+
+    pub fn u8(&mut self) -> Option<u8> {
+        Some(self.bytes(1)?[0])
+    }
+
+    pub fn i8(&mut self) -> Option<i8> {
+        Some(self.u8()? as i8)
+    }
+
+    pub fn u16(&mut self) -> Option<u16> {
+        Some(u16::from_le_bytes(self.bytes(2)?.try_into().unwrap()))
+    }
+
+    pub fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+
+    pub fn i32(&mut self) -> Option<i32> {
+        Some(self.u32()? as i32)
+    }
+
+    pub fn f32(&mut self) -> Option<f32> {
+        Some(f32::from_bits(self.u32()?))
+    }
+
+    /// Little endian integer of `len` bytes, at most 4.
+    pub fn uint(&mut self, len: usize) -> Option<u32> {
+        let bytes = self.bytes(len)?;
+        Some(
+            bytes
+                .iter()
+                .rev()
+                .fold(0u32, |acc, &b| acc.wrapping_shl(8) | b as u32),
+        )
+    }
+
+    /// LEB128 varint.
+    pub fn varint(&mut self) -> Option<u32> {
+        let value = self.varint64()?;
+        u32::try_from(value).ok()
+    }
+
+    pub fn varint64(&mut self) -> Option<u64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.u8()?;
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+        None
+    }
+
+    /// Switch to bit decoding, optionally reading the size of the bit data first.
+    pub fn start_bit_decoding(&mut self, decode_size: bool) -> Option<u64> {
+        let size = if decode_size { self.varint64()? } else { 0 };
+        self.bit = Some(0);
+        Some(size)
+    }
+
+    /// Least significant bit first, bits past the end of the data read as zero.
+    pub fn bits(&mut self, count: u32) -> Option<u32> {
+        let bit = self.bit.as_mut()?;
+        let mut value = 0u32;
+        for i in 0..count {
+            let byte = self.data.get(self.pos + *bit / 8).copied().unwrap_or(0);
+            value |= (((byte >> (*bit % 8)) & 1) as u32) << i;
+            *bit += 1;
+        }
+        Some(value)
+    }
+
+    /// Leave bit decoding, skipping all the touched bytes.
+    pub fn end_bit_decoding(&mut self) {
+        if let Some(bit) = self.bit.take() {
+            self.pos += bit.div_ceil(8);
+        }
+    }
+}
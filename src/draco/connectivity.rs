@@ -0,0 +1,814 @@
+//! Corner tables and the sequential and edgebreaker connectivity decoders.
+
+use std::collections::HashMap;
+
+use super::buffer::Buffer;
+use super::rans::{decode_symbols, BitDecoder};
+
+pub const INVALID: u32 = u32::MAX;
+
+const TOPOLOGY_C: u32 = 0;
+const TOPOLOGY_S: u32 = 1;
+const TOPOLOGY_L: u32 = 3;
+const TOPOLOGY_R: u32 = 5;
+const TOPOLOGY_E: u32 = 7;
+
+const TRAVERSAL_STANDARD: u8 = 0;
+const TRAVERSAL_VALENCE: u8 = 2;
+
+/// Corner table navigation, corners `3 * f..3 * f + 3` belong to the face `f`.
+/// All functions map INVALID to INVALID.
+pub trait Table {
+    fn opposite(&self, corner: u32) -> u32;
+    fn vertex(&self, corner: u32) -> u32;
+    fn left_most_corner(&self, vertex: u32) -> u32;
+    fn num_vertices(&self) -> usize;
+    fn num_faces(&self) -> usize;
+
+    fn next(&self, corner: u32) -> u32 {
+        match corner {
+            INVALID => INVALID,
+            c if c % 3 == 2 => c - 2,
+            c => c + 1,
+        }
+    }
+
+    fn previous(&self, corner: u32) -> u32 {
+        match corner {
+            INVALID => INVALID,
+            c if c % 3 == 0 => c + 2,
+            c => c - 1,
+        }
+    }
+
+    fn swing_left(&self, corner: u32) -> u32 {
+        self.next(self.opposite(self.next(corner)))
+    }
+
+    fn swing_right(&self, corner: u32) -> u32 {
+        self.previous(self.opposite(self.previous(corner)))
+    }
+
+    fn left_corner(&self, corner: u32) -> u32 {
+        self.opposite(self.previous(corner))
+    }
+
+    fn right_corner(&self, corner: u32) -> u32 {
+        self.opposite(self.next(corner))
+    }
+
+    fn is_on_boundary(&self, vertex: u32) -> bool {
+        self.swing_left(self.left_most_corner(vertex)) == INVALID
+    }
+}
+
+/// Corners around the vertex of `corner`, swinging left first and then right
+/// from `corner` when a boundary is reached.
+pub fn vertex_corners<T: Table + ?Sized>(table: &T, corner: u32) -> impl Iterator<Item = u32> + '_ {
+    let start = corner;
+    let mut current = corner;
+    let mut left = true;
+    std::iter::from_fn(move || {
+        if current == INVALID {
+            return None;
+        }
+        let res = current;
+        if left {
+            current = table.swing_left(current);
+            if current == INVALID {
+                current = table.swing_right(start);
+                left = false;
+            } else if current == start {
+                current = INVALID;
+            }
+        } else {
+            current = table.swing_right(current);
+        }
+        Some(res)
+    })
+}
+
+#[derive(Default)]
+pub struct CornerTable {
+    pub corner_to_vertex: Vec<u32>,
+    pub opposite: Vec<u32>,
+    pub vertex_corners: Vec<u32>,
+}
+
+impl CornerTable {
+    fn new(num_faces: usize) -> CornerTable {
+        CornerTable {
+            corner_to_vertex: vec![INVALID; num_faces * 3],
+            opposite: vec![INVALID; num_faces * 3],
+            vertex_corners: vec![],
+        }
+    }
+
+    fn add_vertex(&mut self) -> u32 {
+        self.vertex_corners.push(INVALID);
+        self.vertex_corners.len() as u32 - 1
+    }
+
+    fn set_opposite(&mut self, a: u32, b: u32) {
+        self.opposite[a as usize] = b;
+        self.opposite[b as usize] = a;
+    }
+}
+
+impl Table for CornerTable {
+    fn opposite(&self, corner: u32) -> u32 {
+        self.opposite
+            .get(corner as usize)
+            .copied()
+            .unwrap_or(INVALID)
+    }
+
+    fn vertex(&self, corner: u32) -> u32 {
+        self.corner_to_vertex
+            .get(corner as usize)
+            .copied()
+            .unwrap_or(INVALID)
+    }
+
+    fn left_most_corner(&self, vertex: u32) -> u32 {
+        self.vertex_corners
+            .get(vertex as usize)
+            .copied()
+            .unwrap_or(INVALID)
+    }
+
+    fn num_vertices(&self) -> usize {
+        self.vertex_corners.len()
+    }
+
+    fn num_faces(&self) -> usize {
+        self.corner_to_vertex.len() / 3
+    }
+}
+
+/// Attribute connectivity on top of the position corner table, edges marked as seams
+/// have no opposite corners and split the vertices they touch.
+pub struct AttributeConnectivity {
+    is_edge_on_seam: Vec<bool>,
+    is_vertex_on_seam: Vec<bool>,
+    corner_to_vertex: Vec<u32>,
+    vertex_to_left_most_corner: Vec<u32>,
+}
+
+impl AttributeConnectivity {
+    fn new(table: &CornerTable, seam_corners: &[u32]) -> Option<AttributeConnectivity> {
+        let mut conn = AttributeConnectivity {
+            is_edge_on_seam: vec![false; table.opposite.len()],
+            is_vertex_on_seam: vec![false; table.num_vertices()],
+            corner_to_vertex: vec![INVALID; table.opposite.len()],
+            vertex_to_left_most_corner: vec![],
+        };
+        for &c in seam_corners {
+            for c in [c, table.opposite(c)] {
+                if c == INVALID {
+                    continue;
+                }
+                conn.is_edge_on_seam[c as usize] = true;
+                conn.is_vertex_on_seam[table.vertex(table.next(c)) as usize] = true;
+                conn.is_vertex_on_seam[table.vertex(table.previous(c)) as usize] = true;
+            }
+        }
+
+        let mut num_vertices = 0;
+        for v in 0..table.num_vertices() {
+            let c = table.left_most_corner(v as u32);
+            if c == INVALID {
+                continue;
+            }
+            let mut vertex = num_vertices;
+            num_vertices += 1;
+            let mut first = c;
+            if conn.is_vertex_on_seam[v] {
+                // The first corner right after a seam edge, going counter clockwise.
+                let view = conn.view(table);
+                let mut act = view.swing_left(first);
+                while act != INVALID {
+                    first = act;
+                    act = view.swing_left(act);
+                    if act == c {
+                        return None;
+                    }
+                }
+            }
+            conn.corner_to_vertex[first as usize] = vertex;
+            conn.vertex_to_left_most_corner.push(first);
+            let mut act = table.swing_right(first);
+            while act != INVALID && act != first {
+                if conn.is_edge_on_seam[table.next(act) as usize] {
+                    vertex = num_vertices;
+                    num_vertices += 1;
+                    conn.vertex_to_left_most_corner.push(act);
+                }
+                conn.corner_to_vertex[act as usize] = vertex;
+                act = table.swing_right(act);
+            }
+        }
+        Some(conn)
+    }
+
+    pub fn view<'a>(&'a self, table: &'a CornerTable) -> SeamTable<'a> {
+        SeamTable { table, conn: self }
+    }
+
+    fn is_corner_on_seam(&self, table: &CornerTable, corner: u32) -> bool {
+        self.is_vertex_on_seam[table.vertex(corner) as usize]
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct SeamTable<'a> {
+    table: &'a CornerTable,
+    conn: &'a AttributeConnectivity,
+}
+
+impl Table for SeamTable<'_> {
+    fn opposite(&self, corner: u32) -> u32 {
+        if corner == INVALID || self.conn.is_edge_on_seam[corner as usize] {
+            return INVALID;
+        }
+        self.table.opposite(corner)
+    }
+
+    fn vertex(&self, corner: u32) -> u32 {
+        self.conn
+            .corner_to_vertex
+            .get(corner as usize)
+            .copied()
+            .unwrap_or(INVALID)
+    }
+
+    fn left_most_corner(&self, vertex: u32) -> u32 {
+        self.conn
+            .vertex_to_left_most_corner
+            .get(vertex as usize)
+            .copied()
+            .unwrap_or(INVALID)
+    }
+
+    fn num_vertices(&self) -> usize {
+        self.conn.vertex_to_left_most_corner.len()
+    }
+
+    fn num_faces(&self) -> usize {
+        self.table.num_faces()
+    }
+}
+
+/// Decoded connectivity, `faces` index points, each point is a unique combination
+/// of the attribute values.
+pub struct Connectivity {
+    pub faces: Vec<[u32; 3]>,
+    pub num_points: usize,
+    /// Position corner table, None for the sequential encoding.
+    pub corner_table: Option<CornerTable>,
+    /// Attribute connectivity referenced by `att_data_id` of the attribute decoders.
+    pub attribute_data: Vec<AttributeConnectivity>,
+}
+
+pub fn decode_sequential(buffer: &mut Buffer) -> Option<Connectivity> {
+    let num_faces = buffer.varint()? as usize;
+    let num_points = buffer.varint()? as usize;
+    if num_faces > u32::MAX as usize / 3 {
+        return None;
+    }
+    let mut faces = Vec::with_capacity(num_faces.min(buffer.remaining_size()));
+    if buffer.u8()? == 0 {
+        let symbols = decode_symbols(num_faces * 3, 1, buffer)?;
+        let mut last = 0i64;
+        for face in symbols.chunks_exact(3) {
+            let mut res = [0; 3];
+            for (res, &symbol) in res.iter_mut().zip(face) {
+                let diff = (symbol >> 1) as i64;
+                last += if symbol & 1 == 1 { -diff } else { diff };
+                *res = u32::try_from(last).ok()?;
+            }
+            faces.push(res);
+        }
+    } else {
+        for _ in 0..num_faces {
+            let mut face = [0; 3];
+            for index in &mut face {
+                *index = match num_points {
+                    0..=0xff => buffer.u8()? as u32,
+                    0x100..=0xffff => buffer.u16()? as u32,
+                    0x10000..=0x1f_ffff => buffer.varint()?,
+                    _ => buffer.u32()?,
+                };
+            }
+            faces.push(face);
+        }
+    }
+    if faces.iter().flatten().any(|&i| i as usize >= num_points) {
+        return None;
+    }
+    Some(Connectivity {
+        faces,
+        num_points,
+        corner_table: None,
+        attribute_data: vec![],
+    })
+}
+
+struct TopologySplit {
+    source_symbol: u32,
+    split_symbol: u32,
+    /// 1 for the right face edge, 0 for the left one.
+    source_edge: u32,
+}
+
+enum Traversal<'a> {
+    Standard {
+        symbols: Buffer<'a>,
+    },
+    Valence {
+        valences: Vec<i32>,
+        context: Option<usize>,
+        context_symbols: Vec<Vec<u32>>,
+        last_symbol: u32,
+    },
+}
+
+impl Traversal<'_> {
+    fn decode_symbol(&mut self) -> Option<u32> {
+        match self {
+            Traversal::Standard { symbols } => {
+                let symbol = symbols.bits(1)?;
+                if symbol == TOPOLOGY_C {
+                    return Some(symbol);
+                }
+                Some(symbol | symbols.bits(2)? << 1)
+            }
+            Traversal::Valence {
+                context,
+                context_symbols,
+                last_symbol,
+                ..
+            } => {
+                *last_symbol = match context {
+                    Some(context) => {
+                        let symbol = context_symbols[*context].pop()?;
+                        *[TOPOLOGY_C, TOPOLOGY_S, TOPOLOGY_L, TOPOLOGY_R, TOPOLOGY_E]
+                            .get(symbol as usize)?
+                    }
+                    None => TOPOLOGY_E,
+                };
+                Some(*last_symbol)
+            }
+        }
+    }
+
+    fn new_active_corner(&mut self, table: &CornerTable, corner: u32) -> Option<()> {
+        let Traversal::Valence {
+            valences,
+            context,
+            last_symbol,
+            ..
+        } = self
+        else {
+            return Some(());
+        };
+        let next = table.vertex(table.next(corner)) as usize;
+        let prev = table.vertex(table.previous(corner)) as usize;
+        let tip = table.vertex(corner) as usize;
+        let (tip_add, next_add, prev_add) = match *last_symbol {
+            TOPOLOGY_C | TOPOLOGY_S => (0, 1, 1),
+            TOPOLOGY_R => (1, 1, 2),
+            TOPOLOGY_L => (1, 2, 1),
+            TOPOLOGY_E => (2, 2, 2),
+            _ => (0, 0, 0),
+        };
+        for (v, add) in [(tip, tip_add), (next, next_add), (prev, prev_add)] {
+            if add != 0 {
+                *valences.get_mut(v)? += add;
+            }
+        }
+        let valence = *valences.get(next)?;
+        *context = Some((valence.clamp(2, 7) - 2) as usize);
+        Some(())
+    }
+
+    fn merge_vertices(&mut self, dest: u32, source: u32) -> Option<()> {
+        if let Traversal::Valence { valences, .. } = self {
+            let source = *valences.get(source as usize)?;
+            *valences.get_mut(dest as usize)? += source;
+        }
+        Some(())
+    }
+}
+
+pub fn decode_edgebreaker(buffer: &mut Buffer) -> Option<Connectivity> {
+    let traversal_type = buffer.u8()?;
+    if traversal_type != TRAVERSAL_STANDARD && traversal_type != TRAVERSAL_VALENCE {
+        return None;
+    }
+    let num_encoded_vertices = buffer.varint()? as usize;
+    let num_faces = buffer.varint()? as usize;
+    if num_faces > (INVALID / 3) as usize || num_encoded_vertices > num_faces * 3 {
+        return None;
+    }
+    let num_attribute_data = buffer.u8()? as usize;
+    let num_symbols = buffer.varint()? as usize;
+    if num_faces < num_symbols || num_faces > num_symbols + num_symbols / 3 {
+        return None;
+    }
+    let num_split_symbols = buffer.varint()? as usize;
+    if num_split_symbols > num_symbols {
+        return None;
+    }
+    let max_num_vertices = num_encoded_vertices + num_split_symbols;
+
+    let connectivity_size = buffer.varint()? as usize;
+    if connectivity_size == 0 || connectivity_size > buffer.remaining_size() {
+        return None;
+    }
+    let mut events = Buffer::new(&buffer.remaining()[connectivity_size..]);
+    let mut splits = decode_topology_splits(&mut events, num_faces)?;
+    let split_bytes = buffer.remaining_size() - connectivity_size - events.remaining_size();
+
+    // Traversal data: symbols (standard only), start faces and attribute seams.
+    let mut traversal_buffer = *buffer;
+    let mut traversal = if traversal_type == TRAVERSAL_STANDARD {
+        let size = traversal_buffer.varint64()?;
+        let mut symbols = traversal_buffer;
+        symbols.start_bit_decoding(false)?;
+        traversal_buffer.advance(usize::try_from(size).ok()?)?;
+        Traversal::Standard { symbols }
+    } else {
+        Traversal::Valence {
+            valences: vec![],
+            context: None,
+            context_symbols: vec![],
+            last_symbol: TOPOLOGY_E,
+        }
+    };
+    let mut start_faces = BitDecoder::new(&mut traversal_buffer)?;
+    let mut seam_decoders = (0..num_attribute_data)
+        .map(|_| BitDecoder::new(&mut traversal_buffer))
+        .collect::<Option<Vec<_>>>()?;
+    if let Traversal::Valence {
+        valences,
+        context_symbols,
+        ..
+    } = &mut traversal
+    {
+        let num_split_symbols = traversal_buffer.varint()? as usize;
+        if num_split_symbols >= max_num_vertices {
+            return None;
+        }
+        // Only the valence 2..7 mode exists.
+        if traversal_buffer.u8()? != 0 {
+            return None;
+        }
+        *valences = vec![0; max_num_vertices + num_split_symbols];
+        for _ in 2..=7 {
+            let num = traversal_buffer.varint()? as usize;
+            if num > num_faces {
+                return None;
+            }
+            context_symbols.push(decode_symbols(num, 1, &mut traversal_buffer)?);
+        }
+    }
+
+    let mut table = CornerTable::new(num_faces);
+    let mut is_vert_hole = vec![true; max_num_vertices];
+    let remove_invalid_vertices = num_attribute_data == 0;
+    let mut invalid_vertices = vec![];
+    let mut active_corners: Vec<u32> = vec![];
+    let mut split_active_corners = HashMap::new();
+    let mut num_decoded_faces = 0;
+
+    for symbol_id in 0..num_symbols {
+        let corner = 3 * num_decoded_faces as u32;
+        num_decoded_faces += 1;
+        let mut check_topology_split = false;
+        match traversal.decode_symbol()? {
+            TOPOLOGY_C => {
+                let corner_a = *active_corners.last()?;
+                let vertex_x = table.vertex(table.next(corner_a));
+                let corner_b = table.next(table.left_most_corner(vertex_x));
+                if corner_a == corner_b
+                    || table.opposite(corner_a) != INVALID
+                    || table.opposite(corner_b) != INVALID
+                {
+                    return None;
+                }
+                table.set_opposite(corner_a, corner + 1);
+                table.set_opposite(corner_b, corner + 2);
+                let vert_a_prev = table.vertex(table.previous(corner_a));
+                let vert_b_next = table.vertex(table.next(corner_b));
+                if vertex_x == vert_a_prev || vertex_x == vert_b_next {
+                    return None;
+                }
+                table.corner_to_vertex[corner as usize] = vertex_x;
+                table.corner_to_vertex[corner as usize + 1] = vert_b_next;
+                table.corner_to_vertex[corner as usize + 2] = vert_a_prev;
+                table.vertex_corners[vert_a_prev as usize] = corner + 2;
+                is_vert_hole[vertex_x as usize] = false;
+                *active_corners.last_mut()? = corner;
+            }
+            symbol @ (TOPOLOGY_R | TOPOLOGY_L) => {
+                let corner_a = *active_corners.last()?;
+                if table.opposite(corner_a) != INVALID {
+                    return None;
+                }
+                let (opp_corner, corner_l, corner_r) = if symbol == TOPOLOGY_R {
+                    (corner + 2, corner + 1, corner)
+                } else {
+                    (corner + 1, corner, corner + 2)
+                };
+                table.set_opposite(opp_corner, corner_a);
+                let new_vertex = table.add_vertex();
+                if table.num_vertices() > max_num_vertices {
+                    return None;
+                }
+                table.corner_to_vertex[opp_corner as usize] = new_vertex;
+                table.vertex_corners[new_vertex as usize] = opp_corner;
+                let vertex_r = table.vertex(table.previous(corner_a));
+                table.corner_to_vertex[corner_r as usize] = vertex_r;
+                *table.vertex_corners.get_mut(vertex_r as usize)? = corner_r;
+                table.corner_to_vertex[corner_l as usize] = table.vertex(table.next(corner_a));
+                *active_corners.last_mut()? = corner;
+                check_topology_split = true;
+            }
+            TOPOLOGY_S => {
+                let corner_b = active_corners.pop()?;
+                if let Some(&corner) = split_active_corners.get(&symbol_id) {
+                    active_corners.push(corner);
+                }
+                let corner_a = *active_corners.last()?;
+                if corner_a == corner_b
+                    || table.opposite(corner_a) != INVALID
+                    || table.opposite(corner_b) != INVALID
+                {
+                    return None;
+                }
+                table.set_opposite(corner_a, corner + 2);
+                table.set_opposite(corner_b, corner + 1);
+                let vertex_p = table.vertex(table.previous(corner_a));
+                table.corner_to_vertex[corner as usize] = vertex_p;
+                table.corner_to_vertex[corner as usize + 1] = table.vertex(table.next(corner_a));
+                let vert_b_prev = table.vertex(table.previous(corner_b));
+                table.corner_to_vertex[corner as usize + 2] = vert_b_prev;
+                *table.vertex_corners.get_mut(vert_b_prev as usize)? = corner + 2;
+                let mut corner_n = table.next(corner_b);
+                let vertex_n = table.vertex(corner_n);
+                traversal.merge_vertices(vertex_p, vertex_n)?;
+                *table.vertex_corners.get_mut(vertex_p as usize)? =
+                    table.left_most_corner(vertex_n);
+                let first_corner = corner_n;
+                while corner_n != INVALID {
+                    table.corner_to_vertex[corner_n as usize] = vertex_p;
+                    corner_n = table.swing_left(corner_n);
+                    if corner_n == first_corner {
+                        return None;
+                    }
+                }
+                *table.vertex_corners.get_mut(vertex_n as usize)? = INVALID;
+                if remove_invalid_vertices {
+                    invalid_vertices.push(vertex_n);
+                }
+                *active_corners.last_mut()? = corner;
+            }
+            TOPOLOGY_E => {
+                for i in 0..3 {
+                    let vertex = table.add_vertex();
+                    table.corner_to_vertex[(corner + i) as usize] = vertex;
+                    table.vertex_corners[vertex as usize] = corner + i;
+                }
+                if table.num_vertices() > max_num_vertices {
+                    return None;
+                }
+                active_corners.push(corner);
+                check_topology_split = true;
+            }
+            _ => return None,
+        }
+        traversal.new_active_corner(&table, *active_corners.last()?)?;
+
+        if check_topology_split {
+            let encoder_symbol_id = (num_symbols - symbol_id - 1) as u32;
+            while let Some(split) = splits.last() {
+                if split.source_symbol > encoder_symbol_id {
+                    return None;
+                }
+                if split.source_symbol != encoder_symbol_id {
+                    break;
+                }
+                let act_top_corner = *active_corners.last()?;
+                let new_active_corner = if split.source_edge == 1 {
+                    table.next(act_top_corner)
+                } else {
+                    table.previous(act_top_corner)
+                };
+                let decoder_split_symbol = num_symbols - split.split_symbol as usize - 1;
+                split_active_corners.insert(decoder_split_symbol, new_active_corner);
+                splits.pop();
+            }
+        }
+    }
+    if table.num_vertices() > max_num_vertices {
+        return None;
+    }
+
+    // Start faces, either connecting the remaining open edges or left open.
+    while let Some(corner) = active_corners.pop() {
+        if !start_faces.decode() {
+            continue;
+        }
+        if num_decoded_faces >= num_faces {
+            return None;
+        }
+        let vert_n = table.vertex(table.next(corner));
+        let corner_b = table.next(table.left_most_corner(vert_n));
+        let vert_x = table.vertex(table.next(corner_b));
+        let corner_c = table.next(table.left_most_corner(vert_x));
+        if corner == corner_b
+            || corner == corner_c
+            || corner_b == corner_c
+            || table.opposite(corner) != INVALID
+            || table.opposite(corner_b) != INVALID
+            || table.opposite(corner_c) != INVALID
+        {
+            return None;
+        }
+        let vert_p = table.vertex(table.next(corner_c));
+        let new_corner = 3 * num_decoded_faces as u32;
+        num_decoded_faces += 1;
+        table.set_opposite(new_corner, corner);
+        table.set_opposite(new_corner + 1, corner_b);
+        table.set_opposite(new_corner + 2, corner_c);
+        table.corner_to_vertex[new_corner as usize] = vert_x;
+        table.corner_to_vertex[new_corner as usize + 1] = vert_p;
+        table.corner_to_vertex[new_corner as usize + 2] = vert_n;
+        for v in [vert_x, vert_p, vert_n] {
+            *is_vert_hole.get_mut(v as usize)? = false;
+        }
+    }
+    if num_decoded_faces != num_faces {
+        return None;
+    }
+
+    // Move the vertices from the end of the table into the ones merged by S symbols.
+    let mut num_vertices = table.num_vertices();
+    for invalid_vert in invalid_vertices {
+        let mut src_vert = num_vertices as u32 - 1;
+        while table.left_most_corner(src_vert) == INVALID {
+            num_vertices -= 1;
+            src_vert = num_vertices as u32 - 1;
+        }
+        if src_vert < invalid_vert {
+            continue;
+        }
+        let corners = vertex_corners(&table, table.left_most_corner(src_vert)).collect::<Vec<_>>();
+        for c in corners {
+            if table.vertex(c) != src_vert {
+                return None;
+            }
+            table.corner_to_vertex[c as usize] = invalid_vert;
+        }
+        table.vertex_corners[invalid_vert as usize] = table.left_most_corner(src_vert);
+        table.vertex_corners[src_vert as usize] = INVALID;
+        is_vert_hole[invalid_vert as usize] = is_vert_hole[src_vert as usize];
+        is_vert_hole[src_vert as usize] = false;
+        num_vertices -= 1;
+    }
+
+    // Attribute seams, boundary edges are always seams.
+    let mut seam_corners = vec![vec![]; num_attribute_data];
+    for face in 0..num_faces as u32 {
+        let corner = 3 * face;
+        for c in [corner, table.next(corner), table.previous(corner)] {
+            let opp = table.opposite(c);
+            if opp == INVALID {
+                seam_corners.iter_mut().for_each(|s| s.push(c));
+                continue;
+            }
+            if opp / 3 < face {
+                continue;
+            }
+            for (seams, decoder) in seam_corners.iter_mut().zip(&mut seam_decoders) {
+                if decoder.decode() {
+                    seams.push(c);
+                }
+            }
+        }
+    }
+    let attribute_data = seam_corners
+        .iter()
+        .map(|seams| AttributeConnectivity::new(&table, seams))
+        .collect::<Option<Vec<_>>>()?;
+
+    // Attribute data follows the traversal and the topology splits.
+    *buffer = traversal_buffer;
+    buffer.advance(split_bytes)?;
+
+    let (faces, num_points) =
+        assign_points_to_corners(&table, &attribute_data, &is_vert_hole, num_vertices)?;
+    Some(Connectivity {
+        faces,
+        num_points,
+        corner_table: Some(table),
+        attribute_data,
+    })
+}
+
+fn decode_topology_splits(buffer: &mut Buffer, num_faces: usize) -> Option<Vec<TopologySplit>> {
+    let num_splits = buffer.varint()? as usize;
+    if num_splits > num_faces {
+        return None;
+    }
+    let mut splits = Vec::with_capacity(num_splits);
+    let mut last_source_symbol = 0u32;
+    for _ in 0..num_splits {
+        let source_symbol = last_source_symbol.checked_add(buffer.varint()?)?;
+        let split_symbol = source_symbol.checked_sub(buffer.varint()?)?;
+        splits.push(TopologySplit {
+            source_symbol,
+            split_symbol,
+            source_edge: 0,
+        });
+        last_source_symbol = source_symbol;
+    }
+    buffer.start_bit_decoding(false)?;
+    for split in &mut splits {
+        split.source_edge = buffer.bits(1)?;
+    }
+    buffer.end_bit_decoding();
+    // No hole events since bitstream 2.1.
+    Some(splits)
+}
+
+/// Faces over points, a new point is created whenever any attribute changes its value
+/// around a vertex.
+fn assign_points_to_corners(
+    table: &CornerTable,
+    attribute_data: &[AttributeConnectivity],
+    is_vert_hole: &[bool],
+    num_vertices: usize,
+) -> Option<(Vec<[u32; 3]>, usize)> {
+    let face = |f: usize, map: &dyn Fn(u32) -> u32| [0, 1, 2].map(|i| map((3 * f + i) as u32));
+    if attribute_data.is_empty() {
+        let faces = (0..table.num_faces())
+            .map(|f| face(f, &|c| table.vertex(c)))
+            .collect();
+        return Some((faces, num_vertices));
+    }
+
+    let views = attribute_data
+        .iter()
+        .map(|conn| conn.view(table))
+        .collect::<Vec<_>>();
+    let mut num_points = 0;
+    let mut corner_to_point = vec![INVALID; table.opposite.len()];
+    for v in 0..table.num_vertices() {
+        let c = table.left_most_corner(v as u32);
+        if c == INVALID {
+            continue;
+        }
+        let mut first = c;
+        if is_vert_hole.get(v) != Some(&true) {
+            // Start from any seam so a point is not split in two.
+            'attributes: for (conn, view) in attribute_data.iter().zip(&views) {
+                if !conn.is_corner_on_seam(table, c) {
+                    continue;
+                }
+                let vertex = view.vertex(c);
+                let mut act = table.swing_right(c);
+                while act != c {
+                    if act == INVALID {
+                        return None;
+                    }
+                    if view.vertex(act) != vertex {
+                        first = act;
+                        break 'attributes;
+                    }
+                    act = table.swing_right(act);
+                }
+            }
+        }
+
+        corner_to_point[first as usize] = num_points;
+        num_points += 1;
+        let mut prev = first;
+        let mut c = table.swing_right(first);
+        while c != INVALID && c != first {
+            if views.iter().any(|view| view.vertex(c) != view.vertex(prev)) {
+                corner_to_point[c as usize] = num_points;
+                num_points += 1;
+            } else {
+                corner_to_point[c as usize] = corner_to_point[prev as usize];
+            }
+            prev = c;
+            c = table.swing_right(c);
+        }
+    }
+    let faces = (0..table.num_faces())
+        .map(|f| face(f, &|c| corner_to_point[c as usize]))
+        .collect();
+    Some((faces, num_points as usize))
+}
@@ -0,0 +1,723 @@
+//! Prediction schemes and the transforms applying corrections to the predicted values.
+
+use super::buffer::Buffer;
+use super::connectivity::{vertex_corners, Table, INVALID};
+use super::rans::BitDecoder;
+use super::traversal::EncodingData;
+
+pub const PREDICTION_NONE: i8 = -2;
+const PREDICTION_DIFFERENCE: i8 = 0;
+const MESH_PREDICTION_PARALLELOGRAM: i8 = 1;
+const MESH_PREDICTION_CONSTRAINED_MULTI_PARALLELOGRAM: i8 = 4;
+const MESH_PREDICTION_TEX_COORDS_PORTABLE: i8 = 5;
+const MESH_PREDICTION_GEOMETRIC_NORMAL: i8 = 6;
+
+pub const TRANSFORM_WRAP: i8 = 1;
+pub const TRANSFORM_NORMAL_OCTAHEDRON: i8 = 2;
+pub const TRANSFORM_NORMAL_OCTAHEDRON_CANONICALIZED: i8 = 3;
+
+const MAX_PARALLELOGRAMS: usize = 4;
+
+/// Octahedral normal encoding helpers.
+#[derive(Clone, Copy)]
+pub struct Octahedron {
+    max_quantized_value: i32,
+    max_value: i32,
+    center_value: i32,
+}
+
+impl Octahedron {
+    pub fn new(quantization_bits: u32) -> Option<Octahedron> {
+        if !(2..=30).contains(&quantization_bits) {
+            return None;
+        }
+        let max_quantized_value = (1 << quantization_bits) - 1;
+        let max_value = max_quantized_value - 1;
+        Some(Octahedron {
+            max_quantized_value,
+            max_value,
+            center_value: max_value / 2,
+        })
+    }
+
+    fn from_max_quantized_value(max_quantized_value: i32) -> Option<Octahedron> {
+        if max_quantized_value <= 0 || max_quantized_value % 2 == 0 {
+            return None;
+        }
+        Octahedron::new(32 - max_quantized_value.leading_zeros())
+    }
+
+    /// Unit vector of the quantized octahedral coordinates.
+    pub fn unit_vector(&self, s: i32, t: i32) -> [f32; 3] {
+        let scale = 2.0 / self.max_value as f32;
+        let mut y = s as f32 * scale - 1.0;
+        let mut z = t as f32 * scale - 1.0;
+        let x = 1.0 - y.abs() - z.abs();
+        // Points outside of the central diamond are mirrored along its nearest edge.
+        let x_offset = (-x).max(0.0);
+        y += if y < 0.0 { x_offset } else { -x_offset };
+        z += if z < 0.0 { x_offset } else { -x_offset };
+        let norm_squared = x * x + y * y + z * z;
+        if norm_squared < 1e-6 {
+            return [0.0; 3];
+        }
+        let d = 1.0 / norm_squared.sqrt();
+        [x * d, y * d, z * d]
+    }
+
+    fn is_in_diamond(&self, s: i32, t: i32) -> bool {
+        (s as i64).abs() + (t as i64).abs() <= self.center_value as i64
+    }
+
+    fn invert_diamond(&self, [s, t]: [i32; 2]) -> [i32; 2] {
+        let (sign_s, sign_t) = if s >= 0 && t >= 0 {
+            (1, 1)
+        } else if s <= 0 && t <= 0 {
+            (-1, -1)
+        } else {
+            (if s > 0 { 1 } else { -1 }, if t > 0 { 1 } else { -1 })
+        };
+        let corner_s = (sign_s * self.center_value) as u32;
+        let corner_t = (sign_t * self.center_value) as u32;
+        let us = (s as u32).wrapping_mul(2).wrapping_sub(corner_s);
+        let ut = (t as u32).wrapping_mul(2).wrapping_sub(corner_t);
+        let (us, ut) = if sign_s * sign_t >= 0 {
+            (ut.wrapping_neg(), us.wrapping_neg())
+        } else {
+            (ut, us)
+        };
+        [
+            (us.wrapping_add(corner_s) as i32) / 2,
+            (ut.wrapping_add(corner_t) as i32) / 2,
+        ]
+    }
+
+    fn mod_max(&self, x: i32) -> i32 {
+        if x > self.center_value {
+            x - self.max_quantized_value
+        } else if x < -self.center_value {
+            x + self.max_quantized_value
+        } else {
+            x
+        }
+    }
+
+    /// Scale the vector so its components' absolute values sum up to the center value.
+    fn canonicalize_integer_vector(&self, v: &mut [i32; 3]) {
+        let abs_sum = v.iter().map(|&x| (x as i64).abs()).sum::<i64>();
+        if abs_sum == 0 {
+            v[0] = self.center_value;
+            return;
+        }
+        let center = self.center_value as i64;
+        v[0] = (v[0] as i64 * center / abs_sum) as i32;
+        v[1] = (v[1] as i64 * center / abs_sum) as i32;
+        let z = self.center_value - v[0].abs() - v[1].abs();
+        v[2] = if v[2] >= 0 { z } else { -z };
+    }
+
+    fn integer_vector_to_octahedral_coords(&self, v: [i32; 3]) -> [i32; 2] {
+        let (mut s, mut t) = if v[0] >= 0 {
+            (v[1] + self.center_value, v[2] + self.center_value)
+        } else {
+            (
+                if v[1] < 0 {
+                    v[2].abs()
+                } else {
+                    self.max_value - v[2].abs()
+                },
+                if v[2] < 0 {
+                    v[1].abs()
+                } else {
+                    self.max_value - v[1].abs()
+                },
+            )
+        };
+        let (max, center) = (self.max_value, self.center_value);
+        if (s == 0 && (t == 0 || t == max)) || (s == max && t == 0) {
+            s = max;
+            t = max;
+        } else if s == 0 && t > center {
+            t = center - (t - center);
+        } else if s == max && t < center {
+            t = center + (center - t);
+        } else if t == max && s < center {
+            s = center + (center - s);
+        } else if t == 0 && s > center {
+            s = center - (s - center);
+        }
+        [s, t]
+    }
+}
+
+enum Transform {
+    Wrap { min: i32, max: i32, max_dif: i32 },
+    Octahedron(Octahedron),
+    OctahedronCanonicalized(Octahedron),
+}
+
+impl Transform {
+    fn decode(transform_type: i8, buffer: &mut Buffer) -> Option<Transform> {
+        Some(match transform_type {
+            TRANSFORM_WRAP => {
+                let min = buffer.i32()?;
+                let max = buffer.i32()?;
+                let dif = max as i64 - min as i64;
+                if dif < 0 || dif >= i32::MAX as i64 {
+                    return None;
+                }
+                Transform::Wrap {
+                    min,
+                    max,
+                    max_dif: 1 + dif as i32,
+                }
+            }
+            TRANSFORM_NORMAL_OCTAHEDRON => {
+                Transform::Octahedron(Octahedron::from_max_quantized_value(buffer.i32()?)?)
+            }
+            TRANSFORM_NORMAL_OCTAHEDRON_CANONICALIZED => {
+                let max_quantized_value = buffer.i32()?;
+                let _center_value = buffer.i32()?;
+                Transform::OctahedronCanonicalized(Octahedron::from_max_quantized_value(
+                    max_quantized_value,
+                )?)
+            }
+            _ => return None,
+        })
+    }
+
+    fn octahedron(&self) -> Option<Octahedron> {
+        match self {
+            Transform::Octahedron(o) | Transform::OctahedronCanonicalized(o) => Some(*o),
+            _ => None,
+        }
+    }
+
+    fn original_value(&self, pred: &[i32], corr: &[i32], out: &mut [i32]) {
+        match *self {
+            Transform::Wrap { min, max, max_dif } => {
+                for i in 0..out.len() {
+                    let value = pred[i].clamp(min, max).wrapping_add(corr[i]);
+                    out[i] = if value > max {
+                        value.wrapping_sub(max_dif)
+                    } else if value < min {
+                        value.wrapping_add(max_dif)
+                    } else {
+                        value
+                    };
+                }
+            }
+            Transform::Octahedron(o) | Transform::OctahedronCanonicalized(o) => {
+                let canonicalized = matches!(self, Transform::OctahedronCanonicalized(_));
+                let center = o.center_value;
+                let mut p = [pred[0].wrapping_sub(center), pred[1].wrapping_sub(center)];
+                let in_diamond = o.is_in_diamond(p[0], p[1]);
+                if !in_diamond {
+                    p = o.invert_diamond(p);
+                }
+                // The canonicalized variant rotates the prediction into the bottom left quadrant.
+                let in_bottom_left = (p[0] == 0 && p[1] == 0) || (p[0] < 0 && p[1] <= 0);
+                let rotation = match canonicalized && !in_bottom_left {
+                    true => rotation_count(p),
+                    false => 0,
+                };
+                p = rotate(p, rotation);
+                let mut orig = [
+                    o.mod_max(p[0].wrapping_add(corr[0])),
+                    o.mod_max(p[1].wrapping_add(corr[1])),
+                ];
+                orig = rotate(orig, (4 - rotation) % 4);
+                if !in_diamond {
+                    orig = o.invert_diamond(orig);
+                }
+                out[0] = orig[0].wrapping_add(center);
+                out[1] = orig[1].wrapping_add(center);
+            }
+        }
+    }
+
+    /// Corrections are stored without the sign bit.
+    fn corrections_positive(&self) -> bool {
+        matches!(
+            self,
+            Transform::Octahedron(_) | Transform::OctahedronCanonicalized(_)
+        )
+    }
+}
+
+fn rotation_count(p: [i32; 2]) -> u32 {
+    let [x, y] = p;
+    match (x.signum(), y.signum()) {
+        (0, 0) => 0,
+        (0, 1) => 3,
+        (0, _) => 1,
+        (1, y) if y >= 0 => 2,
+        (1, _) => 1,
+        (_, y) if y <= 0 => 0,
+        _ => 3,
+    }
+}
+
+fn rotate(p: [i32; 2], count: u32) -> [i32; 2] {
+    match count {
+        1 => [p[1], -p[0]],
+        2 => [-p[0], -p[1]],
+        3 => [-p[1], p[0]],
+        _ => p,
+    }
+}
+
+/// Connectivity the attribute values were decoded over.
+pub struct MeshData<'a> {
+    pub table: &'a dyn Table,
+    pub encoding: &'a EncodingData,
+}
+
+/// Portable (quantized) position values, used by schemes predicting from geometry.
+pub struct Positions<'a> {
+    pub values: &'a [i32],
+    pub point_to_value: &'a [u32],
+}
+
+impl Positions<'_> {
+    fn get(&self, point: u32) -> Option<[i64; 3]> {
+        let value = *self.point_to_value.get(point as usize)? as usize;
+        let v = self.values.get(value * 3..value * 3 + 3)?;
+        Some([v[0] as i64, v[1] as i64, v[2] as i64])
+    }
+}
+
+pub struct Prediction<'a> {
+    pub method: i8,
+    pub transform_type: i8,
+    pub mesh: Option<MeshData<'a>>,
+    pub positions: Option<Positions<'a>>,
+}
+
+impl Prediction<'_> {
+    /// Whether the scheme needs the position attribute to be decoded first.
+    pub fn needs_positions(method: i8) -> bool {
+        method == MESH_PREDICTION_TEX_COORDS_PORTABLE || method == MESH_PREDICTION_GEOMETRIC_NORMAL
+    }
+
+    /// Read the scheme data from `buffer` and restore `values` from the corrections in place.
+    /// `values` hold the already decoded symbols.
+    pub fn decode(
+        &self,
+        buffer: &mut Buffer,
+        values: &[u32],
+        num_components: usize,
+    ) -> Option<Vec<i32>> {
+        if self.method == PREDICTION_NONE {
+            return Some(values.iter().map(|&v| unzigzag(v)).collect());
+        }
+        // Mesh schemes fall back to the difference one without connectivity.
+        let method = match &self.mesh {
+            Some(mesh) if mesh.encoding.value_to_corner.len() * num_components == values.len() => {
+                self.method
+            }
+            Some(_) => return None,
+            None => PREDICTION_DIFFERENCE,
+        };
+        let mut crease_edges = vec![];
+        let mut orientations = vec![];
+        match method {
+            MESH_PREDICTION_CONSTRAINED_MULTI_PARALLELOGRAM => {
+                let num_corners = self.mesh.as_ref()?.table.num_faces() * 3;
+                for _ in 0..MAX_PARALLELOGRAMS {
+                    let num_flags = buffer.varint()? as usize;
+                    if num_flags > num_corners {
+                        return None;
+                    }
+                    let mut flags = Vec::with_capacity(num_flags);
+                    if num_flags > 0 {
+                        let mut decoder = BitDecoder::new(buffer)?;
+                        flags.extend((0..num_flags).map(|_| decoder.decode()));
+                    }
+                    crease_edges.push(flags);
+                }
+            }
+            MESH_PREDICTION_TEX_COORDS_PORTABLE => {
+                let num_orientations = usize::try_from(buffer.i32()?).ok()?;
+                let mut decoder = BitDecoder::new(buffer)?;
+                let mut last = true;
+                for _ in 0..num_orientations {
+                    if !decoder.decode() {
+                        last = !last;
+                    }
+                    orientations.push(last);
+                }
+            }
+            _ => {}
+        }
+        let transform = Transform::decode(self.transform_type, buffer)?;
+        let mut flips = match method {
+            MESH_PREDICTION_GEOMETRIC_NORMAL => Some(BitDecoder::new(buffer)?),
+            _ => None,
+        };
+
+        let mut out: Vec<i32> = if transform.corrections_positive() {
+            values.iter().map(|&v| v as i32).collect()
+        } else {
+            values.iter().map(|&v| unzigzag(v)).collect()
+        };
+        if out.is_empty() {
+            return Some(out);
+        }
+        let n = num_components;
+        match method {
+            PREDICTION_DIFFERENCE => difference(&transform, &mut out, n),
+            MESH_PREDICTION_PARALLELOGRAM => {
+                parallelogram(&transform, self.mesh.as_ref()?, &mut out, n)?
+            }
+            MESH_PREDICTION_CONSTRAINED_MULTI_PARALLELOGRAM => constrained_multi_parallelogram(
+                &transform,
+                self.mesh.as_ref()?,
+                &crease_edges,
+                &mut out,
+                n,
+            )?,
+            MESH_PREDICTION_TEX_COORDS_PORTABLE => tex_coords_portable(
+                &transform,
+                self.mesh.as_ref()?,
+                self.positions.as_ref()?,
+                orientations,
+                &mut out,
+                n,
+            )?,
+            MESH_PREDICTION_GEOMETRIC_NORMAL => geometric_normal(
+                &transform,
+                self.mesh.as_ref()?,
+                self.positions.as_ref()?,
+                flips.as_mut()?,
+                &mut out,
+                n,
+            )?,
+            _ => return None,
+        }
+        Some(out)
+    }
+}
+
+fn unzigzag(v: u32) -> i32 {
+    ((v >> 1) as i32) ^ -((v & 1) as i32)
+}
+
+/// `data[i]` is the correction on input and the original value on output.
+fn restore(transform: &Transform, data: &mut [i32], pred: &[i32], i: usize, n: usize) {
+    let corr = data[i * n..i * n + n].to_vec();
+    transform.original_value(pred, &corr, &mut data[i * n..i * n + n]);
+}
+
+fn difference(transform: &Transform, data: &mut [i32], n: usize) {
+    restore(transform, data, &vec![0; n], 0, n);
+    for i in 1..data.len() / n {
+        let pred = data[(i - 1) * n..i * n].to_vec();
+        restore(transform, data, &pred, i, n);
+    }
+}
+
+/// Prediction from the triangle on the other side of the edge opposite to `corner`.
+fn parallelogram_prediction(
+    mesh: &MeshData,
+    corner: u32,
+    value: usize,
+    data: &[i32],
+    n: usize,
+) -> Option<Vec<i32>> {
+    let table = mesh.table;
+    let opp = table.opposite(corner);
+    if opp == INVALID {
+        return None;
+    }
+    let entry = |c: u32| {
+        let value = *mesh
+            .encoding
+            .vertex_to_value
+            .get(table.vertex(c) as usize)?;
+        Some(value as usize)
+    };
+    let vert_opp = entry(opp)?;
+    let vert_next = entry(table.next(opp))?;
+    let vert_prev = entry(table.previous(opp))?;
+    if vert_opp >= value || vert_next >= value || vert_prev >= value {
+        return None;
+    }
+    Some(
+        (0..n)
+            .map(|c| {
+                (data[vert_next * n + c] as i64 + data[vert_prev * n + c] as i64
+                    - data[vert_opp * n + c] as i64) as i32
+            })
+            .collect(),
+    )
+}
+
+fn parallelogram(transform: &Transform, mesh: &MeshData, data: &mut [i32], n: usize) -> Option<()> {
+    restore(transform, data, &vec![0; n], 0, n);
+    for value in 1..mesh.encoding.value_to_corner.len() {
+        let corner = mesh.encoding.value_to_corner[value];
+        let pred = parallelogram_prediction(mesh, corner, value, data, n)
+            .unwrap_or_else(|| data[(value - 1) * n..value * n].to_vec());
+        restore(transform, data, &pred, value, n);
+    }
+    Some(())
+}
+
+fn constrained_multi_parallelogram(
+    transform: &Transform,
+    mesh: &MeshData,
+    crease_edges: &[Vec<bool>],
+    data: &mut [i32],
+    n: usize,
+) -> Option<()> {
+    let table = mesh.table;
+    restore(transform, data, &vec![0; n], 0, n);
+    let mut crease_edge_pos = [0; MAX_PARALLELOGRAMS];
+    for value in 1..mesh.encoding.value_to_corner.len() {
+        let start = mesh.encoding.value_to_corner[value];
+        let mut predictions = vec![];
+        let mut corner = start;
+        let mut first_pass = true;
+        while corner != INVALID {
+            if let Some(pred) = parallelogram_prediction(mesh, corner, value, data, n) {
+                predictions.push(pred);
+                if predictions.len() == MAX_PARALLELOGRAMS {
+                    break;
+                }
+            }
+            corner = if first_pass {
+                table.swing_left(corner)
+            } else {
+                table.swing_right(corner)
+            };
+            if corner == start {
+                break;
+            }
+            if corner == INVALID && first_pass {
+                first_pass = false;
+                corner = table.swing_right(start);
+            }
+        }
+
+        let mut multi_pred = vec![0i32; n];
+        let mut num_used = 0;
+        if !predictions.is_empty() {
+            let context = predictions.len() - 1;
+            for pred in &predictions {
+                let pos = crease_edge_pos[context];
+                crease_edge_pos[context] += 1;
+                if !*crease_edges.get(context)?.get(pos)? {
+                    num_used += 1;
+                    for c in 0..n {
+                        multi_pred[c] = multi_pred[c].wrapping_add(pred[c]);
+                    }
+                }
+            }
+        }
+        if num_used == 0 {
+            let pred = data[(value - 1) * n..value * n].to_vec();
+            restore(transform, data, &pred, value, n);
+        } else {
+            for pred in &mut multi_pred {
+                *pred /= num_used;
+            }
+            restore(transform, data, &multi_pred, value, n);
+        }
+    }
+    Some(())
+}
+
+fn int_sqrt(number: u64) -> u64 {
+    if number == 0 {
+        return 0;
+    }
+    let mut act_number = number;
+    let mut square_root = 1u64;
+    while act_number >= 2 {
+        square_root *= 2;
+        act_number /= 4;
+    }
+    loop {
+        square_root = (square_root + number / square_root) / 2;
+        if square_root.wrapping_mul(square_root) <= number {
+            return square_root;
+        }
+    }
+}
+
+fn tex_coords_portable(
+    transform: &Transform,
+    mesh: &MeshData,
+    positions: &Positions,
+    mut orientations: Vec<bool>,
+    data: &mut [i32],
+    n: usize,
+) -> Option<()> {
+    if n != 2 {
+        return None;
+    }
+    let table = mesh.table;
+    let encoding = mesh.encoding;
+    let position = |value: usize| positions.get(*encoding.value_to_point.get(value)?);
+    for value in 0..encoding.value_to_corner.len() {
+        let corner = encoding.value_to_corner[value];
+        let value_of = |corner: u32| {
+            let value = *encoding
+                .vertex_to_value
+                .get(table.vertex(corner) as usize)?;
+            Some(value as usize)
+        };
+        let next_value = value_of(table.next(corner))?;
+        let prev_value = value_of(table.previous(corner))?;
+        let uv = |value: usize| [data[value * 2] as i64, data[value * 2 + 1] as i64];
+
+        let mut predicted = None;
+        if prev_value < value && next_value < value {
+            let n_uv = uv(next_value);
+            let p_uv = uv(prev_value);
+            if p_uv == n_uv {
+                predicted = Some([p_uv[0] as i32, p_uv[1] as i32]);
+            } else {
+                let tip_pos = position(value)?;
+                let next_pos = position(next_value)?;
+                let prev_pos = position(prev_value)?;
+                let pn = [0, 1, 2].map(|i| prev_pos[i].wrapping_sub(next_pos[i]));
+                let pn_norm2_squared = pn
+                    .iter()
+                    .fold(0i64, |acc, &x| acc.wrapping_add(x.wrapping_mul(x)));
+                if pn_norm2_squared != 0 {
+                    let cn = [0, 1, 2].map(|i| tip_pos[i].wrapping_sub(next_pos[i]));
+                    let cn_dot_pn =
+                        (0..3).fold(0i64, |acc, i| acc.wrapping_add(pn[i].wrapping_mul(cn[i])));
+                    let pn_uv = [p_uv[0].wrapping_sub(n_uv[0]), p_uv[1].wrapping_sub(n_uv[1])];
+                    let n_uv_absmax = n_uv[0].abs().max(n_uv[1].abs());
+                    if n_uv_absmax > i64::MAX / pn_norm2_squared {
+                        return None;
+                    }
+                    let pn_uv_absmax = pn_uv[0].abs().max(pn_uv[1].abs());
+                    if cn_dot_pn > i64::MAX / pn_uv_absmax {
+                        return None;
+                    }
+                    let x_uv = [0, 1].map(|i| {
+                        n_uv[i]
+                            .wrapping_mul(pn_norm2_squared)
+                            .wrapping_add(cn_dot_pn.wrapping_mul(pn_uv[i]))
+                    });
+                    let pn_absmax = pn[0].abs().max(pn[1].abs()).max(pn[2].abs());
+                    if cn_dot_pn > i64::MAX / pn_absmax {
+                        return None;
+                    }
+                    let x_pos = [0, 1, 2].map(|i| {
+                        next_pos[i].wrapping_add(cn_dot_pn.wrapping_mul(pn[i]) / pn_norm2_squared)
+                    });
+                    let cx_norm2_squared = (0..3).fold(0u64, |acc, i| {
+                        let d = tip_pos[i].wrapping_sub(x_pos[i]) as u64;
+                        acc.wrapping_add(d.wrapping_mul(d))
+                    });
+                    let norm_squared =
+                        int_sqrt(cx_norm2_squared.wrapping_mul(pn_norm2_squared as u64)) as i64;
+                    let cx_uv = [
+                        pn_uv[1].wrapping_mul(norm_squared),
+                        pn_uv[0].wrapping_neg().wrapping_mul(norm_squared),
+                    ];
+                    let orientation = orientations.pop()?;
+                    let uv = [0, 1].map(|i| {
+                        let v = if orientation {
+                            x_uv[i].wrapping_add(cx_uv[i])
+                        } else {
+                            x_uv[i].wrapping_sub(cx_uv[i])
+                        };
+                        (v / pn_norm2_squared) as i32
+                    });
+                    predicted = Some(uv);
+                }
+            }
+        }
+        let predicted = match predicted {
+            Some(predicted) => predicted,
+            None => {
+                // Same quirk as the reference decoder: the previous corner is only used
+                // when the next one is available too.
+                let offset = if next_value < value {
+                    Some(next_value)
+                } else if value > 0 {
+                    Some(value - 1)
+                } else {
+                    None
+                };
+                match offset {
+                    Some(offset) => [data[offset * 2], data[offset * 2 + 1]],
+                    None => [0, 0],
+                }
+            }
+        };
+        restore(transform, data, &predicted, value, 2);
+    }
+    Some(())
+}
+
+fn geometric_normal(
+    transform: &Transform,
+    mesh: &MeshData,
+    positions: &Positions,
+    flips: &mut BitDecoder,
+    data: &mut [i32],
+    n: usize,
+) -> Option<()> {
+    if n != 2 {
+        return None;
+    }
+    let octahedron = transform.octahedron()?;
+    let table = mesh.table;
+    let encoding = mesh.encoding;
+    let position = |corner: u32| {
+        let value = *encoding
+            .vertex_to_value
+            .get(table.vertex(corner) as usize)?;
+        positions.get(*encoding.value_to_point.get(value as usize)?)
+    };
+    for value in 0..encoding.value_to_corner.len() {
+        let corner = encoding.value_to_corner[value];
+        let center = position(corner)?;
+        let mut normal = [0i64; 3];
+        for c in vertex_corners(table, corner) {
+            let next = position(table.next(c))?;
+            let prev = position(table.previous(c))?;
+            let dn = [0, 1, 2].map(|i| next[i].wrapping_sub(center[i]));
+            let dp = [0, 1, 2].map(|i| prev[i].wrapping_sub(center[i]));
+            let cross = [
+                dn[1]
+                    .wrapping_mul(dp[2])
+                    .wrapping_sub(dn[2].wrapping_mul(dp[1])),
+                dn[2]
+                    .wrapping_mul(dp[0])
+                    .wrapping_sub(dn[0].wrapping_mul(dp[2])),
+                dn[0]
+                    .wrapping_mul(dp[1])
+                    .wrapping_sub(dn[1].wrapping_mul(dp[0])),
+            ];
+            for i in 0..3 {
+                normal[i] = normal[i].wrapping_add(cross[i]);
+            }
+        }
+        const UPPER_BOUND: i64 = 1 << 29;
+        // Truncated to 32 bits like in the reference decoder.
+        let abs_sum = normal
+            .iter()
+            .fold(0i64, |acc, x| acc.wrapping_add(x.wrapping_abs())) as i32
+            as i64;
+        if abs_sum > UPPER_BOUND {
+            let quotient = abs_sum / UPPER_BOUND;
+            normal = normal.map(|x| x / quotient);
+        }
+        let mut normal = normal.map(|x| x as i32);
+        octahedron.canonicalize_integer_vector(&mut normal);
+        if flips.decode() {
+            normal = normal.map(|x| x.wrapping_neg());
+        }
+        let pred = octahedron.integer_vector_to_octahedral_coords(normal);
+        restore(transform, data, &pred, value, 2);
+    }
+    Some(())
+}
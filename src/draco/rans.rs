@@ -0,0 +1,223 @@
+//! rANS entropy decoders: multi-symbol decoder used for symbol streams and the
+//! binary one used for flags.
+
+use super::buffer::Buffer;
+
+const IO_BASE: u32 = 256;
+const BIT_L_BASE: u32 = 4096;
+const BIT_PRECISION: u32 = 256;
+
+/// Tagged scheme, bit length of the values is entropy coded, the values are raw bits.
+const SYMBOL_CODING_TAGGED: u8 = 0;
+/// All values are entropy coded.
+const SYMBOL_CODING_RAW: u8 = 1;
+
+/// Initial state stored in the last 1 to 4 bytes, the top two bits hold the byte count.
+fn read_init(data: &[u8], l_base: u32, allow_four_bytes: bool) -> Option<(usize, u32)> {
+    let last = *data.last()?;
+    let len = data.len();
+    let (offset, state) = match last >> 6 {
+        0 => (len - 1, (last & 0x3f) as u32),
+        1 if len >= 2 => (
+            len - 2,
+            u16::from_le_bytes([data[len - 2], data[len - 1]]) as u32 & 0x3fff,
+        ),
+        2 if len >= 3 => (
+            len - 3,
+            u32::from_le_bytes([data[len - 3], data[len - 2], data[len - 1], 0]) & 0x3f_ffff,
+        ),
+        3 if len >= 4 && allow_four_bytes => (
+            len - 4,
+            u32::from_le_bytes(data[len - 4..].try_into().unwrap()) & 0x3fff_ffff,
+        ),
+        _ => return None,
+    };
+    let state = state + l_base;
+    (state < l_base * IO_BASE).then_some((offset, state))
+}
+
+pub struct SymbolDecoder<'a> {
+    data: &'a [u8],
+    offset: usize,
+    state: u32,
+    precision: u32,
+    l_base: u32,
+    lut: Vec<u32>,
+    /// (probability, cumulative probability) for each symbol.
+    probabilities: Vec<(u32, u32)>,
+}
+
+impl<'a> SymbolDecoder<'a> {
+    /// Read the probability table and the encoded data, leaving `buffer` past both.
+    pub fn new(buffer: &mut Buffer<'a>, symbol_bit_length: u32) -> Option<SymbolDecoder<'a>> {
+        let precision_bits = (3 * symbol_bit_length / 2).clamp(12, 20);
+        let precision = 1 << precision_bits;
+
+        let num_symbols = buffer.varint()? as usize;
+        if num_symbols / 64 > buffer.remaining_size() {
+            return None;
+        }
+        let mut probs = vec![0u32; num_symbols];
+        let mut i = 0;
+        while i < num_symbols {
+            let data = buffer.u8()?;
+            let token = data & 3;
+            if token == 3 {
+                // Run of zero probability symbols.
+                let offset = (data >> 2) as usize;
+                if i + offset >= num_symbols {
+                    return None;
+                }
+                i += offset + 1;
+                continue;
+            }
+            let mut prob = (data >> 2) as u32;
+            for b in 0..token as u32 {
+                prob |= (buffer.u8()? as u32) << (8 * (b + 1) - 2);
+            }
+            probs[i] = prob;
+            i += 1;
+        }
+
+        let mut lut = vec![0; precision as usize];
+        let mut probabilities = Vec::with_capacity(num_symbols);
+        let mut cum_prob = 0u32;
+        for (i, &prob) in probs.iter().enumerate() {
+            probabilities.push((prob, cum_prob));
+            let start = cum_prob;
+            cum_prob = cum_prob.checked_add(prob)?;
+            if cum_prob > precision {
+                return None;
+            }
+            lut[start as usize..cum_prob as usize].fill(i as u32);
+        }
+        if num_symbols != 0 && cum_prob != precision {
+            return None;
+        }
+
+        let mut decoder = SymbolDecoder {
+            data: &[],
+            offset: 0,
+            state: 0,
+            precision,
+            l_base: precision * 4,
+            lut,
+            probabilities,
+        };
+        if num_symbols == 0 {
+            return Some(decoder);
+        }
+        let size = buffer.varint64()?;
+        let data = buffer.bytes(usize::try_from(size).ok()?)?;
+        let (offset, state) = read_init(data, decoder.l_base, true)?;
+        decoder.data = data;
+        decoder.offset = offset;
+        decoder.state = state;
+        Some(decoder)
+    }
+
+    pub fn num_symbols(&self) -> usize {
+        self.probabilities.len()
+    }
+
+    pub fn decode(&mut self) -> u32 {
+        while self.state < self.l_base && self.offset > 0 {
+            self.offset -= 1;
+            self.state = self.state * IO_BASE + self.data[self.offset] as u32;
+        }
+        let quo = self.state / self.precision;
+        let rem = self.state % self.precision;
+        let symbol = self.lut[rem as usize];
+        let (prob, cum_prob) = self.probabilities[symbol as usize];
+        self.state = quo * prob + rem - cum_prob;
+        symbol
+    }
+}
+
+pub struct BitDecoder<'a> {
+    data: &'a [u8],
+    offset: usize,
+    state: u32,
+    prob_zero: u8,
+}
+
+impl<'a> BitDecoder<'a> {
+    pub fn new(buffer: &mut Buffer<'a>) -> Option<BitDecoder<'a>> {
+        let prob_zero = buffer.u8()?;
+        let size = buffer.varint()? as usize;
+        let data = buffer.bytes(size)?;
+        let (offset, state) = read_init(data, BIT_L_BASE, false)?;
+        Some(BitDecoder {
+            data,
+            offset,
+            state,
+            prob_zero,
+        })
+    }
+
+    pub fn decode(&mut self) -> bool {
+        let p = BIT_PRECISION - self.prob_zero as u32;
+        if self.state < BIT_L_BASE && self.offset > 0 {
+            self.offset -= 1;
+            self.state = self.state * IO_BASE + self.data[self.offset] as u32;
+        }
+        let quot = self.state / BIT_PRECISION;
+        let rem = self.state % BIT_PRECISION;
+        let xn = quot * p;
+        let bit = rem < p;
+        if bit {
+            self.state = xn + rem;
+        } else {
+            self.state -= xn + p;
+        }
+        bit
+    }
+}
+
+/// Decode `num_values` symbols, `num_components` of them share a bit length
+/// in the tagged scheme.
+pub fn decode_symbols(
+    num_values: usize,
+    num_components: usize,
+    buffer: &mut Buffer,
+) -> Option<Vec<u32>> {
+    if num_values == 0 {
+        return Some(vec![]);
+    }
+    let mut values = Vec::with_capacity(num_values);
+    match buffer.u8()? {
+        SYMBOL_CODING_TAGGED => {
+            let mut tags = SymbolDecoder::new(buffer, 5)?;
+            if tags.num_symbols() == 0 {
+                return None;
+            }
+            buffer.start_bit_decoding(false)?;
+            while values.len() < num_values {
+                let bit_length = tags.decode();
+                if bit_length > 32 {
+                    return None;
+                }
+                for _ in 0..num_components {
+                    values.push(buffer.bits(bit_length)?);
+                }
+            }
+            buffer.end_bit_decoding();
+            values.truncate(num_values);
+        }
+        SYMBOL_CODING_RAW => {
+            let max_bit_length = buffer.u8()? as u32;
+            if !(1..=18).contains(&max_bit_length) {
+                return None;
+            }
+            let mut decoder = SymbolDecoder::new(buffer, max_bit_length)?;
+            if decoder.num_symbols() == 0 {
+                return None;
+            }
+            for _ in 0..num_values {
+                values.push(decoder.decode());
+            }
+        }
+        _ => return None,
+    }
+    Some(values)
+}
@@ -0,0 +1,177 @@
+//! Mesh traversals defining the order in which attribute values are decoded.
+
+use super::connectivity::{Table, INVALID};
+
+/// Attribute value order produced by a traversal.
+#[derive(Default)]
+pub struct EncodingData {
+    /// Decoded value index of each corner table vertex.
+    pub vertex_to_value: Vec<u32>,
+    /// Corner the value was first reached from.
+    pub value_to_corner: Vec<u32>,
+    /// Point index of each value, in decoding order.
+    pub value_to_point: Vec<u32>,
+}
+
+struct Visitor<'a, T: ?Sized> {
+    table: &'a T,
+    faces: &'a [[u32; 3]],
+    face_visited: Vec<bool>,
+    vertex_visited: Vec<bool>,
+    data: EncodingData,
+}
+
+impl<'a, T: Table + ?Sized> Visitor<'a, T> {
+    fn is_face_visited(&self, corner: u32) -> bool {
+        corner == INVALID || self.face_visited[corner as usize / 3]
+    }
+
+    fn visit_vertex(&mut self, corner: u32) -> Option<()> {
+        let vertex = self.table.vertex(corner);
+        let visited = self.vertex_visited.get_mut(vertex as usize)?;
+        if *visited {
+            return Some(());
+        }
+        *visited = true;
+        let point = self.faces[corner as usize / 3][corner as usize % 3];
+        self.data.vertex_to_value[vertex as usize] = self.data.value_to_point.len() as u32;
+        self.data.value_to_point.push(point);
+        self.data.value_to_corner.push(corner);
+        Some(())
+    }
+
+    fn start(table: &'a T, faces: &'a [[u32; 3]]) -> Visitor<'a, T> {
+        Visitor {
+            table,
+            faces,
+            face_visited: vec![false; table.num_faces()],
+            vertex_visited: vec![false; table.num_vertices()],
+            data: EncodingData {
+                vertex_to_value: vec![0; table.num_vertices()],
+                ..Default::default()
+            },
+        }
+    }
+}
+
+pub fn depth_first<T: Table + ?Sized>(table: &T, faces: &[[u32; 3]]) -> Option<EncodingData> {
+    let mut visitor = Visitor::start(table, faces);
+    let mut stack = vec![];
+    for face in 0..table.num_faces() as u32 {
+        let corner = 3 * face;
+        if visitor.is_face_visited(corner) {
+            continue;
+        }
+        visitor.visit_vertex(table.next(corner))?;
+        visitor.visit_vertex(table.previous(corner))?;
+        stack.push(corner);
+        while let Some(&top) = stack.last() {
+            let mut corner = top;
+            if visitor.is_face_visited(corner) {
+                stack.pop();
+                continue;
+            }
+            loop {
+                visitor.face_visited[corner as usize / 3] = true;
+                let vertex = table.vertex(corner);
+                if !*visitor.vertex_visited.get(vertex as usize)? {
+                    let on_boundary = table.is_on_boundary(vertex);
+                    visitor.visit_vertex(corner)?;
+                    if !on_boundary {
+                        corner = table.right_corner(corner);
+                        continue;
+                    }
+                }
+                let right = table.right_corner(corner);
+                let left = table.left_corner(corner);
+                match (
+                    visitor.is_face_visited(right),
+                    visitor.is_face_visited(left),
+                ) {
+                    (true, true) => {
+                        stack.pop();
+                        break;
+                    }
+                    (true, false) => corner = left,
+                    (false, true) => corner = right,
+                    (false, false) => {
+                        // Right first, the left one is processed once the right side is done.
+                        *stack.last_mut()? = left;
+                        stack.push(right);
+                        break;
+                    }
+                }
+            }
+        }
+    }
+    Some(visitor.data)
+}
+
+/// Prefers faces whose tip vertex can be predicted from more already decoded neighbours.
+pub fn max_prediction_degree<T: Table + ?Sized>(
+    table: &T,
+    faces: &[[u32; 3]],
+) -> Option<EncodingData> {
+    const MAX_PRIORITY: usize = 3;
+    let mut visitor = Visitor::start(table, faces);
+    let mut prediction_degree = vec![0u32; table.num_vertices()];
+    let mut stacks: [Vec<u32>; MAX_PRIORITY] = Default::default();
+    let mut best_priority;
+
+    for face in 0..table.num_faces() as u32 {
+        let corner = 3 * face;
+        if visitor.is_face_visited(corner) {
+            continue;
+        }
+        stacks[0].push(corner);
+        best_priority = 0;
+        visitor.visit_vertex(table.next(corner))?;
+        visitor.visit_vertex(table.previous(corner))?;
+        visitor.visit_vertex(corner)?;
+
+        while let Some(priority) = (best_priority..MAX_PRIORITY).find(|&i| !stacks[i].is_empty()) {
+            best_priority = priority;
+            let mut corner = stacks[priority].pop().unwrap();
+            if visitor.is_face_visited(corner) {
+                continue;
+            }
+            loop {
+                visitor.face_visited[corner as usize / 3] = true;
+                visitor.visit_vertex(corner)?;
+
+                let right = table.right_corner(corner);
+                let left = table.left_corner(corner);
+                let right_visited = visitor.is_face_visited(right);
+                let left_visited = visitor.is_face_visited(left);
+                let mut compute_priority = |corner: u32| -> Option<usize> {
+                    let tip = table.vertex(corner) as usize;
+                    if *visitor.vertex_visited.get(tip)? {
+                        return Some(0);
+                    }
+                    prediction_degree[tip] += 1;
+                    Some(if prediction_degree[tip] > 1 { 1 } else { 2 })
+                };
+                if !left_visited {
+                    let priority = compute_priority(left)?;
+                    if right_visited && priority <= best_priority {
+                        corner = left;
+                        continue;
+                    }
+                    stacks[priority].push(left);
+                    best_priority = best_priority.min(priority);
+                }
+                if !right_visited {
+                    let priority = compute_priority(right)?;
+                    if priority <= best_priority {
+                        corner = right;
+                        continue;
+                    }
+                    stacks[priority].push(right);
+                    best_priority = best_priority.min(priority);
+                }
+                break;
+            }
+        }
+    }
+    Some(visitor.data)
+}
@@ -0,0 +1,87 @@
+//! EXT_lights_image_based environment lighting.
+//!
+//! Environment maps come as prefiltered specular cube maps, one set of 6 face images per
+//! mip level, plus spherical harmonics for the diffuse irradiance. The images are
+//! regular `gltf.images`, decoding them is up to the loader.
+
+use crate::gltf::ImageBasedLight;
+use crate::math::{self, Mat4, Vec3};
+use crate::Gltf;
+
+/// All the document's image based lights.
+pub fn image_based_lights(gltf: &Gltf) -> &[ImageBasedLight] {
+    gltf.extensions
+        .as_ref()
+        .and_then(|e| e.ext_lights_image_based.as_ref())
+        .map_or(&[], |e| &e.lights)
+}
+
+/// The environment light of the scene, None if the scene has none or references a missing one.
+pub fn scene_image_based_light(gltf: &Gltf, scene: usize) -> Option<&ImageBasedLight> {
+    let light = gltf.scenes[scene].image_based_light()?;
+    image_based_lights(gltf).get(light)
+}
+
+/// One mip level of the specular cube map.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpecularMip {
+    /// Width and height of the face images.
+    pub size: usize,
+    /// Indices into `gltf.images`, in the +X, -X, +Y, -Y, +Z, -Z face order.
+    pub faces: [usize; 6],
+}
+
+/// Specular cube map mip levels, from the largest to the smallest.
+pub fn specular_mips(light: &ImageBasedLight) -> Vec<SpecularMip> {
+    light
+        .specular_images
+        .iter()
+        .enumerate()
+        .map(|(level, faces)| SpecularMip {
+            size: (light.specular_image_size >> level).max(1),
+            faces: *faces,
+        })
+        .collect()
+}
+
+/// Rotation of the environment as a column-major matrix,
+/// directions in world space are sampled at `rotation^-1 * direction`.
+pub fn rotation(light: &ImageBasedLight) -> Mat4 {
+    let r = light.rotation.map(|x| x as f32);
+    math::from_trs([0.0; 3], r, [1.0; 3])
+}
+
+/// Irradiance for the world space surface normal, scaled by the light's intensity.
+/// Black if the light has less than 9 coefficients.
+pub fn irradiance(light: &ImageBasedLight, normal: Vec3) -> [f32; 3] {
+    let Some(coefficients) = light.irradiance_coefficients.get(..9) else {
+        return [0.0; 3];
+    };
+    // inverse of a rotation is its transpose
+    let r = rotation(light);
+    let [x, y, z] = math::normalize([
+        r[0] * normal[0] + r[1] * normal[1] + r[2] * normal[2],
+        r[4] * normal[0] + r[5] * normal[1] + r[6] * normal[2],
+        r[8] * normal[0] + r[9] * normal[1] + r[10] * normal[2],
+    ]);
+    let basis = [
+        0.282095,
+        0.488603 * y,
+        0.488603 * z,
+        0.488603 * x,
+        1.092548 * x * y,
+        1.092548 * y * z,
+        0.315392 * (3.0 * z * z - 1.0),
+        1.092548 * x * z,
+        0.546274 * (x * x - y * y),
+    ];
+    let intensity = light.intensity as f32;
+    [0, 1, 2].map(|c| {
+        let sum = coefficients
+            .iter()
+            .zip(basis)
+            .map(|(coefficient, b)| coefficient[c] as f32 * b)
+            .sum::<f32>();
+        sum.max(0.0) * intensity
+    })
+}
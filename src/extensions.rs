@@ -0,0 +1,197 @@
+//! User defined vendor extensions.
+//!
+//! nanogltf parses the Khronos and common vendor extensions into typed fields and skips
+//! everything else. An application with its own extensions, like `ACME_collision_shapes`,
+//! implements `Extension` for its payload type, registers it and loads the document with
+//! `Gltf::from_json_with_extensions`. The payloads are then available on the objects:
+//! `node.extension::<AcmeCollision>()`.
+//!
+//! Custom extensions are looked up on the root, scenes, nodes, meshes, primitives,
+//! materials, textures, images, samplers, animations, accessors, buffers and buffer views.
+//! Cameras and skins are not parsed by nanogltf, so neither are their extensions.
+
+use std::any::Any;
+use std::fmt;
+
+use nanoserde::DeJson;
+
+use crate::gltf::JsonValue;
+use crate::Gltf;
+
+/// A vendor extension payload.
+pub trait Extension: Any + Send + Sync + Sized {
+    /// The extension name, the key in the objects' `extensions`.
+    const NAME: &'static str;
+
+    /// Parse the extension's json object. None if it is malformed,
+    /// the extension is then left out for that object.
+    fn from_json(value: &JsonValue) -> Option<Self>;
+}
+
+type Payload = Box<dyn Any + Send + Sync>;
+type Parser = fn(&JsonValue) -> Option<Payload>;
+
+/// The custom extensions of a single object, with the json they were parsed from.
+#[derive(Default)]
+pub struct CustomExtensions {
+    payloads: Vec<(&'static str, JsonValue, Payload)>,
+}
+
+impl CustomExtensions {
+    pub fn get<T: Extension>(&self) -> Option<&T> {
+        self.payloads
+            .iter()
+            .find(|(name, _, _)| *name == T::NAME)
+            .and_then(|(_, _, payload)| payload.downcast_ref())
+    }
+
+    /// Json of the extension the payload was parsed from.
+    pub fn raw(&self, name: &str) -> Option<&JsonValue> {
+        self.payloads
+            .iter()
+            .find(|(n, _, _)| *n == name)
+            .map(|(_, raw, _)| raw)
+    }
+
+    /// Names of the extensions present.
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.payloads.iter().map(|(name, _, _)| *name)
+    }
+}
+
+impl fmt::Debug for CustomExtensions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.names()).finish()
+    }
+}
+
+// payloads are opaque, the json they were parsed from is compared instead
+impl PartialEq for CustomExtensions {
+    fn eq(&self, other: &CustomExtensions) -> bool {
+        self.payloads.len() == other.payloads.len()
+            && self
+                .payloads
+                .iter()
+                .zip(&other.payloads)
+                .all(|((a, a_raw, _), (b, b_raw, _))| a == b && a_raw == b_raw)
+    }
+}
+
+/// Extensions to parse while loading the document.
+#[derive(Default)]
+pub struct Registry {
+    parsers: Vec<(&'static str, Parser)>,
+}
+
+impl Registry {
+    pub fn new() -> Registry {
+        Registry::default()
+    }
+
+    /// Claim `T::NAME`, registering the same name twice keeps the first parser.
+    pub fn register<T: Extension>(&mut self) -> &mut Registry {
+        if !self.parsers.iter().any(|(name, _)| *name == T::NAME) {
+            self.parsers.push((T::NAME, |value| {
+                T::from_json(value).map(|payload| Box::new(payload) as Payload)
+            }));
+        }
+        self
+    }
+
+    /// Payloads of the registered extensions in the object's `extensions`,
+    /// None if there are none.
+    fn parse(&self, object: &JsonValue) -> Option<CustomExtensions> {
+        let extensions = object.get("extensions")?;
+        let payloads: Vec<_> = self
+            .parsers
+            .iter()
+            .filter_map(|(name, parse)| {
+                let raw = extensions.get(name)?;
+                Some((*name, raw.clone(), parse(raw)?))
+            })
+            .collect();
+        (!payloads.is_empty()).then_some(CustomExtensions { payloads })
+    }
+}
+
+macro_rules! impl_extension {
+    ($($ty:ty),*) => {
+        $(
+            impl $ty {
+                /// Payload of the registered custom extension `T`, if the object has it.
+                pub fn extension<T: Extension>(&self) -> Option<&T> {
+                    self.custom_extensions.as_ref()?.get()
+                }
+            }
+        )*
+    };
+}
+
+impl_extension!(
+    crate::gltf::Gltf,
+    crate::gltf::Scene,
+    crate::gltf::Node,
+    crate::gltf::Mesh,
+    crate::gltf::Primitive,
+    crate::gltf::Material,
+    crate::gltf::Texture,
+    crate::gltf::Image,
+    crate::gltf::Sampler,
+    crate::gltf::Animation,
+    crate::gltf::Accessor,
+    crate::gltf::Buffer,
+    crate::gltf::BufferView
+);
+
+/// Parse the document and the custom extensions of `registry`.
+pub(crate) fn from_json(json: &str, registry: &Registry) -> Result<Gltf, nanoserde::DeJsonErr> {
+    let mut gltf = Gltf::deserialize_json(json)?;
+    if registry.parsers.is_empty() {
+        return Ok(gltf);
+    }
+    let raw = JsonValue::deserialize_json(json)?;
+    let objects = |name| raw.get(name).and_then(JsonValue::as_array).unwrap_or(&[]);
+
+    gltf.custom_extensions = registry.parse(&raw);
+    for (scene, raw) in gltf.scenes.iter_mut().zip(objects("scenes")) {
+        scene.custom_extensions = registry.parse(raw);
+    }
+    for (node, raw) in gltf.nodes.iter_mut().zip(objects("nodes")) {
+        node.custom_extensions = registry.parse(raw);
+    }
+    for (mesh, raw) in gltf.meshes.iter_mut().zip(objects("meshes")) {
+        mesh.custom_extensions = registry.parse(raw);
+        let primitives = raw
+            .get("primitives")
+            .and_then(JsonValue::as_array)
+            .unwrap_or(&[]);
+        for (primitive, raw) in mesh.primitives.iter_mut().zip(primitives) {
+            primitive.custom_extensions = registry.parse(raw);
+        }
+    }
+    for (material, raw) in gltf.materials.iter_mut().zip(objects("materials")) {
+        material.custom_extensions = registry.parse(raw);
+    }
+    for (texture, raw) in gltf.textures.iter_mut().zip(objects("textures")) {
+        texture.custom_extensions = registry.parse(raw);
+    }
+    for (image, raw) in gltf.images.iter_mut().zip(objects("images")) {
+        image.custom_extensions = registry.parse(raw);
+    }
+    for (sampler, raw) in gltf.samplers.iter_mut().zip(objects("samplers")) {
+        sampler.custom_extensions = registry.parse(raw);
+    }
+    for (animation, raw) in gltf.animations.iter_mut().zip(objects("animations")) {
+        animation.custom_extensions = registry.parse(raw);
+    }
+    for (accessor, raw) in gltf.accessors.iter_mut().zip(objects("accessors")) {
+        accessor.custom_extensions = registry.parse(raw);
+    }
+    for (buffer, raw) in gltf.buffers.iter_mut().zip(objects("buffers")) {
+        buffer.custom_extensions = registry.parse(raw);
+    }
+    for (view, raw) in gltf.buffer_views.iter_mut().zip(objects("bufferViews")) {
+        view.custom_extensions = registry.parse(raw);
+    }
+    Ok(gltf)
+}
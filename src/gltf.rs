@@ -1,9 +1,11 @@
 use std::fmt;
 
-use nanoserde::DeJson;
+use nanoserde::{DeJson, SerJson};
 
 use std::collections::HashMap;
 
+use crate::extensions::CustomExtensions;
+
 // a helper for Debug impls
 fn debug_trim_string(uri: &str) -> String {
     if uri.len() > 30 {
@@ -13,12 +15,167 @@ fn debug_trim_string(uri: &str) -> String {
     }
 }
 
+#[derive(DeJson)]
+struct RawExtras {
+    #[nserde(rename = "MSFT_screencoverage")]
+    msft_screencoverage: Option<Vec<f64>>,
+}
+
+/// The well known `extras` properties, everything else in `extras` is ignored.
+#[derive(PartialEq, Debug, Default)]
+pub struct Extras {
+    /// MSFT_lod screen coverage thresholds, one per LOD level including the object itself.
+    /// Below the last one the object is not rendered at all.
+    pub msft_screencoverage: Option<Vec<f64>>,
+}
+
+// extras may be any json value, only objects can have the known properties
+impl DeJson for Extras {
+    fn de_json(
+        s: &mut nanoserde::DeJsonState,
+        i: &mut std::str::Chars,
+    ) -> Result<Extras, nanoserde::DeJsonErr> {
+        if s.tok != nanoserde::DeJsonTok::CurlyOpen {
+            s.whole_field(i)?;
+            return Ok(Extras::default());
+        }
+        let RawExtras {
+            msft_screencoverage,
+        } = RawExtras::de_json(s, i)?;
+        Ok(Extras {
+            msft_screencoverage,
+        })
+    }
+}
+
+/// Any json value, for the parts of the document without a fixed schema.
+/// Object keys keep their order, so the value serializes back the way it was written.
+#[derive(Clone, PartialEq, Debug)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(Vec<(String, JsonValue)>),
+}
+
+impl JsonValue {
+    /// Value of the object's key, None for other values and missing keys.
+    pub fn get(&self, key: &str) -> Option<&JsonValue> {
+        match self {
+            JsonValue::Object(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            JsonValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            JsonValue::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[JsonValue]> {
+        match self {
+            JsonValue::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    /// Serialize back into json.
+    pub fn to_json(&self) -> String {
+        SerJson::serialize_json(self)
+    }
+}
+
+impl DeJson for JsonValue {
+    fn de_json(
+        s: &mut nanoserde::DeJsonState,
+        i: &mut std::str::Chars,
+    ) -> Result<JsonValue, nanoserde::DeJsonErr> {
+        use nanoserde::DeJsonTok;
+
+        Ok(match s.tok {
+            DeJsonTok::Null => {
+                s.next_tok(i)?;
+                JsonValue::Null
+            }
+            DeJsonTok::Bool(value) => {
+                s.next_tok(i)?;
+                JsonValue::Bool(value)
+            }
+            DeJsonTok::U64(_) | DeJsonTok::I64(_) | DeJsonTok::F64(_) => {
+                let value = s.as_f64()?;
+                s.next_tok(i)?;
+                JsonValue::Number(value)
+            }
+            DeJsonTok::Str => JsonValue::String(String::de_json(s, i)?),
+            DeJsonTok::BlockOpen => {
+                s.block_open(i)?;
+                let mut values = vec![];
+                while s.tok != DeJsonTok::BlockClose {
+                    values.push(JsonValue::de_json(s, i)?);
+                    s.eat_comma_block(i)?;
+                }
+                s.block_close(i)?;
+                JsonValue::Array(values)
+            }
+            DeJsonTok::CurlyOpen => {
+                s.curly_open(i)?;
+                let mut entries = vec![];
+                while s.tok != DeJsonTok::CurlyClose {
+                    let key = String::de_json(s, i)?;
+                    s.colon(i)?;
+                    entries.push((key, JsonValue::de_json(s, i)?));
+                    s.eat_comma_curly(i)?;
+                }
+                s.curly_close(i)?;
+                JsonValue::Object(entries)
+            }
+            _ => return Err(s.err_token("json value")),
+        })
+    }
+}
+
+impl SerJson for JsonValue {
+    fn ser_json(&self, d: usize, s: &mut nanoserde::SerJsonState) {
+        match self {
+            JsonValue::Null => s.out.push_str("null"),
+            JsonValue::Bool(value) => value.ser_json(d, s),
+            JsonValue::Number(value) => value.ser_json(d, s),
+            JsonValue::String(value) => value.ser_json(d, s),
+            JsonValue::Array(values) => values.ser_json(d, s),
+            JsonValue::Object(entries) => {
+                s.out.push('{');
+                for (n, (key, value)) in entries.iter().enumerate() {
+                    if n > 0 {
+                        s.out.push(',');
+                    }
+                    key.ser_json(d + 1, s);
+                    s.out.push(':');
+                    value.ser_json(d + 1, s);
+                }
+                s.out.push('}');
+            }
+        }
+    }
+}
+
 #[derive(DeJson, PartialEq, Debug)]
 pub struct Gltf {
     #[nserde(default)]
     pub accessors: Vec<Accessor>,
     #[nserde(default)]
-    pub assets: Vec<Asset>,
+    pub animations: Vec<Animation>,
+    pub asset: Option<Asset>,
     #[nserde(default)]
     pub buffers: Vec<Buffer>,
     #[nserde(rename = "bufferViews")]
@@ -26,6 +183,7 @@ pub struct Gltf {
     pub buffer_views: Vec<BufferView>,
     #[nserde(default)]
     pub images: Vec<Image>,
+    pub scene: Option<usize>,
     #[nserde(default)]
     pub scenes: Vec<Scene>,
     #[nserde(default)]
@@ -34,6 +192,220 @@ pub struct Gltf {
     pub meshes: Vec<Mesh>,
     #[nserde(default)]
     pub nodes: Vec<Node>,
+    #[nserde(default)]
+    pub samplers: Vec<Sampler>,
+    #[nserde(default)]
+    pub textures: Vec<Texture>,
+    #[nserde(rename = "extensionsUsed")]
+    #[nserde(default)]
+    pub extensions_used: Vec<String>,
+    #[nserde(rename = "extensionsRequired")]
+    #[nserde(default)]
+    pub extensions_required: Vec<String>,
+    pub extensions: Option<GltfExtensions>,
+    /// Extensions of `extensions::Registry`, set by `Gltf::from_json_with_extensions`.
+    #[nserde(skip)]
+    pub custom_extensions: Option<CustomExtensions>,
+}
+
+#[derive(DeJson, PartialEq, Debug)]
+pub struct GltfExtensions {
+    #[nserde(rename = "KHR_lights_punctual")]
+    pub khr_lights_punctual: Option<LightsPunctual>,
+    #[nserde(rename = "KHR_materials_variants")]
+    pub khr_materials_variants: Option<MaterialsVariants>,
+    #[nserde(rename = "EXT_lights_image_based")]
+    pub ext_lights_image_based: Option<LightsImageBased>,
+    #[nserde(rename = "KHR_xmp_json_ld")]
+    pub khr_xmp_json_ld: Option<XmpPackets>,
+}
+
+/// KHR_xmp_json_ld metadata packets, referenced by index from the document objects.
+#[derive(DeJson, SerJson, PartialEq, Debug)]
+pub struct XmpPackets {
+    /// JSON-LD XMP packets, each one an object with its "@context".
+    #[nserde(default)]
+    pub packets: Vec<JsonValue>,
+}
+
+/// KHR_xmp_json_ld reference from a document object.
+#[derive(DeJson, SerJson, PartialEq, Debug)]
+pub struct XmpPacket {
+    /// Index into the root KHR_xmp_json_ld `packets`.
+    pub packet: usize,
+}
+
+#[derive(DeJson, PartialEq, Debug)]
+pub struct LightsImageBased {
+    #[nserde(default)]
+    pub lights: Vec<ImageBasedLight>,
+}
+
+/// EXT_lights_image_based environment light.
+#[derive(DeJson, PartialEq, Debug)]
+pub struct ImageBasedLight {
+    pub name: Option<String>,
+    /// Rotation of the environment, a unit quaternion (x, y, z, w).
+    #[nserde(default = "[0.0, 0.0, 0.0, 1.0]")]
+    pub rotation: [f64; 4],
+    #[nserde(default = 1.0)]
+    pub intensity: f64,
+    /// 9 RGB spherical harmonics coefficients of the irradiance, bands 0 to 2.
+    #[nserde(rename = "irradianceCoefficients")]
+    pub irradiance_coefficients: Vec<[f64; 3]>,
+    /// Width and height of the largest specular mip level.
+    #[nserde(rename = "specularImageSize")]
+    pub specular_image_size: usize,
+    /// Prefiltered specular cube maps, one per mip level starting from the largest.
+    /// Each is 6 images in the +X, -X, +Y, -Y, +Z, -Z face order.
+    #[nserde(rename = "specularImages")]
+    pub specular_images: Vec<[usize; 6]>,
+}
+
+#[derive(DeJson, PartialEq, Debug)]
+pub struct MaterialsVariants {
+    #[nserde(default)]
+    pub variants: Vec<MaterialVariant>,
+}
+
+#[derive(DeJson, PartialEq, Debug)]
+pub struct MaterialVariant {
+    pub name: String,
+}
+
+#[derive(DeJson, PartialEq, Debug)]
+pub struct LightsPunctual {
+    #[nserde(default)]
+    pub lights: Vec<Light>,
+}
+
+#[derive(DeJson)]
+struct RawSpot {
+    #[nserde(rename = "innerConeAngle")]
+    #[nserde(default = 0.0)]
+    inner_cone_angle: f64,
+    #[nserde(rename = "outerConeAngle")]
+    #[nserde(default = "std::f64::consts::FRAC_PI_4")]
+    outer_cone_angle: f64,
+}
+
+#[derive(DeJson)]
+struct RawLight {
+    name: Option<String>,
+    #[nserde(default = "[1.0, 1.0, 1.0]")]
+    color: [f64; 3],
+    #[nserde(default = 1.0)]
+    intensity: f64,
+    #[nserde(rename = "type")]
+    type_: String,
+    range: Option<f64>,
+    spot: Option<RawSpot>,
+}
+
+/// KHR_lights_punctual light. Lights shine along their node's -Z.
+///
+/// Intensity is in candela for point and spot lights and lux for directional ones.
+/// `range` of None means infinite range.
+#[derive(PartialEq, Debug)]
+pub enum Light {
+    Directional {
+        name: Option<String>,
+        color: [f64; 3],
+        intensity: f64,
+    },
+    Point {
+        name: Option<String>,
+        color: [f64; 3],
+        intensity: f64,
+        range: Option<f64>,
+    },
+    Spot {
+        name: Option<String>,
+        color: [f64; 3],
+        intensity: f64,
+        range: Option<f64>,
+        inner_cone_angle: f64,
+        outer_cone_angle: f64,
+    },
+}
+
+impl DeJson for Light {
+    fn de_json(
+        s: &mut nanoserde::DeJsonState,
+        i: &mut std::str::Chars,
+    ) -> Result<Light, nanoserde::DeJsonErr> {
+        let RawLight {
+            name,
+            color,
+            intensity,
+            type_,
+            range,
+            spot,
+        } = RawLight::de_json(s, i)?;
+
+        Ok(match type_.as_str() {
+            "directional" => Light::Directional {
+                name,
+                color,
+                intensity,
+            },
+            "point" => Light::Point {
+                name,
+                color,
+                intensity,
+                range,
+            },
+            "spot" => {
+                let spot = spot.unwrap_or(RawSpot {
+                    inner_cone_angle: 0.0,
+                    outer_cone_angle: std::f64::consts::FRAC_PI_4,
+                });
+                Light::Spot {
+                    name,
+                    color,
+                    intensity,
+                    range,
+                    inner_cone_angle: spot.inner_cone_angle,
+                    outer_cone_angle: spot.outer_cone_angle,
+                }
+            }
+            _ => return Err(s.err_enum(&type_)),
+        })
+    }
+}
+
+impl Light {
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            Light::Directional { name, .. }
+            | Light::Point { name, .. }
+            | Light::Spot { name, .. } => name.as_deref(),
+        }
+    }
+
+    pub fn color(&self) -> [f64; 3] {
+        match self {
+            Light::Directional { color, .. }
+            | Light::Point { color, .. }
+            | Light::Spot { color, .. } => *color,
+        }
+    }
+
+    pub fn intensity(&self) -> f64 {
+        match self {
+            Light::Directional { intensity, .. }
+            | Light::Point { intensity, .. }
+            | Light::Spot { intensity, .. } => *intensity,
+        }
+    }
+
+    /// None for directional lights and lights with infinite range.
+    pub fn range(&self) -> Option<f64> {
+        match self {
+            Light::Directional { .. } => None,
+            Light::Point { range, .. } | Light::Spot { range, .. } => *range,
+        }
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -45,20 +417,33 @@ pub enum ComponentType {
     UnsignedInt,
     Float,
 }
-impl From<&u32> for ComponentType {
-    fn from(n: &u32) -> ComponentType {
-        match *n {
-            5120 => ComponentType::Byte,
-            5121 => ComponentType::UnsignedByte,
-            5122 => ComponentType::Short,
-            5123 => ComponentType::UnsignedShort,
-            5125 => ComponentType::UnsignedInt,
-            5126 => ComponentType::Float,
-            x => panic!("Not a ComponentType u32!: {x}"),
+impl TryFrom<u32> for ComponentType {
+    type Error = u32;
+
+    fn try_from(n: u32) -> Result<ComponentType, u32> {
+        match n {
+            5120 => Ok(ComponentType::Byte),
+            5121 => Ok(ComponentType::UnsignedByte),
+            5122 => Ok(ComponentType::Short),
+            5123 => Ok(ComponentType::UnsignedShort),
+            5125 => Ok(ComponentType::UnsignedInt),
+            5126 => Ok(ComponentType::Float),
+            x => Err(x),
         }
     }
 }
 
+// an unknown componentType is a parse error, not a panic
+impl DeJson for ComponentType {
+    fn de_json(
+        s: &mut nanoserde::DeJsonState,
+        i: &mut std::str::Chars,
+    ) -> Result<ComponentType, nanoserde::DeJsonErr> {
+        let n = u32::de_json(s, i)?;
+        ComponentType::try_from(n).map_err(|n| s.err_range(&format!("componentType {n}")))
+    }
+}
+
 impl ComponentType {
     pub fn byte_size(&self) -> usize {
         use ComponentType::*;
@@ -80,7 +465,6 @@ pub struct Accessor {
     #[nserde(default = 0)]
     pub byte_offset: usize,
     #[nserde(rename = "componentType")]
-    #[nserde(proxy = "u32")]
     pub component_type: ComponentType,
     #[nserde(default = "false")]
     pub normalized: bool,
@@ -91,6 +475,32 @@ pub struct Accessor {
     pub name: Option<String>,
     #[nserde(rename = "type")]
     pub type_: Option<String>,
+    /// Extensions of `extensions::Registry`, set by `Gltf::from_json_with_extensions`.
+    #[nserde(skip)]
+    pub custom_extensions: Option<CustomExtensions>,
+}
+
+impl Accessor {
+    /// Amount of components in a single element: 1 for SCALAR, 3 for VEC3, 16 for MAT4 etc.
+    ///
+    /// Will panic on an unknown or missing `type`, see `checked_components`.
+    pub fn components(&self) -> usize {
+        self.checked_components()
+            .unwrap_or_else(|| panic!("Not an accessor type! {:?}", self.type_))
+    }
+
+    /// `components`, None on an unknown or missing `type`.
+    pub fn checked_components(&self) -> Option<usize> {
+        Some(match self.type_.as_deref()? {
+            "SCALAR" => 1,
+            "VEC2" => 2,
+            "VEC3" => 3,
+            "VEC4" | "MAT2" => 4,
+            "MAT3" => 9,
+            "MAT4" => 16,
+            _ => return None,
+        })
+    }
 }
 
 #[derive(DeJson, PartialEq, Debug)]
@@ -98,24 +508,89 @@ pub struct SparseIndices {
     #[nserde(rename = "bufferView")]
     pub buffer_view: usize,
     #[nserde(rename = "byteOffset")]
-    pub byte_fffset: usize,
+    #[nserde(default = 0)]
+    pub byte_offset: usize,
     #[nserde(rename = "componentType")]
-    pub component_type: u32,
+    pub component_type: ComponentType,
 }
 
 #[derive(DeJson, PartialEq, Debug)]
-struct SparseValues {
+pub struct SparseValues {
     #[nserde(rename = "bufferView")]
     pub buffer_view: usize,
     #[nserde(rename = "byteOffset")]
+    #[nserde(default = 0)]
     pub byte_offset: usize,
 }
 
 #[derive(DeJson, PartialEq, Debug)]
 pub struct Sparse {
-    count: usize,
-    indices: SparseIndices,
-    values: SparseValues,
+    pub count: usize,
+    pub indices: SparseIndices,
+    pub values: SparseValues,
+}
+
+#[derive(DeJson, PartialEq, Debug)]
+pub struct Animation {
+    pub channels: Vec<AnimationChannel>,
+    pub samplers: Vec<AnimationSampler>,
+    pub name: Option<String>,
+    pub extensions: Option<AnimationExtensions>,
+    #[nserde(skip)]
+    pub custom_extensions: Option<CustomExtensions>,
+}
+
+#[derive(DeJson, PartialEq, Debug)]
+pub struct AnimationExtensions {
+    #[nserde(rename = "KHR_xmp_json_ld")]
+    pub khr_xmp_json_ld: Option<XmpPacket>,
+}
+
+#[derive(DeJson, PartialEq, Debug)]
+pub struct AnimationChannel {
+    /// Index into the animation's `samplers`.
+    pub sampler: usize,
+    pub target: AnimationTarget,
+}
+
+#[derive(DeJson, PartialEq, Debug)]
+pub struct AnimationTarget {
+    pub node: Option<usize>,
+    /// "translation", "rotation", "scale", "weights" or "pointer" for KHR_animation_pointer.
+    pub path: String,
+    pub extensions: Option<AnimationTargetExtensions>,
+}
+
+impl AnimationTarget {
+    /// KHR_animation_pointer JSON pointer of the animated property.
+    pub fn pointer(&self) -> Option<&str> {
+        self.extensions
+            .as_ref()
+            .and_then(|e| e.khr_animation_pointer.as_ref())
+            .map(|e| e.pointer.as_str())
+    }
+}
+
+#[derive(DeJson, PartialEq, Debug)]
+pub struct AnimationTargetExtensions {
+    #[nserde(rename = "KHR_animation_pointer")]
+    pub khr_animation_pointer: Option<AnimationPointer>,
+}
+
+#[derive(DeJson, PartialEq, Debug)]
+pub struct AnimationPointer {
+    pub pointer: String,
+}
+
+#[derive(DeJson, PartialEq, Debug)]
+pub struct AnimationSampler {
+    /// Keyframe times accessor.
+    pub input: usize,
+    /// "LINEAR", "STEP" or "CUBICSPLINE".
+    #[nserde(default = "LINEAR")]
+    pub interpolation: String,
+    /// Keyframe values accessor.
+    pub output: usize,
 }
 
 #[derive(DeJson, PartialEq, Debug)]
@@ -125,25 +600,61 @@ pub struct Asset {
     pub version: String,
     #[nserde(rename = "minVersion")]
     pub min_version: Option<String>,
+    pub extensions: Option<AssetExtensions>,
+}
+
+#[derive(DeJson, PartialEq, Debug)]
+pub struct AssetExtensions {
+    #[nserde(rename = "KHR_xmp_json_ld")]
+    pub khr_xmp_json_ld: Option<XmpPacket>,
 }
 
 #[derive(DeJson, PartialEq)]
 pub struct Buffer {
-    pub uri: String,
+    /// None for GLB binary chunk and EXT_meshopt_compression fallback buffers.
+    pub uri: Option<String>,
     #[nserde(rename = "byteLength")]
     pub byte_length: usize,
     pub name: Option<String>,
+    pub extensions: Option<BufferExtensions>,
+    /// Extensions of `extensions::Registry`, set by `Gltf::from_json_with_extensions`.
+    #[nserde(skip)]
+    pub custom_extensions: Option<CustomExtensions>,
 }
 impl fmt::Debug for Buffer {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Buffer")
-            .field("uri", &debug_trim_string(&self.uri))
+            .field("uri", &self.uri.as_ref().map(|s| debug_trim_string(s)))
             .field("byte_length", &self.byte_length)
             .field("name", &self.name)
+            .field("extensions", &self.extensions)
+            .field("custom_extensions", &self.custom_extensions)
             .finish()
     }
 }
 
+impl Buffer {
+    /// EXT_meshopt_compression fallback buffer, it may have no data at all.
+    pub fn is_fallback(&self) -> bool {
+        self.extensions
+            .as_ref()
+            .and_then(|e| e.ext_meshopt_compression.as_ref())
+            .is_some_and(|e| e.fallback)
+    }
+}
+
+#[derive(DeJson, PartialEq, Debug)]
+pub struct BufferExtensions {
+    #[nserde(rename = "EXT_meshopt_compression")]
+    pub ext_meshopt_compression: Option<MeshoptBuffer>,
+}
+
+#[derive(DeJson, PartialEq, Debug)]
+pub struct MeshoptBuffer {
+    #[nserde(default)]
+    pub fallback: bool,
+}
+
 #[derive(PartialEq, Debug)]
 pub enum BufferViewTarget {
     ArrayBuffer,
@@ -167,10 +678,48 @@ pub struct BufferView {
     pub byte_offset: usize,
     #[nserde(rename = "byteLength")]
     pub byte_length: usize,
-    pub stride: Option<usize>,
+    #[nserde(rename = "byteStride")]
+    pub byte_stride: Option<usize>,
     #[nserde(proxy = "u32")]
     pub target: Option<BufferViewTarget>,
     pub name: Option<String>,
+    pub extensions: Option<BufferViewExtensions>,
+    /// Extensions of `extensions::Registry`, set by `Gltf::from_json_with_extensions`.
+    #[nserde(skip)]
+    pub custom_extensions: Option<CustomExtensions>,
+}
+
+impl BufferView {
+    pub fn meshopt_compression(&self) -> Option<&MeshoptCompression> {
+        self.extensions
+            .as_ref()
+            .and_then(|e| e.ext_meshopt_compression.as_ref())
+    }
+}
+
+#[derive(DeJson, PartialEq, Debug)]
+pub struct BufferViewExtensions {
+    #[nserde(rename = "EXT_meshopt_compression")]
+    pub ext_meshopt_compression: Option<MeshoptCompression>,
+}
+
+/// Compressed data for the buffer view, decoding it gives `BufferView::byte_length` bytes.
+#[derive(DeJson, PartialEq, Debug)]
+pub struct MeshoptCompression {
+    pub buffer: usize,
+    #[nserde(rename = "byteOffset")]
+    #[nserde(default)]
+    pub byte_offset: usize,
+    #[nserde(rename = "byteLength")]
+    pub byte_length: usize,
+    #[nserde(rename = "byteStride")]
+    pub byte_stride: usize,
+    pub count: usize,
+    /// "ATTRIBUTES", "TRIANGLES" or "INDICES".
+    pub mode: String,
+    /// "NONE", "OCTAHEDRAL", "QUATERNION" or "EXPONENTIAL".
+    #[nserde(default = "NONE")]
+    pub filter: String,
 }
 
 #[derive(DeJson, PartialEq)]
@@ -181,6 +730,15 @@ pub struct Image {
     #[nserde(rename = "bufferView")]
     pub buffer_view: Option<usize>,
     pub name: Option<String>,
+    pub extensions: Option<ImageExtensions>,
+    #[nserde(skip)]
+    pub custom_extensions: Option<CustomExtensions>,
+}
+
+#[derive(DeJson, PartialEq, Debug)]
+pub struct ImageExtensions {
+    #[nserde(rename = "KHR_xmp_json_ld")]
+    pub khr_xmp_json_ld: Option<XmpPacket>,
 }
 
 impl fmt::Debug for Image {
@@ -210,12 +768,45 @@ pub struct PBRMetallicRoughness {
     pub metallic_roughness_texture: Option<MetallicRoughnessTexture>,
 }
 
+impl Default for PBRMetallicRoughness {
+    fn default() -> PBRMetallicRoughness {
+        PBRMetallicRoughness {
+            base_color_factor: [1.0, 1.0, 1.0, 1.0],
+            base_color_texture: None,
+            metallic_factor: 1.0,
+            roughness_factor: 1.0,
+            metallic_roughness_texture: None,
+        }
+    }
+}
+
+/// KHR_texture_transform, uv offset/rotation/scale for a single texture reference.
+#[derive(DeJson, PartialEq, Debug)]
+pub struct TextureTransform {
+    #[nserde(default = "[0.0, 0.0]")]
+    pub offset: [f64; 2],
+    #[nserde(default = 0.0)]
+    pub rotation: f64,
+    #[nserde(default = "[1.0, 1.0]")]
+    pub scale: [f64; 2],
+    /// Overrides the texture reference's texCoord when present.
+    #[nserde(rename = "texCoord")]
+    pub tex_coord: Option<usize>,
+}
+
+#[derive(DeJson, PartialEq, Debug)]
+pub struct TextureInfoExtensions {
+    #[nserde(rename = "KHR_texture_transform")]
+    pub khr_texture_transform: Option<TextureTransform>,
+}
+
 #[derive(DeJson, PartialEq, Debug)]
 pub struct BaseColorTexture {
     pub index: usize,
     #[nserde(rename = "texCoord")]
     #[nserde(default = 0)]
     pub tex_coord: usize,
+    pub extensions: Option<TextureInfoExtensions>,
 }
 
 #[derive(DeJson, PartialEq, Debug)]
@@ -226,6 +817,7 @@ pub struct NormalTexture {
     pub tex_coord: usize,
     #[nserde(default = 1.0)]
     pub scale: f64,
+    pub extensions: Option<TextureInfoExtensions>,
 }
 
 #[derive(DeJson, PartialEq, Debug)]
@@ -236,6 +828,7 @@ pub struct OcclusionTexture {
     pub tex_coord: usize,
     #[nserde(default = 1.0)]
     pub strength: f64,
+    pub extensions: Option<TextureInfoExtensions>,
 }
 
 #[derive(DeJson, PartialEq, Debug)]
@@ -244,6 +837,7 @@ pub struct EmissiveTexture {
     #[nserde(rename = "texCoord")]
     #[nserde(default = 0)]
     pub tex_coord: usize,
+    pub extensions: Option<TextureInfoExtensions>,
 }
 
 #[derive(DeJson, PartialEq, Debug)]
@@ -252,12 +846,190 @@ pub struct MetallicRoughnessTexture {
     #[nserde(rename = "texCoord")]
     #[nserde(default = 0)]
     pub tex_coord: usize,
+    pub extensions: Option<TextureInfoExtensions>,
+}
+
+/// Texture reference used by the material extensions.
+#[derive(DeJson, PartialEq, Debug)]
+pub struct TextureInfo {
+    pub index: usize,
+    #[nserde(rename = "texCoord")]
+    #[nserde(default = 0)]
+    pub tex_coord: usize,
+    pub extensions: Option<TextureInfoExtensions>,
+}
+
+#[derive(DeJson, PartialEq, Debug)]
+pub struct MaterialsEmissiveStrength {
+    #[nserde(rename = "emissiveStrength")]
+    #[nserde(default = 1.0)]
+    pub emissive_strength: f64,
+}
+
+#[derive(DeJson, PartialEq, Debug)]
+pub struct MaterialsClearcoat {
+    #[nserde(rename = "clearcoatFactor")]
+    #[nserde(default = 0.0)]
+    pub clearcoat_factor: f64,
+    #[nserde(rename = "clearcoatTexture")]
+    pub clearcoat_texture: Option<TextureInfo>,
+    #[nserde(rename = "clearcoatRoughnessFactor")]
+    #[nserde(default = 0.0)]
+    pub clearcoat_roughness_factor: f64,
+    #[nserde(rename = "clearcoatRoughnessTexture")]
+    pub clearcoat_roughness_texture: Option<TextureInfo>,
+    #[nserde(rename = "clearcoatNormalTexture")]
+    pub clearcoat_normal_texture: Option<NormalTexture>,
+}
+
+#[derive(DeJson, PartialEq, Debug)]
+pub struct MaterialsSheen {
+    #[nserde(rename = "sheenColorFactor")]
+    #[nserde(default = "[0.0, 0.0, 0.0]")]
+    pub sheen_color_factor: [f64; 3],
+    #[nserde(rename = "sheenColorTexture")]
+    pub sheen_color_texture: Option<TextureInfo>,
+    #[nserde(rename = "sheenRoughnessFactor")]
+    #[nserde(default = 0.0)]
+    pub sheen_roughness_factor: f64,
+    #[nserde(rename = "sheenRoughnessTexture")]
+    pub sheen_roughness_texture: Option<TextureInfo>,
+}
+
+#[derive(DeJson, PartialEq, Debug)]
+pub struct MaterialsTransmission {
+    #[nserde(rename = "transmissionFactor")]
+    #[nserde(default = 0.0)]
+    pub transmission_factor: f64,
+    #[nserde(rename = "transmissionTexture")]
+    pub transmission_texture: Option<TextureInfo>,
+}
+
+#[derive(DeJson, PartialEq, Debug)]
+pub struct MaterialsVolume {
+    #[nserde(rename = "thicknessFactor")]
+    #[nserde(default = 0.0)]
+    pub thickness_factor: f64,
+    #[nserde(rename = "thicknessTexture")]
+    pub thickness_texture: Option<TextureInfo>,
+    /// Infinite when not specified: no attenuation at all.
+    #[nserde(rename = "attenuationDistance")]
+    #[nserde(default = "f64::INFINITY")]
+    pub attenuation_distance: f64,
+    #[nserde(rename = "attenuationColor")]
+    #[nserde(default = "[1.0, 1.0, 1.0]")]
+    pub attenuation_color: [f64; 3],
+}
+
+#[derive(DeJson, PartialEq, Debug)]
+pub struct MaterialsIor {
+    #[nserde(default = 1.5)]
+    pub ior: f64,
+}
+
+#[derive(DeJson, PartialEq, Debug)]
+pub struct MaterialsSpecular {
+    #[nserde(rename = "specularFactor")]
+    #[nserde(default = 1.0)]
+    pub specular_factor: f64,
+    #[nserde(rename = "specularTexture")]
+    pub specular_texture: Option<TextureInfo>,
+    #[nserde(rename = "specularColorFactor")]
+    #[nserde(default = "[1.0, 1.0, 1.0]")]
+    pub specular_color_factor: [f64; 3],
+    #[nserde(rename = "specularColorTexture")]
+    pub specular_color_texture: Option<TextureInfo>,
+}
+
+#[derive(DeJson, PartialEq, Debug)]
+pub struct MaterialsIridescence {
+    #[nserde(rename = "iridescenceFactor")]
+    #[nserde(default = 0.0)]
+    pub iridescence_factor: f64,
+    #[nserde(rename = "iridescenceTexture")]
+    pub iridescence_texture: Option<TextureInfo>,
+    #[nserde(rename = "iridescenceIor")]
+    #[nserde(default = 1.3)]
+    pub iridescence_ior: f64,
+    #[nserde(rename = "iridescenceThicknessMinimum")]
+    #[nserde(default = 100.0)]
+    pub iridescence_thickness_minimum: f64,
+    #[nserde(rename = "iridescenceThicknessMaximum")]
+    #[nserde(default = 400.0)]
+    pub iridescence_thickness_maximum: f64,
+    #[nserde(rename = "iridescenceThicknessTexture")]
+    pub iridescence_thickness_texture: Option<TextureInfo>,
+}
+
+#[derive(DeJson, PartialEq, Debug)]
+pub struct MaterialsAnisotropy {
+    #[nserde(rename = "anisotropyStrength")]
+    #[nserde(default = 0.0)]
+    pub anisotropy_strength: f64,
+    #[nserde(rename = "anisotropyRotation")]
+    #[nserde(default = 0.0)]
+    pub anisotropy_rotation: f64,
+    #[nserde(rename = "anisotropyTexture")]
+    pub anisotropy_texture: Option<TextureInfo>,
+}
+
+/// Archived KHR_materials_pbrSpecularGlossiness, see `spec_gloss` for the conversion.
+#[derive(DeJson, PartialEq, Debug)]
+pub struct MaterialsPbrSpecularGlossiness {
+    #[nserde(rename = "diffuseFactor")]
+    #[nserde(default = "[1.0, 1.0, 1.0, 1.0]")]
+    pub diffuse_factor: [f64; 4],
+    #[nserde(rename = "diffuseTexture")]
+    pub diffuse_texture: Option<TextureInfo>,
+    #[nserde(rename = "specularFactor")]
+    #[nserde(default = "[1.0, 1.0, 1.0]")]
+    pub specular_factor: [f64; 3],
+    #[nserde(rename = "glossinessFactor")]
+    #[nserde(default = 1.0)]
+    pub glossiness_factor: f64,
+    #[nserde(rename = "specularGlossinessTexture")]
+    pub specular_glossiness_texture: Option<TextureInfo>,
+}
+
+/// KHR_materials_unlit has no properties, its presence is the whole point.
+#[derive(DeJson, PartialEq, Debug)]
+pub struct MaterialsUnlit {}
+
+#[derive(DeJson, PartialEq, Debug)]
+pub struct MaterialExtensions {
+    #[nserde(rename = "KHR_materials_emissive_strength")]
+    pub emissive_strength: Option<MaterialsEmissiveStrength>,
+    #[nserde(rename = "KHR_materials_clearcoat")]
+    pub clearcoat: Option<MaterialsClearcoat>,
+    #[nserde(rename = "KHR_materials_sheen")]
+    pub sheen: Option<MaterialsSheen>,
+    #[nserde(rename = "KHR_materials_transmission")]
+    pub transmission: Option<MaterialsTransmission>,
+    #[nserde(rename = "KHR_materials_volume")]
+    pub volume: Option<MaterialsVolume>,
+    #[nserde(rename = "KHR_materials_ior")]
+    pub ior: Option<MaterialsIor>,
+    #[nserde(rename = "KHR_materials_specular")]
+    pub specular: Option<MaterialsSpecular>,
+    #[nserde(rename = "KHR_materials_iridescence")]
+    pub iridescence: Option<MaterialsIridescence>,
+    #[nserde(rename = "KHR_materials_anisotropy")]
+    pub anisotropy: Option<MaterialsAnisotropy>,
+    #[nserde(rename = "KHR_materials_unlit")]
+    pub unlit: Option<MaterialsUnlit>,
+    #[nserde(rename = "KHR_materials_pbrSpecularGlossiness")]
+    pub pbr_specular_glossiness: Option<MaterialsPbrSpecularGlossiness>,
+    #[nserde(rename = "MSFT_lod")]
+    pub msft_lod: Option<Lod>,
+    #[nserde(rename = "KHR_xmp_json_ld")]
+    pub khr_xmp_json_ld: Option<XmpPacket>,
 }
 
 #[derive(DeJson, PartialEq, Debug)]
 pub struct Material {
     pub name: Option<String>,
     #[nserde(rename = "pbrMetallicRoughness")]
+    #[nserde(default)]
     pub pbr_metallic_roughness: PBRMetallicRoughness,
     #[nserde(rename = "normalTexture")]
     pub normal_texture: Option<NormalTexture>,
@@ -277,6 +1049,188 @@ pub struct Material {
     #[nserde(rename = "doubleSided")]
     #[nserde(default = "false")]
     pub double_sided: bool,
+    pub extensions: Option<MaterialExtensions>,
+    pub extras: Option<Extras>,
+    #[nserde(skip)]
+    pub custom_extensions: Option<CustomExtensions>,
+}
+
+/// Any of the material's texture references, see `Material::textures`.
+#[derive(Debug, PartialEq)]
+pub struct TextureRef<'a> {
+    /// JSON pointer of the reference relative to the material, like "/normalTexture".
+    pub path: &'static str,
+    pub index: usize,
+    pub tex_coord: usize,
+    pub extensions: Option<&'a TextureInfoExtensions>,
+}
+
+impl TextureRef<'_> {
+    pub fn transform(&self) -> Option<&TextureTransform> {
+        self.extensions?.khr_texture_transform.as_ref()
+    }
+
+    /// TEXCOORD_n set to sample with, KHR_texture_transform may override `tex_coord`.
+    pub fn effective_tex_coord(&self) -> usize {
+        self.transform()
+            .and_then(|transform| transform.tex_coord)
+            .unwrap_or(self.tex_coord)
+    }
+}
+
+impl Material {
+    pub fn lod(&self) -> Option<&Lod> {
+        self.extensions.as_ref().and_then(|e| e.msft_lod.as_ref())
+    }
+
+    pub fn is_unlit(&self) -> bool {
+        self.extensions.as_ref().is_some_and(|e| e.unlit.is_some())
+    }
+
+    /// KHR_materials_emissive_strength multiplier for `emissive_factor`, 1.0 without the extension.
+    pub fn emissive_strength(&self) -> f64 {
+        self.extensions
+            .as_ref()
+            .and_then(|e| e.emissive_strength.as_ref())
+            .map_or(1.0, |e| e.emissive_strength)
+    }
+
+    /// KHR_materials_ior index of refraction, 1.5 without the extension.
+    pub fn ior(&self) -> f64 {
+        self.extensions
+            .as_ref()
+            .and_then(|e| e.ior.as_ref())
+            .map_or(1.5, |e| e.ior)
+    }
+
+    /// All the texture references present in the material.
+    pub fn textures<'a>(&'a self) -> Vec<TextureRef<'a>> {
+        let pbr = &self.pbr_metallic_roughness;
+        let mut res = vec![];
+        let mut push = |path, index, tex_coord, extensions: &'a Option<TextureInfoExtensions>| {
+            res.push(TextureRef {
+                path,
+                index,
+                tex_coord,
+                extensions: extensions.as_ref(),
+            })
+        };
+        if let Some(t) = &pbr.base_color_texture {
+            push(
+                "/pbrMetallicRoughness/baseColorTexture",
+                t.index,
+                t.tex_coord,
+                &t.extensions,
+            );
+        }
+        if let Some(t) = &pbr.metallic_roughness_texture {
+            push(
+                "/pbrMetallicRoughness/metallicRoughnessTexture",
+                t.index,
+                t.tex_coord,
+                &t.extensions,
+            );
+        }
+        if let Some(t) = &self.normal_texture {
+            push("/normalTexture", t.index, t.tex_coord, &t.extensions);
+        }
+        if let Some(t) = &self.occlusion_texture {
+            push("/occlusionTexture", t.index, t.tex_coord, &t.extensions);
+        }
+        if let Some(t) = &self.emissive_texture {
+            push("/emissiveTexture", t.index, t.tex_coord, &t.extensions);
+        }
+
+        let Some(extensions) = &self.extensions else {
+            return res;
+        };
+        let mut push_info = |path, texture: &'a Option<TextureInfo>| {
+            if let Some(t) = texture {
+                push(path, t.index, t.tex_coord, &t.extensions);
+            }
+        };
+        if let Some(e) = &extensions.clearcoat {
+            push_info(
+                "/extensions/KHR_materials_clearcoat/clearcoatTexture",
+                &e.clearcoat_texture,
+            );
+            push_info(
+                "/extensions/KHR_materials_clearcoat/clearcoatRoughnessTexture",
+                &e.clearcoat_roughness_texture,
+            );
+        }
+        if let Some(e) = &extensions.sheen {
+            push_info(
+                "/extensions/KHR_materials_sheen/sheenColorTexture",
+                &e.sheen_color_texture,
+            );
+            push_info(
+                "/extensions/KHR_materials_sheen/sheenRoughnessTexture",
+                &e.sheen_roughness_texture,
+            );
+        }
+        if let Some(e) = &extensions.transmission {
+            push_info(
+                "/extensions/KHR_materials_transmission/transmissionTexture",
+                &e.transmission_texture,
+            );
+        }
+        if let Some(e) = &extensions.volume {
+            push_info(
+                "/extensions/KHR_materials_volume/thicknessTexture",
+                &e.thickness_texture,
+            );
+        }
+        if let Some(e) = &extensions.specular {
+            push_info(
+                "/extensions/KHR_materials_specular/specularTexture",
+                &e.specular_texture,
+            );
+            push_info(
+                "/extensions/KHR_materials_specular/specularColorTexture",
+                &e.specular_color_texture,
+            );
+        }
+        if let Some(e) = &extensions.iridescence {
+            push_info(
+                "/extensions/KHR_materials_iridescence/iridescenceTexture",
+                &e.iridescence_texture,
+            );
+            push_info(
+                "/extensions/KHR_materials_iridescence/iridescenceThicknessTexture",
+                &e.iridescence_thickness_texture,
+            );
+        }
+        if let Some(e) = &extensions.pbr_specular_glossiness {
+            push_info(
+                "/extensions/KHR_materials_pbrSpecularGlossiness/diffuseTexture",
+                &e.diffuse_texture,
+            );
+            push_info(
+                "/extensions/KHR_materials_pbrSpecularGlossiness/specularGlossinessTexture",
+                &e.specular_glossiness_texture,
+            );
+        }
+        if let Some(e) = &extensions.anisotropy {
+            push_info(
+                "/extensions/KHR_materials_anisotropy/anisotropyTexture",
+                &e.anisotropy_texture,
+            );
+        }
+        if let Some(t) = extensions
+            .clearcoat
+            .as_ref()
+            .and_then(|e| e.clearcoat_normal_texture.as_ref())
+        {
+            push(
+                "/extensions/KHR_materials_clearcoat/clearcoatNormalTexture",
+                t.index,
+                t.tex_coord,
+                &t.extensions,
+            );
+        }
+        res
+    }
 }
 
 #[derive(DeJson, PartialEq, Debug)]
@@ -285,9 +1239,18 @@ pub struct Mesh {
     pub primitives: Vec<Primitive>,
     pub weights: Option<Vec<f64>>,
     pub name: Option<String>,
+    pub extensions: Option<MeshExtensions>,
+    #[nserde(skip)]
+    pub custom_extensions: Option<CustomExtensions>,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(DeJson, PartialEq, Debug)]
+pub struct MeshExtensions {
+    #[nserde(rename = "KHR_xmp_json_ld")]
+    pub khr_xmp_json_ld: Option<XmpPacket>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 #[repr(u32)]
 pub enum PrimitiveMode {
     Points,
@@ -320,7 +1283,59 @@ pub struct Primitive {
     pub material: Option<usize>,
     #[nserde(proxy = "u32")]
     pub mode: Option<PrimitiveMode>,
-    pub targets: Option<HashMap<String, usize>>,
+    pub targets: Option<Vec<HashMap<String, usize>>>,
+    pub extensions: Option<PrimitiveExtensions>,
+    #[nserde(skip)]
+    pub custom_extensions: Option<CustomExtensions>,
+}
+
+impl Primitive {
+    pub fn draco_mesh_compression(&self) -> Option<&DracoMeshCompression> {
+        self.extensions
+            .as_ref()
+            .and_then(|e| e.khr_draco_mesh_compression.as_ref())
+    }
+
+    pub fn variant_mappings(&self) -> &[VariantMapping] {
+        self.extensions
+            .as_ref()
+            .and_then(|e| e.khr_materials_variants.as_ref())
+            .map_or(&[], |e| &e.mappings)
+    }
+}
+
+#[derive(DeJson, PartialEq, Debug)]
+pub struct PrimitiveExtensions {
+    #[nserde(rename = "KHR_draco_mesh_compression")]
+    pub khr_draco_mesh_compression: Option<DracoMeshCompression>,
+    #[nserde(rename = "KHR_materials_variants")]
+    pub khr_materials_variants: Option<PrimitiveVariants>,
+}
+
+#[derive(DeJson, PartialEq, Debug)]
+pub struct PrimitiveVariants {
+    #[nserde(default)]
+    pub mappings: Vec<VariantMapping>,
+}
+
+/// Material the primitive uses for the listed variants.
+#[derive(DeJson, PartialEq, Debug)]
+pub struct VariantMapping {
+    pub material: usize,
+    /// Indices into the root KHR_materials_variants `variants`.
+    #[nserde(default)]
+    pub variants: Vec<usize>,
+    pub name: Option<String>,
+}
+
+/// Draco compressed geometry of the primitive. The primitive's accessors describe the
+/// decoded data and usually have no `buffer_view`.
+#[derive(DeJson, PartialEq, Debug)]
+pub struct DracoMeshCompression {
+    #[nserde(rename = "bufferView")]
+    pub buffer_view: usize,
+    /// Attribute semantic to the draco attribute unique id.
+    pub attributes: HashMap<String, u32>,
 }
 
 #[derive(DeJson, PartialEq, Debug)]
@@ -336,6 +1351,58 @@ pub struct Node {
     pub translation: Option<[f64; 3]>,
     pub weights: Option<Vec<f64>>,
     pub name: Option<String>,
+    pub extensions: Option<NodeExtensions>,
+    pub extras: Option<Extras>,
+    #[nserde(skip)]
+    pub custom_extensions: Option<CustomExtensions>,
+}
+
+impl Node {
+    pub fn gpu_instancing(&self) -> Option<&MeshGpuInstancing> {
+        self.extensions
+            .as_ref()
+            .and_then(|e| e.ext_mesh_gpu_instancing.as_ref())
+    }
+
+    pub fn lod(&self) -> Option<&Lod> {
+        self.extensions.as_ref().and_then(|e| e.msft_lod.as_ref())
+    }
+}
+
+#[derive(DeJson, PartialEq, Debug)]
+pub struct NodeExtensions {
+    #[nserde(rename = "KHR_lights_punctual")]
+    pub khr_lights_punctual: Option<NodeLight>,
+    #[nserde(rename = "EXT_mesh_gpu_instancing")]
+    pub ext_mesh_gpu_instancing: Option<MeshGpuInstancing>,
+    #[nserde(rename = "MSFT_lod")]
+    pub msft_lod: Option<Lod>,
+    #[nserde(rename = "KHR_xmp_json_ld")]
+    pub khr_xmp_json_ld: Option<XmpPacket>,
+}
+
+/// MSFT_lod, lower detail replacements of the node or material,
+/// ordered from the highest detail to the lowest.
+#[derive(DeJson, PartialEq, Debug)]
+pub struct Lod {
+    /// Indices into `gltf.nodes` or `gltf.materials`, the same array as the object itself.
+    #[nserde(default)]
+    pub ids: Vec<usize>,
+}
+
+/// Per-instance accessors of the node's mesh: TRANSLATION, ROTATION, SCALE
+/// and application specific ones starting with an underscore, like `_ID`.
+/// All of them have the same count, the amount of instances.
+#[derive(DeJson, PartialEq, Debug)]
+pub struct MeshGpuInstancing {
+    #[nserde(default)]
+    pub attributes: HashMap<String, usize>,
+}
+
+#[derive(DeJson, PartialEq, Debug)]
+pub struct NodeLight {
+    /// Index into the root KHR_lights_punctual `lights`.
+    pub light: usize,
 }
 
 #[derive(Debug, PartialEq)]
@@ -393,17 +1460,64 @@ pub struct Sampler {
     #[nserde(rename = "wrapT")]
     pub wrap_t: Option<WrappingMode>,
     pub name: Option<String>,
+    /// Extensions of `extensions::Registry`, set by `Gltf::from_json_with_extensions`.
+    #[nserde(skip)]
+    pub custom_extensions: Option<CustomExtensions>,
 }
 
 #[derive(DeJson, PartialEq, Debug)]
 pub struct Scene {
     pub nodes: Vec<usize>,
     pub name: Option<String>,
+    pub extensions: Option<SceneExtensions>,
+    #[nserde(skip)]
+    pub custom_extensions: Option<CustomExtensions>,
+}
+
+impl Scene {
+    /// Index into the root EXT_lights_image_based `lights`.
+    pub fn image_based_light(&self) -> Option<usize> {
+        self.extensions
+            .as_ref()
+            .and_then(|e| e.ext_lights_image_based.as_ref())
+            .map(|e| e.light)
+    }
+}
+
+#[derive(DeJson, PartialEq, Debug)]
+pub struct SceneExtensions {
+    #[nserde(rename = "EXT_lights_image_based")]
+    pub ext_lights_image_based: Option<SceneImageBasedLight>,
+}
+
+#[derive(DeJson, PartialEq, Debug)]
+pub struct SceneImageBasedLight {
+    pub light: usize,
 }
 
 #[derive(DeJson, PartialEq, Debug)]
 pub struct Texture {
     pub sampler: Option<usize>,
+    /// PNG or JPEG image. May be missing when one of the extensions provides the image.
     pub source: Option<usize>,
     pub name: Option<String>,
+    pub extensions: Option<TextureExtensions>,
+    #[nserde(skip)]
+    pub custom_extensions: Option<CustomExtensions>,
+}
+
+#[derive(DeJson, PartialEq, Debug)]
+pub struct TextureExtensions {
+    #[nserde(rename = "KHR_texture_basisu")]
+    pub khr_texture_basisu: Option<TextureSource>,
+    #[nserde(rename = "EXT_texture_webp")]
+    pub ext_texture_webp: Option<TextureSource>,
+    #[nserde(rename = "EXT_texture_avif")]
+    pub ext_texture_avif: Option<TextureSource>,
+}
+
+/// Image in the extension's format, used instead of `Texture::source` when supported.
+#[derive(DeJson, PartialEq, Debug)]
+pub struct TextureSource {
+    pub source: usize,
 }
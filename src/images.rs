@@ -0,0 +1,133 @@
+//! Texture image selection for KHR_texture_basisu, EXT_texture_webp and EXT_texture_avif.
+//!
+//! A texture may reference several images of the same content in different formats:
+//! the extension ones and the `source` PNG or JPEG fallback. `texture_image` picks the
+//! first format of the caller's preference list the texture has an image for.
+
+use crate::gltf::{Image, Texture};
+use crate::Gltf;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImageFormat {
+    Ktx2,
+    WebP,
+    Avif,
+    Png,
+    Jpeg,
+}
+
+impl ImageFormat {
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            ImageFormat::Ktx2 => "image/ktx2",
+            ImageFormat::WebP => "image/webp",
+            ImageFormat::Avif => "image/avif",
+            ImageFormat::Png => "image/png",
+            ImageFormat::Jpeg => "image/jpeg",
+        }
+    }
+
+    pub fn from_mime_type(mime_type: &str) -> Option<ImageFormat> {
+        match mime_type {
+            "image/ktx2" => Some(ImageFormat::Ktx2),
+            "image/webp" => Some(ImageFormat::WebP),
+            "image/avif" => Some(ImageFormat::Avif),
+            "image/png" => Some(ImageFormat::Png),
+            "image/jpeg" => Some(ImageFormat::Jpeg),
+            _ => None,
+        }
+    }
+
+    fn from_extension(extension: &str) -> Option<ImageFormat> {
+        match extension.to_ascii_lowercase().as_str() {
+            "ktx2" => Some(ImageFormat::Ktx2),
+            "webp" => Some(ImageFormat::WebP),
+            "avif" => Some(ImageFormat::Avif),
+            "png" => Some(ImageFormat::Png),
+            "jpg" | "jpeg" => Some(ImageFormat::Jpeg),
+            _ => None,
+        }
+    }
+}
+
+/// Image format from `mime_type`, the data URI media type or the file extension of the uri.
+/// None if nothing of those is known.
+pub fn image_format(image: &Image) -> Option<ImageFormat> {
+    if let Some(mime_type) = &image.mime_type {
+        return ImageFormat::from_mime_type(mime_type);
+    }
+    let uri = image.uri.as_deref()?;
+    if let Some(data) = uri.strip_prefix("data:") {
+        let media_type = data.split([';', ',']).next()?;
+        return ImageFormat::from_mime_type(media_type);
+    }
+    let path = uri.split(['?', '#']).next()?;
+    let (_, extension) = path.rsplit_once('.')?;
+    ImageFormat::from_extension(extension)
+}
+
+/// Selected image of a texture.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextureImage {
+    /// Index into `gltf.images`.
+    pub image: usize,
+    /// None for a `source` image of unknown format.
+    pub format: Option<ImageFormat>,
+}
+
+impl TextureImage {
+    pub fn mime_type(&self) -> Option<&'static str> {
+        self.format.map(|format| format.mime_type())
+    }
+}
+
+/// Every image the texture provides, extension ones first and `source` last.
+/// The format of the extension images is the one the extension defines.
+pub fn texture_images(gltf: &Gltf, texture: &Texture) -> Vec<TextureImage> {
+    let mut images = vec![];
+    if let Some(extensions) = &texture.extensions {
+        let sources = [
+            (&extensions.khr_texture_basisu, ImageFormat::Ktx2),
+            (&extensions.ext_texture_webp, ImageFormat::WebP),
+            (&extensions.ext_texture_avif, ImageFormat::Avif),
+        ];
+        for (source, format) in sources {
+            if let Some(source) = source {
+                images.push(TextureImage {
+                    image: source.source,
+                    format: Some(format),
+                });
+            }
+        }
+    }
+    if let Some(source) = texture.source {
+        images.push(TextureImage {
+            image: source,
+            format: gltf.images.get(source).and_then(image_format),
+        });
+    }
+    images
+}
+
+/// The texture's image in the first of the `supported` formats it has,
+/// `supported` is in the order of preference.
+///
+/// `source` of unknown format is the last resort when PNG or JPEG is supported,
+/// core glTF allows only those two there. None if nothing fits.
+pub fn texture_image(
+    gltf: &Gltf,
+    texture: usize,
+    supported: &[ImageFormat],
+) -> Option<TextureImage> {
+    let images = texture_images(gltf, &gltf.textures[texture]);
+    let preferred = supported
+        .iter()
+        .find_map(|format| images.iter().find(|image| image.format == Some(*format)));
+    if let Some(image) = preferred {
+        return Some(*image);
+    }
+    if supported.contains(&ImageFormat::Png) || supported.contains(&ImageFormat::Jpeg) {
+        return images.into_iter().find(|image| image.format.is_none());
+    }
+    None
+}
@@ -0,0 +1,99 @@
+//! EXT_mesh_gpu_instancing per-instance transforms.
+//!
+//! Instance transforms are applied before the node's world transform:
+//! `world = node_world * instance_trs`.
+//! Custom attributes, like `_ID`, are plain accessors, read them with `accessor::read_f32`
+//! or `accessor::read_u32` on the index `attribute` returns.
+
+use crate::math::{self, Mat4};
+use crate::{accessor, scene, Gltf};
+
+/// Instanced node placed in the scene.
+#[derive(Debug)]
+pub struct SceneInstances {
+    pub node: usize,
+    pub mesh: usize,
+    /// World transform of each instance.
+    pub transforms: Vec<Mat4>,
+}
+
+/// Accessor of the node's instance attribute: TRANSLATION, ROTATION, SCALE or a custom one.
+pub fn attribute(gltf: &Gltf, node: usize, semantic: &str) -> Option<usize> {
+    gltf.nodes[node]
+        .gpu_instancing()?
+        .attributes
+        .get(semantic)
+        .copied()
+}
+
+/// Amount of instances of the node, None if it is not instanced, refers to a missing
+/// accessor or its attributes don't have the same count.
+pub fn instance_count(gltf: &Gltf, node: usize) -> Option<usize> {
+    let instancing = gltf.nodes[node].gpu_instancing()?;
+    let mut counts = instancing
+        .attributes
+        .values()
+        .map(|&accessor| gltf.accessors.get(accessor).map(|a| a.count));
+    let count = counts.next()??;
+    counts.all(|c| c == Some(count)).then_some(count)
+}
+
+/// Instance transforms relative to the node, None if the node is not instanced,
+/// `instance_count` is None or TRANSLATION, ROTATION and SCALE aren't VEC3, VEC4 and VEC3.
+/// Missing attributes default to identity.
+pub fn instance_transforms(gltf: &Gltf, buffers: &[Vec<u8>], node: usize) -> Option<Vec<Mat4>> {
+    let count = instance_count(gltf, node)?;
+    let read = |semantic| attribute(gltf, node, semantic);
+    let layout = [("TRANSLATION", 3), ("ROTATION", 4), ("SCALE", 3)];
+    if layout.iter().any(|&(semantic, components)| {
+        read(semantic).is_some_and(|a| gltf.accessors[a].checked_components() != Some(components))
+    }) {
+        return None;
+    }
+    let translations = read("TRANSLATION").map(|a| accessor::read_vec3(gltf, buffers, a));
+    let rotations = read("ROTATION").map(|a| accessor::read_vec4(gltf, buffers, a));
+    let scales = read("SCALE").map(|a| accessor::read_vec3(gltf, buffers, a));
+
+    Some(
+        (0..count)
+            .map(|i| {
+                let t = translations.as_ref().map_or([0.0; 3], |t| t[i]);
+                let r = rotations.as_ref().map_or([0.0, 0.0, 0.0, 1.0], |r| r[i]);
+                let s = scales.as_ref().map_or([1.0; 3], |s| s[i]);
+                math::from_trs(t, r, s)
+            })
+            .collect(),
+    )
+}
+
+/// World transform of each instance of the node, None if the node is not instanced.
+pub fn instance_world_transforms(
+    gltf: &Gltf,
+    buffers: &[Vec<u8>],
+    node: usize,
+) -> Option<Vec<Mat4>> {
+    let world = scene::world_transforms(gltf)[node];
+    let instances = instance_transforms(gltf, buffers, node)?;
+    Some(instances.iter().map(|m| math::mul(&world, m)).collect())
+}
+
+/// Every instanced mesh node of the scene with its instances' world transforms.
+pub fn scene_instances(gltf: &Gltf, buffers: &[Vec<u8>], scene: usize) -> Vec<SceneInstances> {
+    let world = scene::world_transforms(gltf);
+
+    scene::scene_nodes(gltf, scene)
+        .into_iter()
+        .filter_map(|node| {
+            let mesh = gltf.nodes[node].mesh?;
+            let instances = instance_transforms(gltf, buffers, node)?;
+            Some(SceneInstances {
+                node,
+                mesh,
+                transforms: instances
+                    .iter()
+                    .map(|m| math::mul(&world[node], m))
+                    .collect(),
+            })
+        })
+        .collect()
+}
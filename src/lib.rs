@@ -1,7 +1,54 @@
 /// Raw json structs.
 pub mod gltf;
 
+/// Decoding accessors into plain vectors.
+pub mod accessor;
+/// Tangent generation for normal mapped primitives.
+pub mod tangents;
+/// Flat and smooth normals for primitives without NORMAL.
+pub mod normals;
+/// Interleaved or planar GPU-ready vertex and index buffers.
+pub mod mesh_builder;
+/// World transforms, node hierarchy traversal and flat draw lists.
+pub mod scene;
+/// Transform baking and static mesh merging.
+pub mod merge;
+/// Bounding boxes and spheres for primitives, nodes and scenes.
+pub mod bounds;
+/// Document validation with JSON pointer diagnostics.
+pub mod validate;
+/// KHR_texture_transform uv matrices and baking.
+pub mod texture_transform;
+/// Texture image selection across KHR_texture_basisu, EXT_texture_webp and EXT_texture_avif.
+pub mod images;
+/// KHR_materials_pbrSpecularGlossiness to metallic-roughness conversion.
+pub mod spec_gloss;
+/// KHR_materials_variants material switching.
+pub mod variants;
+/// KHR_lights_punctual lights placed in the scene.
+pub mod lights;
+/// EXT_lights_image_based environment lighting.
+pub mod environment;
+/// Animation sampling with KHR_animation_pointer targets.
+pub mod animation;
+/// EXT_mesh_gpu_instancing per-instance world transforms.
+pub mod instancing;
+/// MSFT_lod level selection by screen coverage.
+pub mod lod;
+/// Quadric error metric simplification for LOD generation.
+pub mod simplify;
+/// KHR_xmp_json_ld metadata packets.
+pub mod xmp;
+/// User defined vendor extensions.
+pub mod extensions;
+/// EXT_meshopt_compression buffer view decoders.
+pub mod meshopt;
+/// KHR_draco_mesh_compression decoder.
+#[cfg(feature = "draco")]
+pub mod draco;
+
 mod base64;
+mod math;
 
 pub use gltf::*;
 
@@ -9,6 +56,14 @@ impl gltf::Gltf {
     pub fn from_json(json: &str) -> Result<gltf::Gltf, nanoserde::DeJsonErr> {
         nanoserde::DeJson::deserialize_json(json)
     }
+
+    /// Parse the document together with the custom extensions of `registry`.
+    pub fn from_json_with_extensions(
+        json: &str,
+        registry: &extensions::Registry,
+    ) -> Result<gltf::Gltf, nanoserde::DeJsonErr> {
+        extensions::from_json(json, registry)
+    }
 }
 
 /// A few optional helpers to extract the data out of the parsed gltf.
@@ -47,6 +102,17 @@ pub mod utils {
             return UriData::Bytes(bytes);
         }
 
+        // KHR_texture_basisu, EXT_texture_webp and EXT_texture_avif images
+        for mime_type in ["image/ktx2", "image/webp", "image/avif"] {
+            if let Some(uri) = uri
+                .strip_prefix("data:")
+                .and_then(|uri| uri.strip_prefix(mime_type))
+                .and_then(|uri| uri.strip_prefix(";base64,"))
+            {
+                return UriData::Bytes(base64::decode(uri));
+            }
+        }
+
         unimplemented!()
     }
 
@@ -69,6 +135,9 @@ pub mod utils {
     ///
     /// Common attribute names are: TEXCOORD_*, POSITION, NORMAL
     ///
+    /// EXT_meshopt_compression views are not decoded here, see `meshopt::decompress`.
+    /// `accessor::AccessorView` does the same slicing with bounds and layout checks.
+    ///
     /// Will panic if gltf have sparse accessors present.
     /// (they are described here [glTF-Tutorials](https://github.com/KhronosGroup/glTF-Tutorials/blob/master/gltfTutorial/gltfTutorial_005_BuffersBufferViewsAccessors.md), but not yet implemented by nanogltf)
     pub fn attribute_bytes(gltf: &Gltf, attribute: usize) -> (usize, usize, usize) {
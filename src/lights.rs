@@ -0,0 +1,53 @@
+//! KHR_lights_punctual scene queries.
+
+use crate::math;
+use crate::{gltf::Light, scene, Gltf};
+
+/// A light placed in the scene.
+#[derive(Debug)]
+pub struct SceneLight<'a> {
+    pub node: usize,
+    /// Index into the root KHR_lights_punctual `lights`.
+    pub index: usize,
+    pub light: &'a Light,
+    /// World space position of the node.
+    pub position: [f32; 3],
+    /// World space, normalized direction the light shines along: node's -Z.
+    /// Meaningless for point lights, but still computed.
+    pub direction: [f32; 3],
+}
+
+/// All the document's lights.
+pub fn lights(gltf: &Gltf) -> &[Light] {
+    gltf.extensions
+        .as_ref()
+        .and_then(|e| e.khr_lights_punctual.as_ref())
+        .map_or(&[], |e| &e.lights)
+}
+
+/// Every light attached to the scene's nodes, with world space position and direction.
+/// Lights referencing missing indices are skipped.
+pub fn scene_lights(gltf: &Gltf, scene: usize) -> Vec<SceneLight<'_>> {
+    let lights = lights(gltf);
+    let world = scene::world_transforms(gltf);
+
+    scene::scene_nodes(gltf, scene)
+        .into_iter()
+        .filter_map(|node| {
+            let index = gltf.nodes[node]
+                .extensions
+                .as_ref()?
+                .khr_lights_punctual
+                .as_ref()?
+                .light;
+            let transform = &world[node];
+            Some(SceneLight {
+                node,
+                index,
+                light: lights.get(index)?,
+                position: math::transform_point(transform, [0.0; 3]),
+                direction: math::normalize(math::transform_vector(transform, [0.0, 0.0, -1.0])),
+            })
+        })
+        .collect()
+}
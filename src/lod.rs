@@ -0,0 +1,108 @@
+//! MSFT_lod level selection by projected screen coverage.
+//!
+//! A node or material with MSFT_lod lists its lower detail replacements in `ids`, and the
+//! `MSFT_screencoverage` extras give the minimal screen coverage for each level. Coverage
+//! is the fraction of the screen area covered by the projected bounding sphere of the
+//! node's subtree, the world bounds from `bounds::node_spheres`.
+//!
+//! A replacement node takes the place of the original one in the hierarchy, under the
+//! same parent.
+
+use crate::bounds::Sphere;
+use crate::gltf::{Extras, Lod};
+use crate::math::{self, Vec3};
+use crate::Gltf;
+
+/// Perspective camera the coverage is measured for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Camera {
+    /// World space position.
+    pub position: Vec3,
+    /// Vertical field of view in radians.
+    pub yfov: f32,
+    /// Width over height of the viewport.
+    pub aspect_ratio: f32,
+}
+
+/// Fraction of the screen covered by the projected sphere, in [0, 1].
+/// 1 when the camera is inside the sphere.
+pub fn screen_coverage(camera: &Camera, sphere: &Sphere) -> f32 {
+    let distance = math::length(math::sub(sphere.center, camera.position));
+    if distance <= sphere.radius {
+        return 1.0;
+    }
+    // projected radius relative to the half screen height
+    let radius = sphere.radius / (distance * (camera.yfov * 0.5).tan());
+    let area = std::f32::consts::PI * radius * radius;
+    // the screen is 2 half heights tall and 2 * aspect_ratio half heights wide
+    (area / (4.0 * camera.aspect_ratio)).min(1.0)
+}
+
+/// The object followed by its MSFT_lod replacements, highest detail first.
+pub fn levels(object: usize, lod: Option<&Lod>) -> Vec<usize> {
+    std::iter::once(object)
+        .chain(lod.into_iter().flat_map(|lod| lod.ids.iter().copied()))
+        .collect()
+}
+
+/// MSFT_screencoverage thresholds of the object, empty without them.
+pub fn screen_coverages(extras: Option<&Extras>) -> &[f64] {
+    extras
+        .and_then(|extras| extras.msft_screencoverage.as_deref())
+        .unwrap_or(&[])
+}
+
+/// The first level its threshold is reached for, `levels` as returned by `levels`.
+///
+/// MSFT_lod has one threshold per level, below the last one the object is culled: None.
+/// With fewer thresholds than levels, the first level without one is used below them.
+pub fn select(levels: &[usize], coverages: &[f64], coverage: f32) -> Option<usize> {
+    let level = coverages
+        .iter()
+        .position(|&threshold| coverage as f64 >= threshold)
+        .unwrap_or(coverages.len());
+    levels.get(level).copied()
+}
+
+/// Screen coverage of the node's subtree, `spheres` from `bounds::node_spheres`.
+/// None for subtrees without meshes.
+pub fn node_coverage(spheres: &[Option<Sphere>], camera: &Camera, node: usize) -> Option<f32> {
+    spheres[node].map(|sphere| screen_coverage(camera, &sphere))
+}
+
+/// Node to render in place of `node`, `spheres` from `bounds::node_spheres`.
+///
+/// The coverage is measured on the original node's bounds, so every level is
+/// selected with the same metric. Nodes without MSFT_lod, bounds or thresholds
+/// are returned as is. None if the node is culled.
+pub fn select_node(
+    gltf: &Gltf,
+    spheres: &[Option<Sphere>],
+    camera: &Camera,
+    node: usize,
+) -> Option<usize> {
+    let n = &gltf.nodes[node];
+    let levels = levels(node, n.lod());
+    let coverages = screen_coverages(n.extras.as_ref());
+    match node_coverage(spheres, camera, node) {
+        Some(coverage) if levels.len() > 1 && !coverages.is_empty() => {
+            select(&levels, coverages, coverage)
+        }
+        _ => Some(node),
+    }
+}
+
+/// Material to use in place of `material` for an object with the given screen coverage,
+/// usually the `node_coverage` of the node rendering it.
+///
+/// Materials without MSFT_lod or thresholds are returned as is.
+/// None if the object is culled.
+pub fn select_material(gltf: &Gltf, material: usize, coverage: f32) -> Option<usize> {
+    let m = &gltf.materials[material];
+    let levels = levels(material, m.lod());
+    let coverages = screen_coverages(m.extras.as_ref());
+    if levels.len() == 1 || coverages.is_empty() {
+        return Some(material);
+    }
+    select(&levels, coverages, coverage)
+}
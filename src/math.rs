@@ -0,0 +1,152 @@
+//! Tiny vector helpers, just enough for the geometry utilities.
+
+pub type Vec2 = [f32; 2];
+pub type Vec3 = [f32; 3];
+
+pub fn add(a: Vec3, b: Vec3) -> Vec3 {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+pub fn sub(a: Vec3, b: Vec3) -> Vec3 {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+pub fn scale(a: Vec3, k: f32) -> Vec3 {
+    [a[0] * k, a[1] * k, a[2] * k]
+}
+
+pub fn dot(a: Vec3, b: Vec3) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+pub fn cross(a: Vec3, b: Vec3) -> Vec3 {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+pub fn length(a: Vec3) -> f32 {
+    dot(a, a).sqrt()
+}
+
+/// Normalized vector, or zero vector for a zero-length input.
+pub fn normalize(a: Vec3) -> Vec3 {
+    let len = length(a);
+    if len > f32::MIN_POSITIVE {
+        scale(a, 1.0 / len)
+    } else {
+        [0.0; 3]
+    }
+}
+
+/// Angle between two normalized vectors.
+pub fn angle(a: Vec3, b: Vec3) -> f32 {
+    dot(a, b).clamp(-1.0, 1.0).acos()
+}
+
+/// Any unit vector perpendicular to `n`.
+pub fn perpendicular(n: Vec3) -> Vec3 {
+    let axis = if n[0].abs() < 0.9 {
+        [1.0, 0.0, 0.0]
+    } else {
+        [0.0, 1.0, 0.0]
+    };
+    normalize(cross(n, axis))
+}
+
+/// Column-major 4x4 matrix, same layout as `Node::matrix`.
+pub type Mat4 = [f32; 16];
+
+pub fn mul(a: &Mat4, b: &Mat4) -> Mat4 {
+    let mut res = [0.0; 16];
+    for col in 0..4 {
+        for row in 0..4 {
+            res[col * 4 + row] = (0..4).map(|k| a[k * 4 + row] * b[col * 4 + k]).sum();
+        }
+    }
+    res
+}
+
+/// Translation * Rotation * Scale, rotation is a unit quaternion (x, y, z, w).
+pub fn from_trs(t: Vec3, r: [f32; 4], s: Vec3) -> Mat4 {
+    let [x, y, z, w] = r;
+    let (x2, y2, z2) = (x + x, y + y, z + z);
+    let (xx, xy, xz) = (x * x2, x * y2, x * z2);
+    let (yy, yz, zz) = (y * y2, y * z2, z * z2);
+    let (wx, wy, wz) = (w * x2, w * y2, w * z2);
+    [
+        (1.0 - (yy + zz)) * s[0],
+        (xy + wz) * s[0],
+        (xz - wy) * s[0],
+        0.0,
+        (xy - wz) * s[1],
+        (1.0 - (xx + zz)) * s[1],
+        (yz + wx) * s[1],
+        0.0,
+        (xz + wy) * s[2],
+        (yz - wx) * s[2],
+        (1.0 - (xx + yy)) * s[2],
+        0.0,
+        t[0],
+        t[1],
+        t[2],
+        1.0,
+    ]
+}
+
+pub fn transform_point(m: &Mat4, p: Vec3) -> Vec3 {
+    [
+        m[0] * p[0] + m[4] * p[1] + m[8] * p[2] + m[12],
+        m[1] * p[0] + m[5] * p[1] + m[9] * p[2] + m[13],
+        m[2] * p[0] + m[6] * p[1] + m[10] * p[2] + m[14],
+    ]
+}
+
+pub fn transform_vector(m: &Mat4, v: Vec3) -> Vec3 {
+    [
+        m[0] * v[0] + m[4] * v[1] + m[8] * v[2],
+        m[1] * v[0] + m[5] * v[1] + m[9] * v[2],
+        m[2] * v[0] + m[6] * v[1] + m[10] * v[2],
+    ]
+}
+
+/// Determinant of the upper 3x3, negative for mirroring transforms.
+pub fn determinant(m: &Mat4) -> f32 {
+    let [a, b, c] = [0, 4, 8].map(|i| [m[i], m[i + 1], m[i + 2]]);
+    dot(a, cross(b, c))
+}
+
+/// Normal transformed by the inverse-transpose of the upper 3x3, normalized.
+pub fn transform_normal(m: &Mat4, n: Vec3) -> Vec3 {
+    // the inverse-transpose is the cofactor matrix over the determinant,
+    // only its sign matters once normalized
+    let [a, b, c] = [0, 4, 8].map(|i| [m[i], m[i + 1], m[i + 2]]);
+    let cofactor = add(
+        add(scale(cross(b, c), n[0]), scale(cross(c, a), n[1])),
+        scale(cross(a, b), n[2]),
+    );
+    normalize(scale(cofactor, determinant(m).signum()))
+}
+
+/// Shortest path spherical interpolation of unit quaternions (x, y, z, w).
+pub fn slerp(a: [f32; 4], b: [f32; 4], t: f32) -> [f32; 4] {
+    let mut cos = (0..4).map(|i| a[i] * b[i]).sum::<f32>();
+    let mut b = b;
+    if cos < 0.0 {
+        cos = -cos;
+        b = b.map(|c| -c);
+    }
+    // nearly the same rotation, lerp is precise enough and avoids dividing by sin ~ 0
+    let (ka, kb) = if cos > 0.9995 {
+        (1.0 - t, t)
+    } else {
+        let angle = cos.acos();
+        let sin = angle.sin();
+        (((1.0 - t) * angle).sin() / sin, (t * angle).sin() / sin)
+    };
+    let res: [f32; 4] = std::array::from_fn(|i| a[i] * ka + b[i] * kb);
+    let len = (0..4).map(|i| res[i] * res[i]).sum::<f32>().sqrt();
+    res.map(|c| c / len)
+}
@@ -0,0 +1,436 @@
+//! Transform baking and static mesh merging.
+//!
+//! Level geometry that never moves doesn't need a node hierarchy: `merge_scene` bakes the
+//! world transforms into the vertices and merges the primitives sharing a material and a
+//! vertex layout, leaving a single node with a single mesh. Fewer, bigger primitives mean
+//! fewer draw calls.
+//!
+//! Merged vertices are stored as floats, whatever the source accessors were.
+//! KHR_draco_mesh_compression primitives need `draco::decompress` first.
+
+use std::collections::HashMap;
+
+use crate::accessor;
+use crate::gltf::{
+    Accessor, BufferView, BufferViewTarget, ComponentType, Mesh, Node, NodeExtensions, NodeLight,
+    Primitive, PrimitiveMode,
+};
+use crate::instancing;
+use crate::math::{self, Mat4, Vec3};
+use crate::scene::{self, DrawItem};
+use crate::Gltf;
+
+/// Vertex attribute of a `BakedPrimitive`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BakedAttribute {
+    pub semantic: String,
+    /// 1 to 4.
+    pub components: usize,
+    pub values: Vec<f32>,
+}
+
+/// A primitive in world space.
+#[derive(Debug, PartialEq)]
+pub struct BakedPrimitive {
+    pub material: Option<usize>,
+    /// Points, Lines or Triangles: strips, loops and fans are unrolled.
+    pub mode: PrimitiveMode,
+    /// Sorted by semantic.
+    pub attributes: Vec<BakedAttribute>,
+    pub indices: Vec<u32>,
+}
+
+impl BakedPrimitive {
+    pub fn vertex_count(&self) -> usize {
+        self.attributes
+            .first()
+            .map_or(0, |attribute| attribute.values.len() / attribute.components)
+    }
+
+    /// Same material, mode and attributes: the primitives can be merged.
+    pub fn layout_matches(&self, other: &BakedPrimitive) -> bool {
+        self.material == other.material
+            && self.mode == other.mode
+            && self.attributes.len() == other.attributes.len()
+            && self
+                .attributes
+                .iter()
+                .zip(&other.attributes)
+                .all(|(a, b)| a.semantic == b.semantic && a.components == b.components)
+    }
+
+    /// Append the vertices and indices of a primitive with a matching layout.
+    pub fn append(&mut self, other: BakedPrimitive) {
+        debug_assert!(self.layout_matches(&other));
+        let base = self.vertex_count() as u32;
+        for (attribute, other) in self.attributes.iter_mut().zip(other.attributes) {
+            attribute.values.extend(other.values);
+        }
+        self.indices.extend(other.indices.iter().map(|i| i + base));
+    }
+}
+
+/// The draw item's primitive in world space, `item` from `scene::flatten_scene`.
+///
+/// Morph target weights are applied. Normals are transformed by the inverse-transpose of
+/// the world transform; for mirroring transforms the triangle winding and the tangents'
+/// handedness are flipped. JOINTS_n and WEIGHTS_n are dropped, the geometry stays in its
+/// bind pose. None for primitives without POSITION.
+pub fn bake(gltf: &Gltf, buffers: &[Vec<u8>], item: &DrawItem) -> Option<BakedPrimitive> {
+    let primitive = &gltf.meshes[item.mesh].primitives[item.primitive];
+    let vertex_count = gltf.accessors[*primitive.attributes.get("POSITION")?].count;
+
+    let mut attributes: Vec<BakedAttribute> = primitive
+        .attributes
+        .iter()
+        .filter(|(semantic, _)| {
+            !semantic.starts_with("JOINTS_") && !semantic.starts_with("WEIGHTS_")
+        })
+        .map(|(semantic, &accessor)| {
+            let components = gltf.accessors[accessor].components();
+            let mut values = accessor::read_f32(gltf, buffers, accessor);
+            values.resize(vertex_count * components, 0.0);
+            BakedAttribute {
+                semantic: semantic.clone(),
+                components,
+                values,
+            }
+        })
+        .collect();
+    attributes.sort_by(|a, b| a.semantic.cmp(&b.semantic));
+
+    let targets = primitive.targets.iter().flatten();
+    for (target, &weight) in targets.zip(&item.weights) {
+        if weight == 0.0 {
+            continue;
+        }
+        for attribute in &mut attributes {
+            let Some(&accessor) = target.get(&attribute.semantic) else {
+                continue;
+            };
+            let components = gltf.accessors[accessor].components();
+            let deltas = accessor::read_f32(gltf, buffers, accessor);
+            // TANGENT displacements have no w, zip stops at xyz
+            let vertices = attribute.values.chunks_exact_mut(attribute.components);
+            for (vertex, delta) in vertices.zip(deltas.chunks_exact(components)) {
+                for (value, delta) in vertex.iter_mut().zip(delta) {
+                    *value += weight * delta;
+                }
+            }
+        }
+    }
+
+    let world = &item.world;
+    let mirrored = math::determinant(world) < 0.0;
+    for attribute in &mut attributes {
+        let transform: fn(&Mat4, Vec3) -> Vec3 = match attribute.semantic.as_str() {
+            _ if attribute.components < 3 => continue,
+            "POSITION" => math::transform_point,
+            "NORMAL" => math::transform_normal,
+            "TANGENT" => |m, v| math::normalize(math::transform_vector(m, v)),
+            _ => continue,
+        };
+        for vertex in attribute.values.chunks_exact_mut(attribute.components) {
+            let v = transform(world, [vertex[0], vertex[1], vertex[2]]);
+            vertex[..3].copy_from_slice(&v);
+            // only TANGENT has a 4th component, the handedness
+            if mirrored && vertex.len() == 4 {
+                vertex[3] = -vertex[3];
+            }
+        }
+    }
+
+    let indices = accessor::primitive_indices(gltf, buffers, primitive);
+    let (mode, mut indices) = match primitive.mode {
+        Some(PrimitiveMode::Points) => (PrimitiveMode::Points, indices),
+        Some(PrimitiveMode::Lines) => {
+            let lines = indices[..indices.len() / 2 * 2].to_vec();
+            (PrimitiveMode::Lines, lines)
+        }
+        Some(PrimitiveMode::LineStrip) | Some(PrimitiveMode::LineLoop) => {
+            let mut lines: Vec<u32> = indices.windows(2).flatten().copied().collect();
+            if primitive.mode == Some(PrimitiveMode::LineLoop) && indices.len() > 2 {
+                lines.extend([indices[indices.len() - 1], indices[0]]);
+            }
+            (PrimitiveMode::Lines, lines)
+        }
+        _ => (
+            PrimitiveMode::Triangles,
+            accessor::triangle_indices(gltf, buffers, primitive)?,
+        ),
+    };
+    if mirrored && mode == PrimitiveMode::Triangles {
+        for triangle in indices.chunks_exact_mut(3) {
+            triangle.swap(1, 2);
+        }
+    }
+
+    Some(BakedPrimitive {
+        material: primitive.material,
+        mode,
+        attributes,
+        indices,
+    })
+}
+
+/// Merge the primitives sharing a material and vertex layout,
+/// in the order of the first primitive of each group.
+pub fn merge(primitives: impl IntoIterator<Item = BakedPrimitive>) -> Vec<BakedPrimitive> {
+    let mut res: Vec<BakedPrimitive> = vec![];
+    for primitive in primitives {
+        match res.iter_mut().find(|p| p.layout_matches(&primitive)) {
+            Some(merged) => merged.append(primitive),
+            None => res.push(primitive),
+        }
+    }
+    res
+}
+
+const TYPES: [&str; 4] = ["SCALAR", "VEC2", "VEC3", "VEC4"];
+
+// vertex data is decoded and stored as floats by the merge
+const GEOMETRY_EXTENSIONS: &[&str] = &[
+    "KHR_draco_mesh_compression",
+    "EXT_meshopt_compression",
+    "KHR_mesh_quantization",
+    "EXT_mesh_gpu_instancing",
+    "KHR_animation_pointer",
+];
+
+/// Collapse the scene into a single node with a single mesh,
+/// one primitive per material and vertex layout.
+///
+/// The merged geometry is appended to `buffers[buffer]`, growing the buffer's
+/// `byteLength`: the binary chunk of a GLB, or a buffer the caller added with its uri.
+/// The scene becomes the only one; the node hierarchy, the other scenes and the
+/// animations are dropped. Materials, textures and images are kept, so are the buffers,
+/// for the images stored in buffer views.
+///
+/// Cameras and KHR_lights_punctual lights get a root node each, after the mesh's node,
+/// with the world transform of the node they were on as its matrix.
+///
+/// EXT_mesh_gpu_instancing nodes are baked once per instance.
+pub fn merge_scene(mut gltf: Gltf, buffers: &mut [Vec<u8>], scene: usize, buffer: usize) -> Gltf {
+    let mut baked = vec![];
+    for item in scene::flatten_scene(&gltf, scene) {
+        let instances = instancing::instance_transforms(&gltf, buffers, item.node);
+        match instances {
+            Some(instances) => {
+                for instance in instances {
+                    let world = math::mul(&item.world, &instance);
+                    let item = DrawItem {
+                        world,
+                        ..item.clone()
+                    };
+                    baked.extend(bake(&gltf, buffers, &item));
+                }
+            }
+            None => baked.extend(bake(&gltf, buffers, &item)),
+        }
+    }
+    let merged = merge(baked);
+
+    let world = scene::world_transforms(&gltf);
+    let mut nodes = vec![];
+    for node in scene::scene_nodes(&gltf, scene) {
+        let n = &gltf.nodes[node];
+        let light = n
+            .extensions
+            .as_ref()
+            .and_then(|e| e.khr_lights_punctual.as_ref());
+        if n.camera.is_none() && light.is_none() {
+            continue;
+        }
+        nodes.push(Node {
+            camera: n.camera,
+            children: vec![],
+            skin: None,
+            matrix: Some(world[node].map(|x| x as f64)),
+            mesh: None,
+            rotation: None,
+            scale: None,
+            translation: None,
+            weights: None,
+            name: n.name.clone(),
+            extensions: light.map(|light| NodeExtensions {
+                khr_lights_punctual: Some(NodeLight { light: light.light }),
+                ext_mesh_gpu_instancing: None,
+                msft_lod: None,
+                khr_xmp_json_ld: None,
+            }),
+            extras: None,
+            custom_extensions: None,
+        });
+    }
+
+    // the images are the only users of the old buffer views left
+    let mut old_views: Vec<Option<BufferView>> = std::mem::take(&mut gltf.buffer_views)
+        .into_iter()
+        .map(Some)
+        .collect();
+    let mut remap = vec![None; old_views.len()];
+    for view in gltf
+        .images
+        .iter_mut()
+        .filter_map(|i| i.buffer_view.as_mut())
+    {
+        *view = *remap[*view].get_or_insert_with(|| {
+            gltf.buffer_views.push(old_views[*view].take().unwrap());
+            gltf.buffer_views.len() - 1
+        });
+    }
+    gltf.accessors.clear();
+
+    let data = &mut buffers[buffer];
+    data.resize(data.len().div_ceil(4) * 4, 0);
+    let mut primitives = vec![];
+    for primitive in merged {
+        let vertex_count = primitive.vertex_count();
+        let mut attributes = HashMap::new();
+        for attribute in &primitive.attributes {
+            let bytes: Vec<u8> = attribute
+                .values
+                .iter()
+                .flat_map(|v| v.to_le_bytes())
+                .collect();
+            let view = push_view(
+                &mut gltf,
+                data,
+                buffer,
+                &bytes,
+                BufferViewTarget::ArrayBuffer,
+            );
+            let (min, max) = match attribute.semantic.as_str() {
+                "POSITION" => {
+                    let (min, max) = min_max(&attribute.values, attribute.components);
+                    (Some(min), Some(max))
+                }
+                _ => (None, None),
+            };
+            gltf.accessors.push(Accessor {
+                buffer_view: Some(view),
+                byte_offset: 0,
+                component_type: ComponentType::Float,
+                normalized: false,
+                count: vertex_count,
+                max,
+                min,
+                sparse: None,
+                name: None,
+                type_: Some(TYPES[attribute.components - 1].to_string()),
+                custom_extensions: None,
+            });
+            attributes.insert(attribute.semantic.clone(), gltf.accessors.len() - 1);
+        }
+
+        let (component_type, bytes): (_, Vec<u8>) = if vertex_count <= u16::MAX as usize {
+            let bytes = primitive.indices.iter();
+            let bytes = bytes.flat_map(|&i| (i as u16).to_le_bytes());
+            (ComponentType::UnsignedShort, bytes.collect())
+        } else {
+            let bytes = primitive.indices.iter().flat_map(|i| i.to_le_bytes());
+            (ComponentType::UnsignedInt, bytes.collect())
+        };
+        let view = push_view(
+            &mut gltf,
+            data,
+            buffer,
+            &bytes,
+            BufferViewTarget::ElementArrayBuffer,
+        );
+        gltf.accessors.push(Accessor {
+            buffer_view: Some(view),
+            byte_offset: 0,
+            component_type,
+            normalized: false,
+            count: primitive.indices.len(),
+            max: None,
+            min: None,
+            sparse: None,
+            name: None,
+            type_: Some("SCALAR".to_string()),
+            custom_extensions: None,
+        });
+
+        primitives.push(Primitive {
+            attributes,
+            indices: Some(gltf.accessors.len() - 1),
+            material: primitive.material,
+            mode: Some(primitive.mode),
+            targets: None,
+            extensions: None,
+            custom_extensions: None,
+        });
+    }
+    gltf.buffers[buffer].byte_length = data.len();
+
+    let mut scene = gltf.scenes.swap_remove(scene);
+    scene.nodes = (0..=nodes.len()).collect();
+    gltf.meshes = vec![Mesh {
+        primitives,
+        weights: None,
+        name: scene.name.clone(),
+        extensions: None,
+        custom_extensions: None,
+    }];
+    gltf.nodes = vec![Node {
+        camera: None,
+        children: vec![],
+        skin: None,
+        matrix: None,
+        mesh: Some(0),
+        rotation: None,
+        scale: None,
+        translation: None,
+        weights: None,
+        name: scene.name.clone(),
+        extensions: None,
+        extras: None,
+        custom_extensions: None,
+    }];
+    gltf.nodes.extend(nodes);
+    gltf.scenes = vec![scene];
+    gltf.scene = Some(0);
+    gltf.animations.clear();
+    for extensions in [&mut gltf.extensions_used, &mut gltf.extensions_required] {
+        extensions.retain(|e| !GEOMETRY_EXTENSIONS.contains(&e.as_str()));
+    }
+
+    gltf
+}
+
+/// Append a 4 byte aligned buffer view.
+fn push_view(
+    gltf: &mut Gltf,
+    data: &mut Vec<u8>,
+    buffer: usize,
+    bytes: &[u8],
+    target: BufferViewTarget,
+) -> usize {
+    let byte_offset = data.len();
+    data.extend_from_slice(bytes);
+    data.resize(data.len().div_ceil(4) * 4, 0);
+    gltf.buffer_views.push(BufferView {
+        buffer,
+        byte_offset,
+        byte_length: bytes.len(),
+        byte_stride: None,
+        target: Some(target),
+        name: None,
+        extensions: None,
+        custom_extensions: None,
+    });
+    gltf.buffer_views.len() - 1
+}
+
+fn min_max(values: &[f32], components: usize) -> (Vec<f64>, Vec<f64>) {
+    let mut min = vec![f64::MAX; components];
+    let mut max = vec![f64::MIN; components];
+    for vertex in values.chunks_exact(components) {
+        for (c, &v) in vertex.iter().enumerate() {
+            min[c] = min[c].min(v as f64);
+            max[c] = max[c].max(v as f64);
+        }
+    }
+    (min, max)
+}
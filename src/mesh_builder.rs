@@ -0,0 +1,349 @@
+//! GPU-ready vertex and index buffers for a primitive.
+//!
+//! The caller describes the vertex layout it wants: the attributes with their target
+//! formats, interleaved or one stream per attribute. `MeshBuilder` converts whatever
+//! the accessors store into those formats and fills the attributes missing from the
+//! primitive with defaults, so a single shader/pipeline works for every primitive.
+//!
+//! KHR_draco_mesh_compression primitives need `draco::decompress` first.
+
+use crate::{accessor, gltf::Primitive, Gltf};
+
+/// Component type of a vertex format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComponentFormat {
+    F32,
+    /// Integer stored as is, for JOINTS_n and custom integer attributes.
+    U8,
+    U16,
+    U32,
+    /// Value in [0, 1] stored as 0..=255.
+    U8Norm,
+    /// Value in [-1, 1] stored as -127..=127.
+    I8Norm,
+    U16Norm,
+    I16Norm,
+}
+
+impl ComponentFormat {
+    pub fn byte_size(&self) -> usize {
+        use ComponentFormat::*;
+
+        match self {
+            U8 | U8Norm | I8Norm => 1,
+            U16 | U16Norm | I16Norm => 2,
+            F32 | U32 => 4,
+        }
+    }
+
+    /// U8, U16 and U32, the formats storing the accessor values as is.
+    pub fn is_integer(&self) -> bool {
+        matches!(
+            self,
+            ComponentFormat::U8 | ComponentFormat::U16 | ComponentFormat::U32
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VertexFormat {
+    pub component: ComponentFormat,
+    /// 1 to 4.
+    pub components: usize,
+}
+
+impl VertexFormat {
+    pub const FLOAT32X2: VertexFormat = VertexFormat::new(ComponentFormat::F32, 2);
+    pub const FLOAT32X3: VertexFormat = VertexFormat::new(ComponentFormat::F32, 3);
+    pub const FLOAT32X4: VertexFormat = VertexFormat::new(ComponentFormat::F32, 4);
+    pub const UNORM8X4: VertexFormat = VertexFormat::new(ComponentFormat::U8Norm, 4);
+    pub const UINT16X4: VertexFormat = VertexFormat::new(ComponentFormat::U16, 4);
+
+    pub const fn new(component: ComponentFormat, components: usize) -> VertexFormat {
+        VertexFormat {
+            component,
+            components,
+        }
+    }
+
+    pub fn byte_size(&self) -> usize {
+        self.component.byte_size() * self.components
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    /// A single stream, all attributes of a vertex next to each other.
+    Interleaved,
+    /// One stream per attribute, one after another in the vertex buffer.
+    Planar,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexFormat {
+    U16,
+    U32,
+}
+
+/// Requested vertex attribute.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VertexAttribute {
+    /// glTF attribute semantic, like "POSITION" or "TEXCOORD_0".
+    pub semantic: String,
+    pub format: VertexFormat,
+    /// Value for vertices of primitives without this attribute,
+    /// and for the components the accessor does not have.
+    pub default: [f32; 4],
+}
+
+/// Default value of a missing attribute: +Z for NORMAL, +X with positive handedness for
+/// TANGENT, opaque white for COLOR_n, zero for everything else.
+pub fn default_value(semantic: &str) -> [f32; 4] {
+    match semantic {
+        "NORMAL" => [0.0, 0.0, 1.0, 0.0],
+        "TANGENT" => [1.0, 0.0, 0.0, 1.0],
+        _ if semantic.starts_with("COLOR_") => [1.0; 4],
+        _ => [0.0; 4],
+    }
+}
+
+/// Where an attribute is in the built vertex buffer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttributeLayout {
+    pub semantic: String,
+    pub format: VertexFormat,
+    /// Offset inside the vertex for interleaved layouts,
+    /// offset of the attribute's stream in the buffer for planar ones.
+    pub offset: usize,
+    pub stride: usize,
+    /// False if the primitive has no such attribute and the default was used.
+    pub present: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Indices {
+    U16(Vec<u16>),
+    U32(Vec<u32>),
+}
+
+impl Indices {
+    pub fn len(&self) -> usize {
+        match self {
+            Indices::U16(indices) => indices.len(),
+            Indices::U32(indices) => indices.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn format(&self) -> IndexFormat {
+        match self {
+            Indices::U16(_) => IndexFormat::U16,
+            Indices::U32(_) => IndexFormat::U32,
+        }
+    }
+
+    /// Little endian index buffer bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            Indices::U16(indices) => indices.iter().flat_map(|i| i.to_le_bytes()).collect(),
+            Indices::U32(indices) => indices.iter().flat_map(|i| i.to_le_bytes()).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BuiltMesh {
+    /// Vertex data, little endian.
+    pub vertices: Vec<u8>,
+    pub vertex_count: usize,
+    /// Indices in the primitive's own mode, 0..vertex_count for non-indexed primitives.
+    pub indices: Indices,
+    pub layout: Layout,
+    /// One per requested attribute, in the requested order.
+    pub attributes: Vec<AttributeLayout>,
+}
+
+/// Vertex layout to build primitives' buffers for.
+///
+/// ```ignore
+/// let mesh = MeshBuilder::new()
+///     .attribute("POSITION", VertexFormat::FLOAT32X3)
+///     .attribute("NORMAL", VertexFormat::new(ComponentFormat::I8Norm, 4))
+///     .attribute("TEXCOORD_0", VertexFormat::FLOAT32X2)
+///     .build(&gltf, &buffers, primitive);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeshBuilder {
+    attributes: Vec<VertexAttribute>,
+    layout: Layout,
+    index_format: Option<IndexFormat>,
+}
+
+impl Default for MeshBuilder {
+    fn default() -> MeshBuilder {
+        MeshBuilder::new()
+    }
+}
+
+// attributes and vertex strides are padded to 4 bytes, as GPU APIs require
+const ALIGNMENT: usize = 4;
+
+impl MeshBuilder {
+    /// Interleaved layout without attributes, the index format picked by the vertex count.
+    pub fn new() -> MeshBuilder {
+        MeshBuilder {
+            attributes: vec![],
+            layout: Layout::Interleaved,
+            index_format: None,
+        }
+    }
+
+    /// Add an attribute with the `default_value` of its semantic.
+    pub fn attribute(self, semantic: &str, format: VertexFormat) -> MeshBuilder {
+        self.attribute_with_default(semantic, format, default_value(semantic))
+    }
+
+    pub fn attribute_with_default(
+        mut self,
+        semantic: &str,
+        format: VertexFormat,
+        default: [f32; 4],
+    ) -> MeshBuilder {
+        assert!(
+            (1..=4).contains(&format.components),
+            "Vertex format of {semantic} has {} components",
+            format.components
+        );
+        self.attributes.push(VertexAttribute {
+            semantic: semantic.to_string(),
+            format,
+            default,
+        });
+        self
+    }
+
+    pub fn layout(mut self, layout: Layout) -> MeshBuilder {
+        self.layout = layout;
+        self
+    }
+
+    /// Force the index format. U16 still falls back to U32 when the vertices don't fit.
+    /// Without it, U16 is used whenever the vertices fit.
+    pub fn index_format(mut self, index_format: IndexFormat) -> MeshBuilder {
+        self.index_format = Some(index_format);
+        self
+    }
+
+    /// (offset, stride) of each attribute for `vertex_count` vertices and the buffer size.
+    fn attribute_layouts(&self, vertex_count: usize) -> (Vec<(usize, usize)>, usize) {
+        let sizes = self
+            .attributes
+            .iter()
+            .map(|attribute| attribute.format.byte_size().div_ceil(ALIGNMENT) * ALIGNMENT);
+        match self.layout {
+            Layout::Interleaved => {
+                let vertex_size: usize = sizes.clone().sum();
+                let offsets = sizes.scan(0, |offset, size| {
+                    *offset += size;
+                    Some((*offset - size, vertex_size))
+                });
+                (offsets.collect(), vertex_size * vertex_count)
+            }
+            Layout::Planar => {
+                let mut offset = 0;
+                let mut res = vec![];
+                for stride in sizes {
+                    res.push((offset, stride));
+                    offset += stride * vertex_count;
+                }
+                (res, offset)
+            }
+        }
+    }
+
+    /// Build the buffers of the primitive.
+    pub fn build(&self, gltf: &Gltf, buffers: &[Vec<u8>], primitive: &Primitive) -> BuiltMesh {
+        let vertex_count = accessor::vertex_count(gltf, primitive);
+        let (layouts, size) = self.attribute_layouts(vertex_count);
+        let mut vertices = vec![0; size];
+
+        let mut attributes = vec![];
+        for (attribute, (offset, stride)) in self.attributes.iter().zip(layouts) {
+            let format = attribute.format;
+            // missing accessors and unknown types are treated as a missing attribute
+            let accessor = primitive
+                .attributes
+                .get(&attribute.semantic)
+                .and_then(|&a| Some((a, gltf.accessors.get(a)?.checked_components()?)));
+            // integers are read in their stored form, f32 can't hold all u32 values
+            let (values, components) = match accessor {
+                Some((accessor, components)) if format.component.is_integer() => {
+                    (accessor::read_raw(gltf, buffers, accessor), components)
+                }
+                Some((accessor, components)) => (
+                    accessor::read_f32(gltf, buffers, accessor)
+                        .into_iter()
+                        .map(f64::from)
+                        .collect(),
+                    components,
+                ),
+                None => (vec![], 0),
+            };
+            let component_size = format.component.byte_size();
+            for v in 0..vertex_count {
+                let start = offset + v * stride;
+                for c in 0..format.components {
+                    let value = match values.get(v * components + c) {
+                        Some(value) if c < components => *value,
+                        _ => attribute.default[c] as f64,
+                    };
+                    let at = start + c * component_size;
+                    write_component(&mut vertices[at..], value, format.component);
+                }
+            }
+            attributes.push(AttributeLayout {
+                semantic: attribute.semantic.clone(),
+                format,
+                offset,
+                stride,
+                present: accessor.is_some(),
+            });
+        }
+
+        let indices = accessor::primitive_indices(gltf, buffers, primitive);
+        let fits_u16 = vertex_count <= u16::MAX as usize;
+        let indices = match self.index_format {
+            Some(IndexFormat::U32) => Indices::U32(indices),
+            _ if !fits_u16 => Indices::U32(indices),
+            _ => Indices::U16(indices.iter().map(|&i| i as u16).collect()),
+        };
+
+        BuiltMesh {
+            vertices,
+            vertex_count,
+            indices,
+            layout: self.layout,
+            attributes,
+        }
+    }
+}
+
+fn write_component(bytes: &mut [u8], value: f64, format: ComponentFormat) {
+    use ComponentFormat::*;
+
+    let unorm = |max: f64| (value.clamp(0.0, 1.0) * max).round();
+    let snorm = |max: f64| (value.clamp(-1.0, 1.0) * max).round();
+    match format {
+        F32 => bytes[..4].copy_from_slice(&(value as f32).to_le_bytes()),
+        U8 => bytes[0] = value as u8,
+        U16 => bytes[..2].copy_from_slice(&(value as u16).to_le_bytes()),
+        U32 => bytes[..4].copy_from_slice(&(value as u32).to_le_bytes()),
+        U8Norm => bytes[0] = unorm(255.0) as u8,
+        I8Norm => bytes[0] = snorm(127.0) as i8 as u8,
+        U16Norm => bytes[..2].copy_from_slice(&(unorm(65535.0) as u16).to_le_bytes()),
+        I16Norm => bytes[..2].copy_from_slice(&(snorm(32767.0) as i16).to_le_bytes()),
+    }
+}
@@ -0,0 +1,521 @@
+//! EXT_meshopt_compression decoders.
+//!
+//! A port of the meshoptimizer reference decoders: vertex codec version 0 for ATTRIBUTES,
+//! index codec for TRIANGLES, index sequence codec for INDICES, and the OCTAHEDRAL,
+//! QUATERNION and EXPONENTIAL filters applied on top of the decoded attributes.
+//!
+//! All decoders return None on malformed input instead of panicking.
+
+use crate::gltf::MeshoptCompression;
+use crate::Gltf;
+
+const VERTEX_HEADER: u8 = 0xa0;
+const INDEX_HEADER: u8 = 0xe0;
+const SEQUENCE_HEADER: u8 = 0xd0;
+
+const BYTE_GROUP_SIZE: usize = 16;
+const VERTEX_BLOCK_SIZE_BYTES: usize = 8192;
+const VERTEX_BLOCK_MAX_SIZE: usize = 256;
+const TAIL_MIN_SIZE: usize = 32;
+
+/// Decompressed bytes of the buffer view, None if the view is not compressed
+/// or the compressed data is malformed.
+pub fn decode_buffer_view(gltf: &Gltf, buffers: &[Vec<u8>], view: usize) -> Option<Vec<u8>> {
+    let view = gltf.buffer_views.get(view)?;
+    let compression = view.meshopt_compression()?;
+    view.byte_offset.checked_add(view.byte_length)?;
+    if compression.count.checked_mul(compression.byte_stride)? != view.byte_length {
+        return None;
+    }
+    let end = compression
+        .byte_offset
+        .checked_add(compression.byte_length)?;
+    let data = buffers
+        .get(compression.buffer)?
+        .get(compression.byte_offset..end)?;
+    let bytes = decode(compression, data)?;
+    (bytes.len() == view.byte_length).then_some(bytes)
+}
+
+/// Decode all the compressed views into the byte ranges they describe and remove their
+/// EXT_meshopt_compression, so they are plain views: the `accessor` readers don't decode
+/// them on every read anymore and `AccessorView` can borrow them. Fallback buffers without
+/// data are zero filled up to the view end first, views not fitting the declared buffer
+/// `byteLength` are skipped.
+///
+/// Returns the amount of decoded views, the ones failing to decode are left as is.
+pub fn decompress(gltf: &mut Gltf, buffers: &mut [Vec<u8>]) -> usize {
+    let mut decoded = 0;
+    for i in 0..gltf.buffer_views.len() {
+        if gltf.buffer_views[i].meshopt_compression().is_none() {
+            continue;
+        }
+        let Some(bytes) = decode_buffer_view(gltf, buffers, i) else {
+            continue;
+        };
+        let view = &gltf.buffer_views[i];
+        let end = view.byte_offset + view.byte_length;
+        let declared = gltf
+            .buffers
+            .get(view.buffer)
+            .map_or(0, |buffer| buffer.byte_length);
+        let Some(data) = buffers.get_mut(view.buffer).filter(|_| end <= declared) else {
+            continue;
+        };
+        if data.len() < end {
+            data.resize(end, 0);
+        }
+        data[view.byte_offset..end].copy_from_slice(&bytes);
+        if let Some(extensions) = &mut gltf.buffer_views[i].extensions {
+            extensions.ext_meshopt_compression = None;
+        }
+        decoded += 1;
+    }
+    decoded
+}
+
+/// Decode `data`, the compressed bytes the extension points to.
+pub fn decode(compression: &MeshoptCompression, data: &[u8]) -> Option<Vec<u8>> {
+    let count = compression.count;
+    let stride = compression.byte_stride;
+    let mut bytes = match compression.mode.as_str() {
+        "ATTRIBUTES" => decode_vertex_buffer(count, stride, data)?,
+        "TRIANGLES" => decode_index_buffer(count, stride, data)?,
+        "INDICES" => decode_index_sequence(count, stride, data)?,
+        _ => return None,
+    };
+    match compression.filter.as_str() {
+        "NONE" => {}
+        "OCTAHEDRAL" if compression.mode == "ATTRIBUTES" => decode_filter_oct(&mut bytes, stride)?,
+        "QUATERNION" if compression.mode == "ATTRIBUTES" => decode_filter_quat(&mut bytes, stride)?,
+        "EXPONENTIAL" if compression.mode == "ATTRIBUTES" => decode_filter_exp(&mut bytes, stride)?,
+        _ => return None,
+    }
+    Some(bytes)
+}
+
+fn unzigzag8(v: u8) -> u8 {
+    (v >> 1) ^ (v & 1).wrapping_neg()
+}
+
+fn unzigzag32(v: u32) -> u32 {
+    (v >> 1) ^ (v & 1).wrapping_neg()
+}
+
+/// One group of 16 bytes, packed with 0, 2, 4 or 8 bits per byte.
+/// Packed values with all bits set are escapes, the actual byte follows the packed ones.
+fn decode_bytes_group(data: &[u8], out: &mut [u8], bitslog2: u8) -> Option<usize> {
+    if bitslog2 == 0 {
+        out.fill(0);
+        return Some(0);
+    }
+    if bitslog2 == 3 {
+        out.copy_from_slice(data.get(..BYTE_GROUP_SIZE)?);
+        return Some(BYTE_GROUP_SIZE);
+    }
+
+    let bits = 1 << bitslog2;
+    let sentinel = (1u8 << bits) - 1;
+    let packed = BYTE_GROUP_SIZE * bits / 8;
+    let mut extra = packed;
+    for (i, out) in out.iter_mut().enumerate() {
+        let byte = *data.get(i * bits / 8)?;
+        let shift = 8 - bits - (i * bits % 8);
+        let value = (byte >> shift) & sentinel;
+        *out = if value == sentinel {
+            extra += 1;
+            *data.get(extra - 1)?
+        } else {
+            value
+        };
+    }
+    Some(extra)
+}
+
+/// `out.len()` bytes of a single byte channel, a header with 2 bits per group followed by groups.
+fn decode_bytes(data: &[u8], out: &mut [u8]) -> Option<usize> {
+    let groups = out.len() / BYTE_GROUP_SIZE;
+    let header_size = groups.div_ceil(4);
+    let header = data.get(..header_size)?;
+    let mut offset = header_size;
+    for (group, out) in out.chunks_exact_mut(BYTE_GROUP_SIZE).enumerate() {
+        let bitslog2 = (header[group / 4] >> ((group % 4) * 2)) & 3;
+        offset += decode_bytes_group(data.get(offset..)?, out, bitslog2)?;
+    }
+    Some(offset)
+}
+
+fn decode_vertex_block(
+    data: &[u8],
+    out: &mut [u8],
+    vertex_count: usize,
+    vertex_size: usize,
+    last_vertex: &mut [u8],
+) -> Option<usize> {
+    let aligned = vertex_count.div_ceil(BYTE_GROUP_SIZE) * BYTE_GROUP_SIZE;
+    let mut buffer = vec![0; aligned];
+    let mut offset = 0;
+
+    for k in 0..vertex_size {
+        offset += decode_bytes(data.get(offset..)?, &mut buffer)?;
+
+        let mut p = last_vertex[k];
+        for (i, v) in buffer[..vertex_count].iter().enumerate() {
+            p = p.wrapping_add(unzigzag8(*v));
+            out[i * vertex_size + k] = p;
+        }
+    }
+    last_vertex.copy_from_slice(&out[(vertex_count - 1) * vertex_size..vertex_count * vertex_size]);
+    Some(offset)
+}
+
+/// Vertex codec, `count` vertices of `vertex_size` bytes.
+///
+/// Vertices are split into blocks, each byte of the vertex is delta encoded against the
+/// previous vertex separately. The first vertex is predicted from the tail of the data.
+pub fn decode_vertex_buffer(count: usize, vertex_size: usize, data: &[u8]) -> Option<Vec<u8>> {
+    if vertex_size == 0 || vertex_size > 256 || !vertex_size.is_multiple_of(4) {
+        return None;
+    }
+    // only version 0 is allowed by the extension
+    if *data.first()? != VERTEX_HEADER {
+        return None;
+    }
+    let tail_size = vertex_size.max(TAIL_MIN_SIZE);
+    if data.len() < 1 + tail_size {
+        return None;
+    }
+    let mut last_vertex = data[data.len() - vertex_size..].to_vec();
+    let body = &data[1..data.len() - tail_size];
+
+    let block_size = ((VERTEX_BLOCK_SIZE_BYTES / vertex_size) & !(BYTE_GROUP_SIZE - 1))
+        .min(VERTEX_BLOCK_MAX_SIZE);
+    // every block takes at least one header byte per vertex byte
+    if count.div_ceil(block_size).checked_mul(vertex_size)? > body.len() {
+        return None;
+    }
+    let mut out = vec![0; count * vertex_size];
+    let mut offset = 0;
+    for (block, out) in out.chunks_mut(block_size * vertex_size).enumerate() {
+        let vertices = (count - block * block_size).min(block_size);
+        offset += decode_vertex_block(
+            body.get(offset..)?,
+            out,
+            vertices,
+            vertex_size,
+            &mut last_vertex,
+        )?;
+    }
+    (offset == body.len()).then_some(out)
+}
+
+fn decode_vbyte(data: &[u8], offset: &mut usize) -> Option<u32> {
+    let lead = *data.get(*offset)?;
+    *offset += 1;
+    if lead < 128 {
+        return Some(lead as u32);
+    }
+
+    let mut result = (lead & 127) as u32;
+    let mut shift = 7;
+    for _ in 0..4 {
+        let group = *data.get(*offset)?;
+        *offset += 1;
+        result |= ((group & 127) as u32) << shift;
+        shift += 7;
+        if group < 128 {
+            break;
+        }
+    }
+    Some(result)
+}
+
+fn decode_index(data: &[u8], offset: &mut usize, last: u32) -> Option<u32> {
+    Some(last.wrapping_add(unzigzag32(decode_vbyte(data, offset)?)))
+}
+
+fn write_index(out: &mut [u8], i: usize, index_size: usize, index: u32) {
+    if index_size == 2 {
+        out[i * 2..i * 2 + 2].copy_from_slice(&(index as u16).to_le_bytes());
+    } else {
+        out[i * 4..i * 4 + 4].copy_from_slice(&index.to_le_bytes());
+    }
+}
+
+struct Fifos {
+    edges: [(u32, u32); 16],
+    edge_offset: usize,
+    vertices: [u32; 16],
+    vertex_offset: usize,
+}
+
+impl Fifos {
+    fn edge(&self, back: usize) -> (u32, u32) {
+        self.edges[self.edge_offset.wrapping_sub(back) & 15]
+    }
+
+    fn vertex(&self, back: usize) -> u32 {
+        self.vertices[self.vertex_offset.wrapping_sub(back) & 15]
+    }
+
+    fn push_edge(&mut self, a: u32, b: u32) {
+        self.edges[self.edge_offset] = (a, b);
+        self.edge_offset = (self.edge_offset + 1) & 15;
+    }
+
+    fn push_vertex(&mut self, v: u32, cond: bool) {
+        self.vertices[self.vertex_offset] = v;
+        self.vertex_offset = (self.vertex_offset + cond as usize) & 15;
+    }
+}
+
+/// Index codec for triangle lists, `count` indices of `index_size` (2 or 4) bytes.
+///
+/// Each triangle is a code byte referencing recently seen edges and vertices, indices that
+/// were not seen recently are stored as vbyte encoded deltas.
+pub fn decode_index_buffer(count: usize, index_size: usize, data: &[u8]) -> Option<Vec<u8>> {
+    if !count.is_multiple_of(3) || (index_size != 2 && index_size != 4) {
+        return None;
+    }
+    // header, one code per triangle and the 16 bytes codeaux table
+    if data.len() < 1 + count / 3 + 16 || data[0] & 0xf0 != INDEX_HEADER {
+        return None;
+    }
+    let version = data[0] & 0x0f;
+    if version > 1 {
+        return None;
+    }
+
+    let mut fifos = Fifos {
+        edges: [(u32::MAX, u32::MAX); 16],
+        edge_offset: 0,
+        vertices: [u32::MAX; 16],
+        vertex_offset: 0,
+    };
+    let mut next = 0u32;
+    let mut last = 0u32;
+    let fecmax = if version >= 1 { 13 } else { 15 };
+
+    let codes = &data[1..1 + count / 3];
+    let data_end = data.len() - 16;
+    let codeaux_table = &data[data_end..];
+    let data = &data[..data_end];
+    let mut offset = 1 + count / 3;
+
+    let mut out = vec![0; count * index_size];
+    for (triangle, &code) in codes.iter().enumerate() {
+        let i = triangle * 3;
+        let [a, b, c] = if code < 0xf0 {
+            let fe = (code >> 4) as usize;
+            let (a, b) = fifos.edge(1 + fe);
+            let fec = code & 15;
+
+            if fec < fecmax {
+                let c = if fec == 0 {
+                    next
+                } else {
+                    fifos.vertex(1 + fec as usize)
+                };
+                next += (fec == 0) as u32;
+                fifos.push_vertex(c, fec == 0);
+                fifos.push_edge(c, b);
+                fifos.push_edge(a, c);
+                [a, b, c]
+            } else {
+                let c = match fec {
+                    13 => last.wrapping_sub(1),
+                    14 => last.wrapping_add(1),
+                    _ => decode_index(data, &mut offset, last)?,
+                };
+                last = c;
+                fifos.push_vertex(c, true);
+                fifos.push_edge(c, b);
+                fifos.push_edge(a, c);
+                [a, b, c]
+            }
+        } else if code < 0xfe {
+            let codeaux = codeaux_table[(code & 15) as usize];
+            let feb = (codeaux >> 4) as usize;
+            let fec = (codeaux & 15) as usize;
+
+            let a = next;
+            next += 1;
+            let b = if feb == 0 { next } else { fifos.vertex(feb) };
+            next += (feb == 0) as u32;
+            let c = if fec == 0 { next } else { fifos.vertex(fec) };
+            next += (fec == 0) as u32;
+
+            fifos.push_vertex(a, true);
+            fifos.push_vertex(b, feb == 0);
+            fifos.push_vertex(c, fec == 0);
+            fifos.push_edge(b, a);
+            fifos.push_edge(c, b);
+            fifos.push_edge(a, c);
+            [a, b, c]
+        } else {
+            let codeaux = *data.get(offset)?;
+            offset += 1;
+            let fea = if code == 0xfe { 0 } else { 15 };
+            let feb = (codeaux >> 4) as usize;
+            let fec = (codeaux & 15) as usize;
+
+            // restart
+            if codeaux == 0 {
+                next = 0;
+            }
+
+            let mut free = |fe: usize| {
+                if fe == 0 {
+                    next += 1;
+                    next - 1
+                } else if fe == 15 {
+                    0
+                } else {
+                    fifos.vertex(fe)
+                }
+            };
+            let (mut a, mut b, mut c) = (free(fea), free(feb), free(fec));
+
+            if fea == 15 {
+                a = decode_index(data, &mut offset, last)?;
+                last = a;
+            }
+            if feb == 15 {
+                b = decode_index(data, &mut offset, last)?;
+                last = b;
+            }
+            if fec == 15 {
+                c = decode_index(data, &mut offset, last)?;
+                last = c;
+            }
+
+            fifos.push_vertex(a, true);
+            fifos.push_vertex(b, feb == 0 || feb == 15);
+            fifos.push_vertex(c, fec == 0 || fec == 15);
+            fifos.push_edge(b, a);
+            fifos.push_edge(c, b);
+            fifos.push_edge(a, c);
+            [a, b, c]
+        };
+
+        write_index(&mut out, i, index_size, a);
+        write_index(&mut out, i + 1, index_size, b);
+        write_index(&mut out, i + 2, index_size, c);
+    }
+
+    // all the data should be consumed, up to the codeaux table
+    (offset == data_end).then_some(out)
+}
+
+/// Index sequence codec, `count` indices of `index_size` (2 or 4) bytes in any topology.
+///
+/// Each index is a vbyte encoded delta against one of the two previous baselines.
+pub fn decode_index_sequence(count: usize, index_size: usize, data: &[u8]) -> Option<Vec<u8>> {
+    if index_size != 2 && index_size != 4 {
+        return None;
+    }
+    // header, at least one byte per index and the 4 bytes tail
+    if data.len().saturating_sub(5) < count
+        || data[0] & 0xf0 != SEQUENCE_HEADER
+        || data[0] & 0x0f > 1
+    {
+        return None;
+    }
+    let data = &data[..data.len() - 4];
+    let mut offset = 1;
+
+    let mut last = [0u32; 2];
+    let mut out = vec![0; count * index_size];
+    for i in 0..count {
+        let v = decode_vbyte(data, &mut offset)?;
+        let current = (v & 1) as usize;
+        let index = last[current].wrapping_add(unzigzag32(v >> 1));
+        last[current] = index;
+        write_index(&mut out, i, index_size, index);
+    }
+    (offset == data.len()).then_some(out)
+}
+
+fn round(x: f32) -> i32 {
+    (x + if x >= 0.0 { 0.5 } else { -0.5 }) as i32
+}
+
+/// Octahedral encoded unit vectors, 4 signed bytes or shorts per element, the 4th component
+/// is left as is.
+fn decode_filter_oct(data: &mut [u8], stride: usize) -> Option<()> {
+    let size = match stride {
+        4 => 1,
+        8 => 2,
+        _ => return None,
+    };
+    let max = ((1 << (size * 8 - 1)) - 1) as f32;
+    let read = |bytes: &[u8], c: usize| match size {
+        1 => bytes[c] as i8 as f32,
+        _ => i16::from_le_bytes([bytes[c * 2], bytes[c * 2 + 1]]) as f32,
+    };
+
+    for element in data.chunks_exact_mut(stride) {
+        let mut x = read(element, 0);
+        let mut y = read(element, 1);
+        let z = read(element, 2) - x.abs() - y.abs();
+
+        // fold the lower hemisphere back
+        let t = z.min(0.0);
+        x += if x >= 0.0 { t } else { -t };
+        y += if y >= 0.0 { t } else { -t };
+
+        let s = max / (x * x + y * y + z * z).sqrt();
+        for (c, v) in [x, y, z].into_iter().enumerate() {
+            let v = round(v * s);
+            match size {
+                1 => element[c] = v as i8 as u8,
+                _ => element[c * 2..c * 2 + 2].copy_from_slice(&(v as i16).to_le_bytes()),
+            }
+        }
+    }
+    Some(())
+}
+
+/// Unit quaternions, 3 smallest components as shorts plus the index of the largest one
+/// and a scale in the 4th short.
+fn decode_filter_quat(data: &mut [u8], stride: usize) -> Option<()> {
+    if stride != 8 {
+        return None;
+    }
+    let scale = 1.0 / 2f32.sqrt();
+
+    for element in data.chunks_exact_mut(8) {
+        let q = [0, 1, 2, 3].map(|c| i16::from_le_bytes([element[c * 2], element[c * 2 + 1]]));
+        let ss = scale / (q[3] | 3) as f32;
+
+        let x = q[0] as f32 * ss;
+        let y = q[1] as f32 * ss;
+        let z = q[2] as f32 * ss;
+        let w = (1.0 - x * x - y * y - z * z).max(0.0).sqrt();
+
+        // the largest component goes into the slot encoded in the two low bits
+        let qc = (q[3] & 3) as usize;
+        for (k, v) in [w, x, y, z].into_iter().enumerate() {
+            let v = round(v * 32767.0) as i16;
+            let c = (qc + k) & 3;
+            element[c * 2..c * 2 + 2].copy_from_slice(&v.to_le_bytes());
+        }
+    }
+    Some(())
+}
+
+/// Floats stored as 24 bit signed mantissa and 8 bit signed exponent.
+fn decode_filter_exp(data: &mut [u8], stride: usize) -> Option<()> {
+    if !stride.is_multiple_of(4) {
+        return None;
+    }
+
+    for value in data.chunks_exact_mut(4) {
+        let v = u32::from_le_bytes([value[0], value[1], value[2], value[3]]);
+        let m = ((v << 8) as i32) >> 8;
+        let e = (v as i32) >> 24;
+        let f = f32::from_bits(((e + 127) as u32) << 23) * m as f32;
+        value.copy_from_slice(&f.to_le_bytes());
+    }
+    Some(())
+}
@@ -0,0 +1,151 @@
+//! Normal generation for primitives without NORMAL attribute.
+//!
+//! Spec says flat normals should be used when NORMAL is missing. Flat normals are
+//! per-face, so vertices shared by faces facing different directions get split,
+//! the result is a new vertex set with `remap` pointing back to the original vertices.
+
+use std::collections::HashMap;
+
+use crate::math::{self, Vec3};
+use crate::{accessor, gltf::Primitive, Gltf};
+
+pub enum NormalsMode {
+    /// Per-face normals, as spec requires for primitives without NORMAL.
+    Flat,
+    /// Angle-weighted vertex normals. Faces meeting at an angle bigger than
+    /// `crease_angle` (in radians) are not smoothed together, PI smoothes everything.
+    Smooth { crease_angle: f32 },
+}
+
+pub struct GeneratedNormals {
+    /// Original vertex index for each new vertex, to copy the other attributes over.
+    pub remap: Vec<u32>,
+    /// Triangle list indexing the new vertices.
+    pub indices: Vec<u32>,
+    /// Normal for each new vertex.
+    pub normals: Vec<[f32; 3]>,
+}
+
+/// Generate normals for a triangles primitive.
+/// None if there is no POSITION or the primitive is not made of triangles.
+pub fn generate_normals(
+    gltf: &Gltf,
+    buffers: &[Vec<u8>],
+    primitive: &Primitive,
+    mode: NormalsMode,
+) -> Option<GeneratedNormals> {
+    let positions = primitive.attributes.get("POSITION")?;
+    let positions = accessor::read_vec3(gltf, buffers, *positions);
+    let indices = accessor::triangle_indices(gltf, buffers, primitive)?;
+
+    Some(match mode {
+        NormalsMode::Flat => flat_normals(&positions, &indices),
+        NormalsMode::Smooth { crease_angle } => smooth_normals(&positions, &indices, crease_angle),
+    })
+}
+
+fn face_normal(positions: &[Vec3], triangle: &[u32]) -> Vec3 {
+    let [p0, p1, p2] = [0, 1, 2].map(|i| positions[triangle[i] as usize]);
+    math::normalize(math::cross(math::sub(p1, p0), math::sub(p2, p0)))
+}
+
+/// Angle of the triangle at the given corner.
+fn corner_angle(positions: &[Vec3], triangle: &[u32], corner: usize) -> f32 {
+    let p = positions[triangle[corner] as usize];
+    let next = positions[triangle[(corner + 1) % 3] as usize];
+    let prev = positions[triangle[(corner + 2) % 3] as usize];
+    let angle = math::angle(
+        math::normalize(math::sub(next, p)),
+        math::normalize(math::sub(prev, p)),
+    );
+    if angle.is_finite() {
+        angle
+    } else {
+        0.0
+    }
+}
+
+/// Build new vertices out of per-corner normals, sharing a vertex between corners
+/// with the same original vertex and the same normal.
+fn split_vertices(indices: &[u32], corner_normals: Vec<Vec3>) -> GeneratedNormals {
+    let mut vertices = HashMap::new();
+    let mut res = GeneratedNormals {
+        remap: vec![],
+        indices: Vec::with_capacity(indices.len()),
+        normals: vec![],
+    };
+
+    for (index, normal) in indices.iter().zip(corner_normals) {
+        let key = (*index, normal.map(f32::to_bits));
+        let vertex = *vertices.entry(key).or_insert_with(|| {
+            res.remap.push(*index);
+            res.normals.push(normal);
+            res.remap.len() as u32 - 1
+        });
+        res.indices.push(vertex);
+    }
+    res
+}
+
+/// Per-face normals for a triangle list.
+/// Vertices are split only when shared by faces with different normals.
+pub fn flat_normals(positions: &[Vec3], indices: &[u32]) -> GeneratedNormals {
+    let corner_normals = indices
+        .chunks_exact(3)
+        .flat_map(|triangle| {
+            let mut normal = face_normal(positions, triangle);
+            if normal == [0.0; 3] {
+                normal = [0.0, 0.0, 1.0];
+            }
+            [normal; 3]
+        })
+        .collect();
+    split_vertices(indices, corner_normals)
+}
+
+/// Angle-weighted smooth normals for a triangle list.
+///
+/// Faces are smoothed together by vertex position, not by index, so uv seams do not show up
+/// as lighting seams. `crease_angle` is in radians.
+pub fn smooth_normals(positions: &[Vec3], indices: &[u32], crease_angle: f32) -> GeneratedNormals {
+    let face_normals: Vec<Vec3> = indices
+        .chunks_exact(3)
+        .map(|triangle| face_normal(positions, triangle))
+        .collect();
+
+    // position -> (face, angle of that face at this position)
+    let mut around: HashMap<[u32; 3], Vec<(usize, f32)>> = HashMap::new();
+    for (face, triangle) in indices.chunks_exact(3).enumerate() {
+        for corner in 0..3 {
+            let position = positions[triangle[corner] as usize].map(f32::to_bits);
+            let angle = corner_angle(positions, triangle, corner);
+            around.entry(position).or_default().push((face, angle));
+        }
+    }
+
+    let cos_crease = crease_angle.cos();
+    let corner_normals = indices
+        .iter()
+        .enumerate()
+        .map(|(corner, index)| {
+            let face_normal = face_normals[corner / 3];
+            let position = positions[*index as usize].map(f32::to_bits);
+            let mut normal = [0.0; 3];
+            for (face, angle) in &around[&position] {
+                let other = face_normals[*face];
+                if math::dot(face_normal, other) >= cos_crease {
+                    normal = math::add(normal, math::scale(other, *angle));
+                }
+            }
+            let normal = math::normalize(normal);
+            if normal != [0.0; 3] {
+                normal
+            } else if face_normal != [0.0; 3] {
+                face_normal
+            } else {
+                [0.0, 0.0, 1.0]
+            }
+        })
+        .collect();
+    split_vertices(indices, corner_normals)
+}
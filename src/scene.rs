@@ -0,0 +1,140 @@
+//! Node hierarchy helpers.
+//!
+//! Matrices are column-major `[f32; 16]`, the same layout as `Node::matrix`.
+
+use crate::math::{self, Mat4};
+use crate::{gltf::Node, Gltf};
+
+/// Node's transform relative to its parent, either `matrix` or TRS.
+pub fn local_transform(node: &Node) -> Mat4 {
+    if let Some(matrix) = node.matrix {
+        return matrix.map(|x| x as f32);
+    }
+    let t = node.translation.unwrap_or([0.0; 3]).map(|x| x as f32);
+    let r = node
+        .rotation
+        .unwrap_or([0.0, 0.0, 0.0, 1.0])
+        .map(|x| x as f32);
+    let s = node.scale.unwrap_or([1.0; 3]).map(|x| x as f32);
+    math::from_trs(t, r, s)
+}
+
+/// Parent of each node, None for root nodes. Out of range children are ignored.
+pub fn parents(gltf: &Gltf) -> Vec<Option<usize>> {
+    let mut parents = vec![None; gltf.nodes.len()];
+    for (i, node) in gltf.nodes.iter().enumerate() {
+        for child in &node.children {
+            if let Some(parent) = parents.get_mut(*child) {
+                *parent = Some(i);
+            }
+        }
+    }
+    parents
+}
+
+/// World transform of every node, indexed the same way as `gltf.nodes`.
+///
+/// Hierarchies with cycles are invalid glTF, the nodes of a cycle are transformed as if
+/// the cycle was cut above one of them.
+pub fn world_transforms(gltf: &Gltf) -> Vec<Mat4> {
+    let parents = parents(gltf);
+    let mut world: Vec<Option<Mat4>> = vec![None; gltf.nodes.len()];
+    let mut in_chain = vec![false; gltf.nodes.len()];
+    let mut chain = vec![];
+
+    for node in 0..gltf.nodes.len() {
+        // walk up to the first resolved ancestor, the root, or back into the chain for cycles
+        let mut ancestor = Some(node);
+        while let Some(n) = ancestor {
+            if world[n].is_some() || in_chain[n] {
+                break;
+            }
+            in_chain[n] = true;
+            chain.push(n);
+            ancestor = parents[n];
+        }
+        let mut parent = ancestor.and_then(|n| world[n]);
+        while let Some(n) = chain.pop() {
+            in_chain[n] = false;
+            let local = local_transform(&gltf.nodes[n]);
+            let transform = match parent {
+                Some(parent) => math::mul(&parent, &local),
+                None => local,
+            };
+            world[n] = Some(transform);
+            parent = Some(transform);
+        }
+    }
+    world.into_iter().map(Option::unwrap).collect()
+}
+
+/// The nodes reachable from `roots`, parents before their children, each node once
+/// even in malformed hierarchies.
+pub fn subtree_nodes(gltf: &Gltf, roots: &[usize]) -> Vec<usize> {
+    let mut res = vec![];
+    let mut visited = vec![false; gltf.nodes.len()];
+    let mut stack: Vec<usize> = roots.iter().rev().copied().collect();
+    while let Some(node) = stack.pop() {
+        match visited.get_mut(node) {
+            Some(visited) if !*visited => *visited = true,
+            _ => continue,
+        }
+        res.push(node);
+        stack.extend(gltf.nodes[node].children.iter().rev());
+    }
+    res
+}
+
+/// All the nodes of the scene, parents before their children.
+pub fn scene_nodes(gltf: &Gltf, scene: usize) -> Vec<usize> {
+    subtree_nodes(gltf, &gltf.scenes[scene].nodes)
+}
+
+/// A primitive to draw, from `flatten_scene`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DrawItem {
+    pub node: usize,
+    /// World transform of the node.
+    pub world: Mat4,
+    pub mesh: usize,
+    /// Index into the mesh's primitives.
+    pub primitive: usize,
+    pub material: Option<usize>,
+    pub skin: Option<usize>,
+    /// Morph target weights, the node's or else the mesh's, zeros when neither has them.
+    /// Empty without morph targets.
+    pub weights: Vec<f32>,
+}
+
+/// Every primitive of every node in the scene, parents before their children.
+///
+/// EXT_mesh_gpu_instancing and MSFT_lod are not applied, see `instancing` and `lod`.
+pub fn flatten_scene(gltf: &Gltf, scene: usize) -> Vec<DrawItem> {
+    let world = world_transforms(gltf);
+    let mut res = vec![];
+    for node in scene_nodes(gltf, scene) {
+        let n = &gltf.nodes[node];
+        let Some(mesh) = n.mesh else {
+            continue;
+        };
+        let m = &gltf.meshes[mesh];
+        let weights: Option<Vec<f32>> = n
+            .weights
+            .as_ref()
+            .or(m.weights.as_ref())
+            .map(|w| w.iter().map(|&x| x as f32).collect());
+        for (i, primitive) in m.primitives.iter().enumerate() {
+            let targets = primitive.targets.as_ref().map_or(0, Vec::len);
+            res.push(DrawItem {
+                node,
+                world: world[node],
+                mesh,
+                primitive: i,
+                material: primitive.material,
+                skin: n.skin,
+                weights: weights.clone().unwrap_or_else(|| vec![0.0; targets]),
+            });
+        }
+    }
+    res
+}
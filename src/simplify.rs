@@ -0,0 +1,578 @@
+//! Quadric error metric mesh simplification, for LOD generation.
+//!
+//! Edges are collapsed into one of their endpoints, cheapest first, so the simplified
+//! primitives keep the original vertices and only get new index buffers. Vertices sharing
+//! a position, split at UV or normal seams, collapse together along the seam, keeping the
+//! seam in place. Vertices on open borders collapse along the border, or not at all with
+//! `Options::lock_border`.
+//!
+//! KHR_draco_mesh_compression primitives need `draco::decompress` first.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::gltf::{
+    Accessor, BufferView, BufferViewTarget, ComponentType, Extras, Lod, Mesh, Node, NodeExtensions,
+    Primitive, PrimitiveMode,
+};
+use crate::math::{self, Vec3};
+use crate::{accessor, Gltf};
+
+/// Simplification targets, it stops at whichever is reached first.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Options {
+    /// Fraction of the triangles to keep, in [0, 1].
+    pub target_ratio: f32,
+    /// Largest allowed deviation from the original surface, relative to the mesh extents:
+    /// 0.01 is 1% of the largest side of the bounding box.
+    pub target_error: f32,
+    /// Keep the vertices of open borders in place.
+    pub lock_border: bool,
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options {
+            target_ratio: 0.5,
+            target_error: 0.01,
+            lock_border: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Simplified {
+    /// Triangle list into the original vertices.
+    pub indices: Vec<u32>,
+    /// Largest deviation introduced, relative to the mesh extents like `Options::target_error`.
+    pub error: f32,
+}
+
+// constraint planes along borders and seams weigh more than the surface, so they move less
+const BORDER_WEIGHT: f64 = 10.0;
+
+/// Plane distance quadric: `p^T a p + 2 b.p + c` is the weighted sum of squared distances.
+#[derive(Debug, Clone, Copy, Default)]
+struct Quadric {
+    // xx, xy, xz, yy, yz, zz
+    a: [f64; 6],
+    b: [f64; 3],
+    c: f64,
+    weight: f64,
+}
+
+impl Quadric {
+    fn plane(normal: Vec3, point: Vec3, weight: f64) -> Quadric {
+        let [x, y, z] = normal.map(|v| v as f64);
+        let d = -math::dot(normal, point) as f64;
+        Quadric {
+            a: [x * x, x * y, x * z, y * y, y * z, z * z].map(|v| v * weight),
+            b: [x * d, y * d, z * d].map(|v| v * weight),
+            c: d * d * weight,
+            weight,
+        }
+    }
+
+    fn add(&mut self, other: &Quadric) {
+        for (a, b) in self.a.iter_mut().zip(other.a) {
+            *a += b;
+        }
+        for (a, b) in self.b.iter_mut().zip(other.b) {
+            *a += b;
+        }
+        self.c += other.c;
+        self.weight += other.weight;
+    }
+
+    /// Weighted mean squared distance of the point to the planes.
+    fn error(&self, p: Vec3) -> f64 {
+        if self.weight == 0.0 {
+            return 0.0;
+        }
+        let [x, y, z] = p.map(|v| v as f64);
+        let [xx, xy, xz, yy, yz, zz] = self.a;
+        let e = xx * x * x
+            + yy * y * y
+            + zz * z * z
+            + 2.0 * (xy * x * y + xz * x * z + yz * y * z)
+            + 2.0 * (self.b[0] * x + self.b[1] * y + self.b[2] * z)
+            + self.c;
+        (e / self.weight).max(0.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    /// Collapses along any edge.
+    Manifold,
+    /// On an open border, collapses along the border.
+    Border,
+    /// Split into several vertices, collapses along the seam.
+    Seam,
+    Locked,
+}
+
+/// Simplify a triangle list. `positions` may contain vertices with the same position,
+/// they are treated as a single vertex split at a seam.
+pub fn simplify(positions: &[Vec3], indices: &[u32], options: &Options) -> Simplified {
+    let positions = unit_positions(positions);
+    let n = positions.len();
+
+    // every vertex mapped to the first one at the same position
+    let mut first = HashMap::new();
+    let weld: Vec<usize> = positions
+        .iter()
+        .enumerate()
+        .map(|(v, p)| *first.entry(p.map(|x| (x + 0.0).to_bits())).or_insert(v))
+        .collect();
+
+    let mut indices: Vec<u32> = indices
+        .chunks_exact(3)
+        .filter(|t| t.iter().all(|&v| (v as usize) < n))
+        .filter(|t| distinct(&weld, t))
+        .flatten()
+        .copied()
+        .collect();
+    let target = ((indices.len() / 3) as f32 * options.target_ratio.clamp(0.0, 1.0)) as usize;
+    let max_error = options.target_error as f64 * options.target_error as f64;
+
+    let mesh = Topology::new(&weld, &indices);
+    let mut quadrics = vec![Quadric::default(); n];
+    for t in indices.chunks_exact(3) {
+        let [p0, p1, p2] = [0, 1, 2].map(|i| positions[t[i] as usize]);
+        let normal = math::cross(math::sub(p1, p0), math::sub(p2, p0));
+        let area = math::length(normal) as f64 * 0.5;
+        if area > 0.0 {
+            let quadric = Quadric::plane(math::normalize(normal), p0, area);
+            for &v in t {
+                quadrics[weld[v as usize]].add(&quadric);
+            }
+        }
+        // planes through border and seam edges, perpendicular to the triangle
+        for i in 0..3 {
+            let (a, b) = (t[i], t[(i + 1) % 3]);
+            if mesh.wedge_edges.contains(&(b, a)) {
+                continue;
+            }
+            let edge = math::sub(positions[b as usize], positions[a as usize]);
+            let length = math::length(edge) as f64;
+            let plane = math::normalize(math::cross(edge, normal));
+            let quadric = Quadric::plane(
+                plane,
+                positions[a as usize],
+                length * length * BORDER_WEIGHT,
+            );
+            quadrics[weld[a as usize]].add(&quadric);
+            quadrics[weld[b as usize]].add(&quadric);
+        }
+    }
+
+    let kinds: Vec<Kind> = (0..n)
+        .map(|v| {
+            let border = mesh.borders.contains(&v);
+            let seam = mesh.wedges[v].len() > 1;
+            match (border, seam) {
+                (true, true) => Kind::Locked,
+                (true, false) if options.lock_border => Kind::Locked,
+                (true, false) => Kind::Border,
+                (false, true) => Kind::Seam,
+                (false, false) => Kind::Manifold,
+            }
+        })
+        .collect();
+
+    let mut error = 0.0f64;
+    while indices.len() / 3 > target {
+        let mesh = Topology::new(&weld, &indices);
+        let mut candidates = vec![];
+        for t in indices.chunks_exact(3) {
+            for i in 0..3 {
+                let (a, b) = (weld[t[i] as usize], weld[t[(i + 1) % 3] as usize]);
+                for (from, to) in [(a, b), (b, a)] {
+                    let cost = quadrics[from].error(positions[to]);
+                    if cost <= max_error {
+                        candidates.push((cost, from, to));
+                    }
+                }
+            }
+        }
+        candidates.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut remap = HashMap::new();
+        let mut locked = vec![false; n];
+        let mut triangles = indices.len() / 3;
+        for (cost, from, to) in candidates {
+            if locked[from] || locked[to] {
+                continue;
+            }
+            let valid = match kinds[from] {
+                Kind::Manifold | Kind::Seam => true,
+                // along the border, the edge has a triangle on a single side
+                Kind::Border => {
+                    mesh.welded_edges.contains(&(from, to))
+                        != mesh.welded_edges.contains(&(to, from))
+                }
+                Kind::Locked => false,
+            };
+            if !valid || mesh.flips(&positions, from, to) {
+                continue;
+            }
+            let Some(pairs) = mesh.wedge_pairs(from, to, kinds[from] == Kind::Seam) else {
+                continue;
+            };
+            remap.extend(pairs);
+            let q = quadrics[from];
+            quadrics[to].add(&q);
+            error = error.max(cost);
+
+            // the triangles around `from` change shape, their other collapses wait for the next pass
+            locked[from] = true;
+            locked[to] = true;
+            for &t in &mesh.incident[from] {
+                for v in mesh.triangles[t] {
+                    locked[v] = true;
+                }
+            }
+            triangles -= mesh.incident[from]
+                .iter()
+                .filter(|&&t| mesh.triangles[t].contains(&to))
+                .count();
+            if triangles <= target {
+                break;
+            }
+        }
+        if remap.is_empty() {
+            break;
+        }
+        for v in &mut indices {
+            *v = remap.get(v).copied().unwrap_or(*v);
+        }
+        indices = indices
+            .chunks_exact(3)
+            .filter(|t| distinct(&weld, t))
+            .flatten()
+            .copied()
+            .collect();
+    }
+
+    Simplified {
+        indices,
+        error: error.sqrt() as f32,
+    }
+}
+
+/// Positions scaled into a unit box.
+fn unit_positions(positions: &[Vec3]) -> Vec<Vec3> {
+    let mut min = [f32::MAX; 3];
+    let mut max = [f32::MIN; 3];
+    for p in positions {
+        for c in 0..3 {
+            min[c] = min[c].min(p[c]);
+            max[c] = max[c].max(p[c]);
+        }
+    }
+    let extent = (0..3).map(|c| max[c] - min[c]).fold(0.0, f32::max);
+    let scale = if extent > 0.0 { 1.0 / extent } else { 1.0 };
+    positions
+        .iter()
+        .map(|&p| math::scale(math::sub(p, min), scale))
+        .collect()
+}
+
+fn distinct(weld: &[usize], t: &[u32]) -> bool {
+    let [a, b, c] = [0, 1, 2].map(|i| weld[t[i] as usize]);
+    a != b && b != c && c != a
+}
+
+/// Connectivity of the current triangles.
+struct Topology {
+    /// Triangles by welded vertex.
+    triangles: Vec<[usize; 3]>,
+    incident: Vec<Vec<usize>>,
+    /// Directed edges between vertices, and between welded vertices.
+    wedge_edges: HashSet<(u32, u32)>,
+    welded_edges: HashSet<(usize, usize)>,
+    /// Vertices in use at each welded vertex.
+    wedges: Vec<Vec<u32>>,
+    /// Welded vertices on an open border.
+    borders: HashSet<usize>,
+}
+
+impl Topology {
+    fn new(weld: &[usize], indices: &[u32]) -> Topology {
+        let mut res = Topology {
+            triangles: vec![],
+            incident: vec![vec![]; weld.len()],
+            wedge_edges: HashSet::new(),
+            welded_edges: HashSet::new(),
+            wedges: vec![vec![]; weld.len()],
+            borders: HashSet::new(),
+        };
+        for (i, t) in indices.chunks_exact(3).enumerate() {
+            let welded = [0, 1, 2].map(|j| weld[t[j] as usize]);
+            for j in 0..3 {
+                let (a, b) = (t[j], t[(j + 1) % 3]);
+                res.wedge_edges.insert((a, b));
+                res.welded_edges.insert((welded[j], welded[(j + 1) % 3]));
+                res.incident[welded[j]].push(i);
+                if !res.wedges[welded[j]].contains(&a) {
+                    res.wedges[welded[j]].push(a);
+                }
+            }
+            res.triangles.push(welded);
+        }
+        for &(a, b) in &res.welded_edges {
+            if !res.welded_edges.contains(&(b, a)) {
+                res.borders.insert(a);
+                res.borders.insert(b);
+            }
+        }
+        res
+    }
+
+    /// Whether moving `from` onto `to` turns a triangle around `from` upside down.
+    fn flips(&self, positions: &[Vec3], from: usize, to: usize) -> bool {
+        self.incident[from].iter().any(|&t| {
+            let t = self.triangles[t];
+            if t.contains(&to) {
+                return false;
+            }
+            let normal = |p: [Vec3; 3]| math::cross(math::sub(p[1], p[0]), math::sub(p[2], p[0]));
+            let before = t.map(|v| positions[v]);
+            let after = t.map(|v| positions[if v == from { to } else { v }]);
+            math::dot(normal(before), normal(after)) <= 0.0
+        })
+    }
+
+    /// The vertex of `to` each vertex of `from` collapses into, connected by an edge.
+    /// For seams, the edges have to be on the seam and every vertex gets its own target.
+    fn wedge_pairs(&self, from: usize, to: usize, seam: bool) -> Option<Vec<(u32, u32)>> {
+        let mut res = vec![];
+        for &a in &self.wedges[from] {
+            let b = self.wedges[to].iter().copied().find(|&b| {
+                let (ab, ba) = (
+                    self.wedge_edges.contains(&(a, b)),
+                    self.wedge_edges.contains(&(b, a)),
+                );
+                if seam {
+                    ab != ba
+                } else {
+                    ab || ba
+                }
+            })?;
+            if seam && res.iter().any(|&(_, other)| other == b) {
+                return None;
+            }
+            res.push((a, b));
+        }
+        Some(res)
+    }
+}
+
+/// Simplify a primitive, None for points, lines and primitives without POSITION.
+///
+/// Vertices with the same attributes, morph targets included, are the same vertex: only
+/// vertices with the same position and different attributes make a seam.
+pub fn simplify_primitive(
+    gltf: &Gltf,
+    buffers: &[Vec<u8>],
+    primitive: &Primitive,
+    options: &Options,
+) -> Option<Simplified> {
+    let positions = accessor::read_vec3(gltf, buffers, *primitive.attributes.get("POSITION")?);
+    let unique = unique_vertices(gltf, buffers, primitive, positions.len());
+    let indices: Vec<u32> = accessor::triangle_indices(gltf, buffers, primitive)?
+        .into_iter()
+        .map(|v| unique.get(v as usize).copied().unwrap_or(v))
+        .collect();
+    Some(simplify(&positions, &indices, options))
+}
+
+/// Every vertex mapped to the first one with the same attribute values.
+fn unique_vertices(
+    gltf: &Gltf,
+    buffers: &[Vec<u8>],
+    primitive: &Primitive,
+    vertex_count: usize,
+) -> Vec<u32> {
+    let targets = primitive.targets.iter().flatten();
+    let attributes: Vec<(usize, Vec<f32>)> = primitive
+        .attributes
+        .values()
+        .chain(targets.flat_map(|target| target.values()))
+        .map(|&accessor| {
+            let components = gltf.accessors[accessor].components();
+            (components, accessor::read_f32(gltf, buffers, accessor))
+        })
+        .collect();
+
+    let mut first = HashMap::new();
+    (0..vertex_count)
+        .map(|v| {
+            let key: Vec<u32> = attributes
+                .iter()
+                .flat_map(|(components, values)| {
+                    let vertex = values.get(v * components..(v + 1) * components);
+                    vertex.unwrap_or(&[]).iter().map(|x| (x + 0.0).to_bits())
+                })
+                .collect();
+            *first.entry(key).or_insert(v as u32)
+        })
+        .collect()
+}
+
+/// Every primitive of the mesh simplified, in the same order.
+pub fn simplify_mesh(
+    gltf: &Gltf,
+    buffers: &[Vec<u8>],
+    mesh: usize,
+    options: &Options,
+) -> Vec<Option<Simplified>> {
+    gltf.meshes[mesh]
+        .primitives
+        .iter()
+        .map(|primitive| simplify_primitive(gltf, buffers, primitive, options))
+        .collect()
+}
+
+/// Append a copy of the mesh with the simplified index buffers, `simplified` as returned by
+/// `simplify_mesh`. Primitives without simplified indices are copied as is.
+///
+/// The primitives share the original vertex accessors, the new indices are appended to
+/// `buffers[buffer]`, growing the buffer's `byteLength`. Primitive extensions are not copied.
+/// Returns the index of the new mesh.
+pub fn add_mesh(
+    gltf: &mut Gltf,
+    buffers: &mut [Vec<u8>],
+    buffer: usize,
+    mesh: usize,
+    simplified: &[Option<Simplified>],
+) -> usize {
+    let data = &mut buffers[buffer];
+    data.resize(data.len().div_ceil(4) * 4, 0);
+    let mut primitives = vec![];
+    for (p, simplified) in simplified.iter().enumerate() {
+        let primitive = &gltf.meshes[mesh].primitives[p];
+        let mut copy = Primitive {
+            attributes: primitive.attributes.clone(),
+            indices: primitive.indices,
+            material: primitive.material,
+            mode: primitive.mode,
+            targets: primitive.targets.clone(),
+            extensions: None,
+            custom_extensions: None,
+        };
+        if let Some(simplified) = simplified {
+            let small = accessor::vertex_count(gltf, primitive) <= u16::MAX as usize;
+            let byte_offset = data.len();
+            for &i in &simplified.indices {
+                match small {
+                    true => data.extend_from_slice(&(i as u16).to_le_bytes()),
+                    false => data.extend_from_slice(&i.to_le_bytes()),
+                }
+            }
+            let byte_length = data.len() - byte_offset;
+            data.resize(data.len().div_ceil(4) * 4, 0);
+            gltf.buffer_views.push(BufferView {
+                buffer,
+                byte_offset,
+                byte_length,
+                byte_stride: None,
+                target: Some(BufferViewTarget::ElementArrayBuffer),
+                name: None,
+                extensions: None,
+                custom_extensions: None,
+            });
+            gltf.accessors.push(Accessor {
+                buffer_view: Some(gltf.buffer_views.len() - 1),
+                byte_offset: 0,
+                component_type: match small {
+                    true => ComponentType::UnsignedShort,
+                    false => ComponentType::UnsignedInt,
+                },
+                normalized: false,
+                count: simplified.indices.len(),
+                max: None,
+                min: None,
+                sparse: None,
+                name: None,
+                type_: Some("SCALAR".to_string()),
+                custom_extensions: None,
+            });
+            copy.indices = Some(gltf.accessors.len() - 1);
+            copy.mode = Some(PrimitiveMode::Triangles);
+        }
+        primitives.push(copy);
+    }
+    gltf.buffers[buffer].byte_length = data.len();
+
+    let mesh = &gltf.meshes[mesh];
+    gltf.meshes.push(Mesh {
+        primitives,
+        weights: mesh.weights.clone(),
+        name: mesh.name.clone(),
+        extensions: None,
+        custom_extensions: None,
+    });
+    gltf.meshes.len() - 1
+}
+
+/// Generate the MSFT_lod levels of the node's mesh, one per `levels` entry, from the
+/// highest detail to the lowest. Each level is simplified from the original mesh and
+/// placed on a new node with the node's transform, listed in the node's MSFT_lod,
+/// replacing any previous one.
+///
+/// `coverages` are the MSFT_screencoverage thresholds, one per level including the
+/// original node, `levels.len() + 1` values. Below the last one the node is culled.
+///
+/// The new indices are appended to `buffers[buffer]`, see `add_mesh`.
+/// Returns the new nodes, None if the node has no mesh.
+pub fn add_lod_levels(
+    gltf: &mut Gltf,
+    buffers: &mut [Vec<u8>],
+    buffer: usize,
+    node: usize,
+    levels: &[Options],
+    coverages: Option<Vec<f64>>,
+) -> Option<Vec<usize>> {
+    let mesh = gltf.nodes[node].mesh?;
+    let mut ids = vec![];
+    for options in levels {
+        let simplified = simplify_mesh(gltf, buffers, mesh, options);
+        let lod_mesh = add_mesh(gltf, buffers, buffer, mesh, &simplified);
+        let n = &gltf.nodes[node];
+        gltf.nodes.push(Node {
+            camera: None,
+            children: vec![],
+            skin: n.skin,
+            matrix: n.matrix,
+            mesh: Some(lod_mesh),
+            rotation: n.rotation,
+            scale: n.scale,
+            translation: n.translation,
+            weights: n.weights.clone(),
+            name: n.name.clone(),
+            extensions: None,
+            extras: None,
+            custom_extensions: None,
+        });
+        ids.push(gltf.nodes.len() - 1);
+    }
+
+    let n = &mut gltf.nodes[node];
+    let extensions = n.extensions.get_or_insert(NodeExtensions {
+        khr_lights_punctual: None,
+        ext_mesh_gpu_instancing: None,
+        msft_lod: None,
+        khr_xmp_json_ld: None,
+    });
+    extensions.msft_lod = Some(Lod { ids: ids.clone() });
+    if coverages.is_some() {
+        n.extras
+            .get_or_insert_with(Extras::default)
+            .msft_screencoverage = coverages;
+    }
+    if !gltf.extensions_used.iter().any(|e| e == "MSFT_lod") {
+        gltf.extensions_used.push("MSFT_lod".to_string());
+    }
+    Some(ids)
+}
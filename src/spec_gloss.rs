@@ -0,0 +1,192 @@
+//! KHR_materials_pbrSpecularGlossiness to metallic-roughness conversion.
+//!
+//! Uses the same approach as the Khronos sample converter: metallic is solved from the
+//! perceived brightness of diffuse and specular, assuming 4% dielectric reflectance,
+//! base color is a blend between the diffuse and specular derived colors and
+//! roughness is `1 - glossiness`.
+
+use crate::gltf::{Material, MaterialsPbrSpecularGlossiness, PBRMetallicRoughness};
+
+const DIELECTRIC_SPECULAR: f64 = 0.04;
+const EPSILON: f64 = 1e-6;
+
+/// Decoded RGBA8 image, row by row, 4 bytes per pixel.
+pub struct Rgba8Image {
+    pub width: usize,
+    pub height: usize,
+    pub data: Vec<u8>,
+}
+
+fn perceived_brightness(c: [f64; 3]) -> f64 {
+    (0.299 * c[0] * c[0] + 0.587 * c[1] * c[1] + 0.114 * c[2] * c[2]).sqrt()
+}
+
+fn solve_metallic(diffuse: f64, specular: f64, one_minus_specular_strength: f64) -> f64 {
+    if specular < DIELECTRIC_SPECULAR {
+        return 0.0;
+    }
+    let a = DIELECTRIC_SPECULAR;
+    let b = diffuse * one_minus_specular_strength / (1.0 - DIELECTRIC_SPECULAR) + specular
+        - 2.0 * DIELECTRIC_SPECULAR;
+    let c = DIELECTRIC_SPECULAR - specular;
+    let d = (b * b - 4.0 * a * c).max(0.0);
+    ((-b + d.sqrt()) / (2.0 * a)).clamp(0.0, 1.0)
+}
+
+/// Linear diffuse rgba, specular rgb and glossiness into linear base color rgba, metallic and roughness.
+pub fn convert(diffuse: [f64; 4], specular: [f64; 3], glossiness: f64) -> ([f64; 4], f64, f64) {
+    let one_minus_specular_strength = 1.0 - specular[0].max(specular[1]).max(specular[2]);
+    let metallic = solve_metallic(
+        perceived_brightness([diffuse[0], diffuse[1], diffuse[2]]),
+        perceived_brightness(specular),
+        one_minus_specular_strength,
+    );
+
+    let from_diffuse =
+        one_minus_specular_strength / (1.0 - DIELECTRIC_SPECULAR) / (1.0 - metallic).max(EPSILON);
+    let t = metallic * metallic;
+    let base_color = [0, 1, 2].map(|i| {
+        let from_diffuse = diffuse[i] * from_diffuse;
+        let from_specular =
+            (specular[i] - DIELECTRIC_SPECULAR * (1.0 - metallic)) / metallic.max(EPSILON);
+        (from_diffuse + (from_specular - from_diffuse) * t).clamp(0.0, 1.0)
+    });
+
+    (
+        [base_color[0], base_color[1], base_color[2], diffuse[3]],
+        metallic,
+        1.0 - glossiness,
+    )
+}
+
+/// Metallic-roughness factors equivalent to the specular-glossiness ones.
+///
+/// Textured materials can't be expressed with factors alone: when any of the
+/// specular-glossiness textures is present, the factors are baked into `convert_images`
+/// output instead and this returns neutral factors. Texture references are left None,
+/// the caller adds the converted images to the document and fills them in.
+pub fn convert_factors(sg: &MaterialsPbrSpecularGlossiness) -> PBRMetallicRoughness {
+    if sg.diffuse_texture.is_some() || sg.specular_glossiness_texture.is_some() {
+        return PBRMetallicRoughness::default();
+    }
+    let (base_color, metallic, roughness) =
+        convert(sg.diffuse_factor, sg.specular_factor, sg.glossiness_factor);
+    PBRMetallicRoughness {
+        base_color_factor: base_color,
+        metallic_factor: metallic,
+        roughness_factor: roughness,
+        ..Default::default()
+    }
+}
+
+/// Replace material's `pbr_metallic_roughness` with `convert_factors` output.
+/// Returns false if the material does not use KHR_materials_pbrSpecularGlossiness.
+pub fn convert_material(material: &mut Material) -> bool {
+    let Some(sg) = material
+        .extensions
+        .as_ref()
+        .and_then(|e| e.pbr_specular_glossiness.as_ref())
+    else {
+        return false;
+    };
+    material.pbr_metallic_roughness = convert_factors(sg);
+    true
+}
+
+fn srgb_to_linear(x: u8) -> f64 {
+    let x = x as f64 / 255.0;
+    if x <= 0.04045 {
+        x / 12.92
+    } else {
+        ((x + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(x: f64) -> u8 {
+    let x = if x <= 0.0031308 {
+        x * 12.92
+    } else {
+        1.055 * x.powf(1.0 / 2.4) - 0.055
+    };
+    (x.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+fn linear_to_u8(x: f64) -> u8 {
+    (x.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+/// Per-pixel conversion of the decoded diffuse and specular-glossiness textures into
+/// (base color, metallic-roughness) images, with the material factors baked in.
+///
+/// Diffuse and specular are sRGB, glossiness (specular-glossiness alpha) is linear, same as
+/// the outputs: sRGB base color, linear roughness in G and metallic in B.
+/// Both images, when present, should be of the same size, resample beforehand if they are not.
+/// None if neither texture is supplied, if the sizes differ or if an image's data is not
+/// `width * height * 4` bytes long.
+pub fn convert_images(
+    sg: &MaterialsPbrSpecularGlossiness,
+    diffuse: Option<&Rgba8Image>,
+    specular_glossiness: Option<&Rgba8Image>,
+) -> Option<(Rgba8Image, Rgba8Image)> {
+    let (width, height) = diffuse
+        .or(specular_glossiness)
+        .map(|image| (image.width, image.height))?;
+    let len = width.checked_mul(height)?.checked_mul(4)?;
+    let matches = |image: Option<&Rgba8Image>| {
+        image.is_none_or(|image| {
+            image.width == width && image.height == height && image.data.len() == len
+        })
+    };
+    if !matches(diffuse) || !matches(specular_glossiness) {
+        return None;
+    }
+
+    let mut base_color = Vec::with_capacity(width * height * 4);
+    let mut metallic_roughness = Vec::with_capacity(width * height * 4);
+    for i in 0..width * height {
+        let mut d = sg.diffuse_factor;
+        if let Some(image) = diffuse {
+            let pixel = &image.data[i * 4..i * 4 + 4];
+            for c in 0..3 {
+                d[c] *= srgb_to_linear(pixel[c]);
+            }
+            d[3] *= pixel[3] as f64 / 255.0;
+        }
+        let mut s = sg.specular_factor;
+        let mut g = sg.glossiness_factor;
+        if let Some(image) = specular_glossiness {
+            let pixel = &image.data[i * 4..i * 4 + 4];
+            for c in 0..3 {
+                s[c] *= srgb_to_linear(pixel[c]);
+            }
+            g *= pixel[3] as f64 / 255.0;
+        }
+
+        let (color, metallic, roughness) = convert(d, s, g);
+        base_color.extend_from_slice(&[
+            linear_to_srgb(color[0]),
+            linear_to_srgb(color[1]),
+            linear_to_srgb(color[2]),
+            linear_to_u8(color[3]),
+        ]);
+        metallic_roughness.extend_from_slice(&[
+            0,
+            linear_to_u8(roughness),
+            linear_to_u8(metallic),
+            255,
+        ]);
+    }
+
+    Some((
+        Rgba8Image {
+            width,
+            height,
+            data: base_color,
+        },
+        Rgba8Image {
+            width,
+            height,
+            data: metallic_roughness,
+        },
+    ))
+}
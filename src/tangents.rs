@@ -0,0 +1,116 @@
+//! MikkTSpace compatible tangent generation.
+//!
+//! Spec requires clients to compute MikkTSpace tangents when a normal-mapped primitive
+//! has no TANGENT attribute. This follows the MikkTSpace basis: per-triangle tangent and
+//! bitangent from the uv derivatives, projected onto the vertex normal, weighted by the
+//! corner angle and accumulated separately for mirrored (orientation flipped) uv triangles.
+//! Vertices with equal position, normal and uv are welded before accumulation, the same
+//! way MikkTSpace treats them.
+
+use std::collections::HashMap;
+
+use crate::math::{self, Vec2, Vec3};
+use crate::{accessor, gltf::Primitive, Gltf};
+
+/// Tangents for a triangles primitive, with handedness in `w`, one per vertex.
+///
+/// `tex_coord` is the n in TEXCOORD_n, usually `material.normal_texture.tex_coord`.
+/// None if POSITION, NORMAL or TEXCOORD_n is missing or the primitive is not made of triangles.
+pub fn generate_tangents(
+    gltf: &Gltf,
+    buffers: &[Vec<u8>],
+    primitive: &Primitive,
+    tex_coord: usize,
+) -> Option<Vec<[f32; 4]>> {
+    let positions = primitive.attributes.get("POSITION")?;
+    let normals = primitive.attributes.get("NORMAL")?;
+    let uvs = primitive.attributes.get(&format!("TEXCOORD_{tex_coord}"))?;
+    let indices = accessor::triangle_indices(gltf, buffers, primitive)?;
+
+    Some(compute_tangents(
+        &accessor::read_vec3(gltf, buffers, *positions),
+        &accessor::read_vec3(gltf, buffers, *normals),
+        &accessor::read_vec2(gltf, buffers, *uvs),
+        &indices,
+    ))
+}
+
+/// Same as `generate_tangents`, but for already decoded vertex data.
+///
+/// `indices` is a triangle list. Vertices shared by both mirrored and non-mirrored triangles
+/// get the tangent of the group with the bigger weight; split such vertices beforehand to get
+/// the exact per-corner MikkTSpace result.
+pub fn compute_tangents(
+    positions: &[Vec3],
+    normals: &[Vec3],
+    uvs: &[Vec2],
+    indices: &[u32],
+) -> Vec<[f32; 4]> {
+    // vertex -> welded vertex index
+    let mut welded = HashMap::new();
+    let vertex_group: Vec<usize> = (0..positions.len())
+        .map(|i| {
+            let (p, n, t) = (positions[i], normals[i], uvs[i]);
+            let key = [p[0], p[1], p[2], n[0], n[1], n[2], t[0], t[1]].map(f32::to_bits);
+            let len = welded.len();
+            *welded.entry(key).or_insert(len)
+        })
+        .collect();
+
+    // [welded vertex][orientation] -> (sum of weighted tangents, sum of weights)
+    let mut groups = vec![[([0.0f32; 3], 0.0f32); 2]; welded.len()];
+
+    for triangle in indices.chunks_exact(3) {
+        let [i0, i1, i2] = [0, 1, 2].map(|i| triangle[i] as usize);
+        let (p0, p1, p2) = (positions[i0], positions[i1], positions[i2]);
+        let (t0, t1, t2) = (uvs[i0], uvs[i1], uvs[i2]);
+
+        let d1 = math::sub(p1, p0);
+        let d2 = math::sub(p2, p0);
+        let t21 = [t1[0] - t0[0], t1[1] - t0[1]];
+        let t31 = [t2[0] - t0[0], t2[1] - t0[1]];
+        let signed_area = t21[0] * t31[1] - t21[1] * t31[0];
+        if signed_area.abs() <= f32::MIN_POSITIVE {
+            continue;
+        }
+        let orientation = (signed_area > 0.0) as usize;
+        let sign = if signed_area > 0.0 { 1.0 } else { -1.0 };
+        let os = math::sub(math::scale(d1, t31[1]), math::scale(d2, t21[1]));
+        let os = math::scale(math::normalize(os), sign);
+
+        for corner in 0..3 {
+            let i = triangle[corner] as usize;
+            let prev = triangle[(corner + 2) % 3] as usize;
+            let next = triangle[(corner + 1) % 3] as usize;
+            let n = normals[i];
+            let project = |v: Vec3| math::normalize(math::sub(v, math::scale(n, math::dot(n, v))));
+
+            let tangent = project(os);
+            let e1 = project(math::sub(positions[next], positions[i]));
+            let e2 = project(math::sub(positions[prev], positions[i]));
+            let weight = math::angle(e1, e2);
+            if !weight.is_finite() || tangent == [0.0; 3] {
+                continue;
+            }
+            let group = &mut groups[vertex_group[i]][orientation];
+            group.0 = math::add(group.0, math::scale(tangent, weight));
+            group.1 += weight;
+        }
+    }
+
+    (0..positions.len())
+        .map(|i| {
+            let [flipped, preserved] = groups[vertex_group[i]];
+            let (sum, w) = if preserved.1 >= flipped.1 {
+                (preserved.0, 1.0)
+            } else {
+                (flipped.0, -1.0)
+            };
+            let mut t = math::normalize(sum);
+            if t == [0.0; 3] {
+                t = math::perpendicular(normals[i]);
+            }
+            [t[0], t[1], t[2], w]
+        })
+        .collect()
+}
@@ -0,0 +1,49 @@
+//! KHR_texture_transform helpers.
+//!
+//! The transform is `Translation * Rotation * Scale` applied to `(u, v, 1)`,
+//! rotation is counter-clockwise in uv space.
+
+use crate::gltf::{Primitive, TextureRef, TextureTransform};
+use crate::{accessor, Gltf};
+
+impl TextureTransform {
+    /// Column-major 3x3 uv matrix, ready to be uploaded as a mat3 uniform.
+    pub fn matrix(&self) -> [f32; 9] {
+        let (s, c) = (self.rotation as f32).sin_cos();
+        let [sx, sy] = self.scale.map(|x| x as f32);
+        let [ox, oy] = self.offset.map(|x| x as f32);
+        #[rustfmt::skip]
+        let m = [
+            c * sx, -s * sx, 0.0,
+            s * sy, c * sy, 0.0,
+            ox, oy, 1.0,
+        ];
+        m
+    }
+
+    pub fn apply(&self, uv: [f32; 2]) -> [f32; 2] {
+        let m = self.matrix();
+        [
+            m[0] * uv[0] + m[3] * uv[1] + m[6],
+            m[1] * uv[0] + m[4] * uv[1] + m[7],
+        ]
+    }
+}
+
+/// TEXCOORD_n data the texture reference samples with, with its transform baked in.
+///
+/// Texture references without KHR_texture_transform just get a copy of their TEXCOORD_n.
+/// None if the primitive does not have that TEXCOORD_n.
+pub fn baked_tex_coords(
+    gltf: &Gltf,
+    buffers: &[Vec<u8>],
+    primitive: &Primitive,
+    texture: &TextureRef,
+) -> Option<Vec<[f32; 2]>> {
+    let name = format!("TEXCOORD_{}", texture.effective_tex_coord());
+    let uvs = accessor::read_vec2(gltf, buffers, *primitive.attributes.get(&name)?);
+    Some(match texture.transform() {
+        Some(transform) => uvs.into_iter().map(|uv| transform.apply(uv)).collect(),
+        None => uvs,
+    })
+}
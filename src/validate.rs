@@ -0,0 +1,1369 @@
+//! Document validation.
+//!
+//! Checks everything the rest of nanogltf takes for granted: index references, buffer
+//! bounds, accessor layouts, attribute semantics and node hierarchy. Issue codes follow
+//! the Khronos glTF-Validator where an equivalent check exists, and each issue points to
+//! the offending property with a JSON pointer.
+//!
+//! Accessors that passed the layout checks are also decoded, to check indices and min/max.
+
+use std::collections::HashSet;
+
+use crate::gltf::{Accessor, ComponentType, MeshoptCompression, Node, Primitive};
+use crate::{accessor, animation, environment, lights, meshopt, variants, xmp, Gltf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Issue {
+    /// glTF-Validator style code, like "UNRESOLVED_REFERENCE".
+    pub code: &'static str,
+    pub severity: Severity,
+    /// JSON pointer to the property, like "/meshes/0/primitives/1/attributes/NORMAL".
+    pub pointer: String,
+    pub message: String,
+}
+
+#[derive(Debug, Default)]
+pub struct Report {
+    pub issues: Vec<Issue>,
+}
+
+impl Report {
+    pub fn errors(&self) -> impl Iterator<Item = &Issue> {
+        self.with_severity(Severity::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &Issue> {
+        self.with_severity(Severity::Warning)
+    }
+
+    pub fn infos(&self) -> impl Iterator<Item = &Issue> {
+        self.with_severity(Severity::Info)
+    }
+
+    /// No errors, warnings and infos are fine.
+    pub fn is_valid(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    fn with_severity(&self, severity: Severity) -> impl Iterator<Item = &Issue> {
+        self.issues
+            .iter()
+            .filter(move |issue| issue.severity == severity)
+    }
+}
+
+/// Escape a JSON pointer token, "~" and "/" are special there.
+fn escape(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+/// Accessor types and (component type, normalized) pairs allowed for an attribute.
+type AttributeFormats = (&'static [&'static str], &'static [(ComponentType, bool)]);
+
+/// Allowed formats for a vertex attribute semantic, None for unknown semantics.
+///
+/// `quantized` extends the lists with the KHR_mesh_quantization formats, the quantized
+/// lists are supersets of the core ones. Morph targets
+/// only get POSITION, NORMAL, TANGENT and TEXCOORD_n checked, other target semantics are
+/// application specific.
+fn attribute_formats(semantic: &str, is_target: bool, quantized: bool) -> Option<AttributeFormats> {
+    use ComponentType::*;
+
+    const FLOAT: &[(ComponentType, bool)] = &[(Float, false)];
+    const FLOAT_OR_NORMALIZED: &[(ComponentType, bool)] =
+        &[(Float, false), (UnsignedByte, true), (UnsignedShort, true)];
+    const UNSIGNED: &[(ComponentType, bool)] = &[(UnsignedByte, false), (UnsignedShort, false)];
+
+    const QUANTIZED_POSITION: &[(ComponentType, bool)] = &[
+        (Float, false),
+        (Byte, false),
+        (Byte, true),
+        (UnsignedByte, false),
+        (UnsignedByte, true),
+        (Short, false),
+        (Short, true),
+        (UnsignedShort, false),
+        (UnsignedShort, true),
+    ];
+    const QUANTIZED_DIRECTION: &[(ComponentType, bool)] =
+        &[(Float, false), (Byte, true), (Short, true)];
+    const QUANTIZED_DISPLACEMENT: &[(ComponentType, bool)] = &[
+        (Float, false),
+        (Byte, false),
+        (Byte, true),
+        (Short, false),
+        (Short, true),
+    ];
+    const TEXCOORD_DISPLACEMENT: &[(ComponentType, bool)] = &[
+        (Float, false),
+        (Byte, true),
+        (UnsignedByte, true),
+        (Short, true),
+        (UnsignedShort, true),
+    ];
+    const QUANTIZED_TEXCOORD_DISPLACEMENT: &[(ComponentType, bool)] = &[
+        (Float, false),
+        (Byte, false),
+        (Byte, true),
+        (UnsignedByte, true),
+        (Short, false),
+        (Short, true),
+        (UnsignedShort, true),
+    ];
+
+    let numbered = |prefix: &str| {
+        semantic
+            .strip_prefix(prefix)
+            .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+    };
+    Some(match (semantic, is_target, quantized) {
+        ("POSITION", false, false) | ("NORMAL", false, false) => (&["VEC3"], FLOAT),
+        ("POSITION", false, true) => (&["VEC3"], QUANTIZED_POSITION),
+        ("NORMAL", false, true) => (&["VEC3"], QUANTIZED_DIRECTION),
+        ("TANGENT", false, false) => (&["VEC4"], FLOAT),
+        ("TANGENT", false, true) => (&["VEC4"], QUANTIZED_DIRECTION),
+        (_, false, false) if numbered("TEXCOORD_") => (&["VEC2"], FLOAT_OR_NORMALIZED),
+        (_, false, true) if numbered("TEXCOORD_") => (&["VEC2"], QUANTIZED_POSITION),
+        (_, false, _) if numbered("COLOR_") => (&["VEC3", "VEC4"], FLOAT_OR_NORMALIZED),
+        (_, false, _) if numbered("JOINTS_") => (&["VEC4"], UNSIGNED),
+        (_, false, _) if numbered("WEIGHTS_") => (&["VEC4"], FLOAT_OR_NORMALIZED),
+        ("POSITION", true, false) | ("NORMAL", true, false) | ("TANGENT", true, false) => {
+            (&["VEC3"], FLOAT)
+        }
+        ("POSITION", true, true) => (&["VEC3"], QUANTIZED_DISPLACEMENT),
+        ("NORMAL", true, true) | ("TANGENT", true, true) => (&["VEC3"], QUANTIZED_DIRECTION),
+        (_, true, false) if numbered("TEXCOORD_") => (&["VEC2"], TEXCOORD_DISPLACEMENT),
+        (_, true, true) if numbered("TEXCOORD_") => (&["VEC2"], QUANTIZED_TEXCOORD_DISPLACEMENT),
+        _ => return None,
+    })
+}
+
+/// Allowed formats for an EXT_mesh_gpu_instancing attribute, None for custom ones.
+///
+/// `quantized` adds the KHR_mesh_quantization formats to TRANSLATION and SCALE.
+fn instance_attribute_formats(semantic: &str, quantized: bool) -> Option<AttributeFormats> {
+    use ComponentType::*;
+
+    const FLOAT: &[(ComponentType, bool)] = &[(Float, false)];
+    const ROTATION: &[(ComponentType, bool)] = &[(Float, false), (Byte, true), (Short, true)];
+    const QUANTIZED: &[(ComponentType, bool)] = &[
+        (Float, false),
+        (Byte, false),
+        (Byte, true),
+        (Short, false),
+        (Short, true),
+    ];
+
+    Some(match (semantic, quantized) {
+        ("TRANSLATION", false) | ("SCALE", false) => (&["VEC3"], FLOAT),
+        ("TRANSLATION", true) | ("SCALE", true) => (&["VEC3"], QUANTIZED),
+        ("ROTATION", _) => (&["VEC4"], ROTATION),
+        _ => return None,
+    })
+}
+
+const ACCESSOR_TYPES: &[&str] = &["SCALAR", "VEC2", "VEC3", "VEC4", "MAT2", "MAT3", "MAT4"];
+
+fn format_name(accessor: &Accessor) -> String {
+    format!(
+        "{}/{:?}{}",
+        accessor.type_.as_deref().unwrap_or("?"),
+        accessor.component_type,
+        if accessor.normalized {
+            " normalized"
+        } else {
+            ""
+        }
+    )
+}
+
+struct Validator<'a> {
+    gltf: &'a Gltf,
+    buffers: &'a [Vec<u8>],
+    report: Report,
+    /// Views that are inside their buffers data.
+    valid_views: Vec<bool>,
+    /// Accessors safe to decode with `accessor::read_*`.
+    readable_accessors: Vec<bool>,
+    /// KHR_mesh_quantization is declared, attributes may use its formats.
+    quantized: bool,
+    used_accessors: HashSet<usize>,
+    used_materials: HashSet<usize>,
+    used_meshes: HashSet<usize>,
+    used_textures: HashSet<usize>,
+    used_images: HashSet<usize>,
+    used_samplers: HashSet<usize>,
+}
+
+impl<'a> Validator<'a> {
+    fn issue(&mut self, severity: Severity, code: &'static str, pointer: String, message: String) {
+        self.report.issues.push(Issue {
+            code,
+            severity,
+            pointer,
+            message,
+        });
+    }
+
+    fn error(&mut self, code: &'static str, pointer: String, message: String) {
+        self.issue(Severity::Error, code, pointer, message);
+    }
+
+    fn warning(&mut self, code: &'static str, pointer: String, message: String) {
+        self.issue(Severity::Warning, code, pointer, message);
+    }
+
+    fn info(&mut self, code: &'static str, pointer: String, message: String) {
+        self.issue(Severity::Info, code, pointer, message);
+    }
+
+    /// Report an out of bounds index, true if the reference is fine.
+    fn reference(&mut self, pointer: String, index: usize, len: usize) -> bool {
+        if index >= len {
+            self.error(
+                "UNRESOLVED_REFERENCE",
+                pointer,
+                format!("Unresolved reference: {index}."),
+            );
+            return false;
+        }
+        true
+    }
+
+    fn asset(&mut self) {
+        let Some(asset) = &self.gltf.asset else {
+            self.error(
+                "UNDEFINED_PROPERTY",
+                "/asset".to_string(),
+                "Property 'asset' must be defined.".to_string(),
+            );
+            return;
+        };
+        let major = asset.version.split('.').next().unwrap_or("");
+        if major != "2" {
+            self.error(
+                "UNKNOWN_ASSET_MAJOR_VERSION",
+                "/asset/version".to_string(),
+                format!("Unknown glTF major asset version: {}.", asset.version),
+            );
+        }
+    }
+
+    fn extensions(&mut self) {
+        for (i, extension) in self.gltf.extensions_required.iter().enumerate() {
+            if !self.gltf.extensions_used.contains(extension) {
+                self.error(
+                    "UNUSED_EXTENSION_REQUIRED",
+                    format!("/extensionsRequired/{i}"),
+                    format!("Unused extension '{extension}' cannot be required."),
+                );
+            }
+        }
+    }
+
+    fn buffers(&mut self) {
+        for (i, buffer) in self.gltf.buffers.iter().enumerate() {
+            if buffer.is_fallback() {
+                continue;
+            }
+            let actual = self.buffers.get(i).map_or(0, |data| data.len());
+            if actual < buffer.byte_length {
+                self.error(
+                    "BUFFER_BYTE_LENGTH_MISMATCH",
+                    format!("/buffers/{i}/byteLength"),
+                    format!(
+                        "Actual data length {actual} is less than the declared buffer byteLength {}.",
+                        buffer.byte_length
+                    ),
+                );
+            }
+        }
+    }
+
+    fn buffer_views(&mut self) {
+        for (i, view) in self.gltf.buffer_views.iter().enumerate() {
+            let pointer = format!("/bufferViews/{i}");
+            let mut valid = self.reference(
+                format!("{pointer}/buffer"),
+                view.buffer,
+                self.gltf.buffers.len(),
+            );
+
+            if valid {
+                let declared = self.gltf.buffers[view.buffer].byte_length;
+                let actual = self.buffers.get(view.buffer).map_or(0, |data| data.len());
+                // None on overflow, no buffer is that long
+                let end = view.byte_offset.checked_add(view.byte_length);
+                if end.is_none_or(|end| end > declared) {
+                    self.error(
+                        "BUFFER_VIEW_TOO_LONG",
+                        pointer.clone(),
+                        format!(
+                            "BufferView does not fit buffer ({}) byteLength ({declared}).",
+                            view.buffer
+                        ),
+                    );
+                }
+                valid = end.is_some_and(|end| end <= actual);
+            }
+
+            if let Some(compression) = view.meshopt_compression() {
+                // the fallback buffer gets the decoded data, its reference was checked above
+                let fallback = self.gltf.buffers.get(view.buffer);
+                let end = view.byte_offset.checked_add(view.byte_length);
+                valid = self.meshopt_compression(i, compression)
+                    && fallback
+                        .zip(end)
+                        .is_some_and(|(b, end)| end <= b.byte_length);
+            }
+
+            if let Some(stride) = view.byte_stride {
+                if !(4..=252).contains(&stride) {
+                    self.error(
+                        "VALUE_NOT_IN_RANGE",
+                        format!("{pointer}/byteStride"),
+                        format!("Value {stride} is out of range."),
+                    );
+                } else if !stride.is_multiple_of(4) {
+                    self.error(
+                        "VALUE_MULTIPLE_OF",
+                        format!("{pointer}/byteStride"),
+                        format!("Value {stride} is not a multiple of 4."),
+                    );
+                }
+            }
+            self.valid_views.push(valid);
+        }
+    }
+
+    /// Compressed data fits its buffer and decodes, true if it does.
+    fn meshopt_compression(&mut self, view: usize, compression: &MeshoptCompression) -> bool {
+        let pointer = format!("/bufferViews/{view}/extensions/EXT_meshopt_compression");
+        if !self.reference(
+            format!("{pointer}/buffer"),
+            compression.buffer,
+            self.gltf.buffers.len(),
+        ) {
+            return false;
+        }
+        let actual = self
+            .buffers
+            .get(compression.buffer)
+            .map_or(0, |data| data.len());
+        let end = compression.byte_offset.checked_add(compression.byte_length);
+        if end.is_none_or(|end| end > actual) {
+            self.error(
+                "BUFFER_VIEW_TOO_LONG",
+                pointer,
+                format!(
+                    "Compressed data does not fit buffer ({}) data length ({actual}).",
+                    compression.buffer
+                ),
+            );
+            return false;
+        }
+        if meshopt::decode_buffer_view(self.gltf, self.buffers, view).is_none() {
+            self.error(
+                "MESHOPT_INVALID_DATA",
+                pointer,
+                "Compressed data is malformed or does not decode into bufferView byteLength."
+                    .to_string(),
+            );
+            return false;
+        }
+        true
+    }
+
+    /// Check that `length` bytes at `offset` fit into the view, true if it does.
+    /// `length` is None when computing it overflowed.
+    fn fits_view(
+        &mut self,
+        pointer: String,
+        view: usize,
+        offset: usize,
+        length: Option<usize>,
+    ) -> bool {
+        let view_length = self.gltf.buffer_views[view].byte_length;
+        let end = length.and_then(|length| offset.checked_add(length));
+        if end.is_none_or(|end| end > view_length) {
+            let length = length.map_or("overflow".to_string(), |length| length.to_string());
+            self.error(
+                "ACCESSOR_TOO_LONG",
+                pointer,
+                format!(
+                    "Accessor (offset: {offset}, length: {length}) does not fit referenced bufferView [{view}] length {view_length}."
+                ),
+            );
+            return false;
+        }
+        self.valid_views[view]
+    }
+
+    fn accessors(&mut self) {
+        for (i, accessor) in self.gltf.accessors.iter().enumerate() {
+            let readable = self.accessor(i, accessor);
+            self.readable_accessors.push(readable);
+            if readable {
+                self.sparse_indices(i, accessor);
+            }
+        }
+    }
+
+    /// Layout checks, true if the accessor is safe to decode.
+    fn accessor(&mut self, i: usize, accessor: &Accessor) -> bool {
+        let pointer = format!("/accessors/{i}");
+
+        match accessor.type_.as_deref() {
+            None => {
+                self.error(
+                    "UNDEFINED_PROPERTY",
+                    format!("{pointer}/type"),
+                    "Property 'type' must be defined.".to_string(),
+                );
+                return false;
+            }
+            Some(ty) if !ACCESSOR_TYPES.contains(&ty) => {
+                self.error(
+                    "VALUE_NOT_IN_LIST",
+                    format!("{pointer}/type"),
+                    format!("Invalid value '{ty}'."),
+                );
+                return false;
+            }
+            _ => {}
+        }
+
+        if accessor.count == 0 {
+            self.error(
+                "VALUE_NOT_IN_RANGE",
+                format!("{pointer}/count"),
+                "Value 0 is out of range.".to_string(),
+            );
+        }
+        if accessor.normalized
+            && matches!(
+                accessor.component_type,
+                ComponentType::Float | ComponentType::UnsignedInt
+            )
+        {
+            self.error(
+                "ACCESSOR_NORMALIZED_INVALID",
+                format!("{pointer}/normalized"),
+                "Only (u)byte and (u)short accessors can be normalized.".to_string(),
+            );
+        }
+
+        let component_size = accessor.component_type.byte_size();
+        let element_size = accessor::element_size(accessor);
+        let mut readable = true;
+
+        if let Some(view) = accessor.buffer_view {
+            if !self.reference(
+                format!("{pointer}/bufferView"),
+                view,
+                self.gltf.buffer_views.len(),
+            ) {
+                return false;
+            }
+            let buffer_view = &self.gltf.buffer_views[view];
+            if !accessor.byte_offset.is_multiple_of(component_size) {
+                self.error(
+                    "ACCESSOR_OFFSET_ALIGNMENT",
+                    format!("{pointer}/byteOffset"),
+                    format!(
+                        "Offset {} is not a multiple of componentType length {component_size}.",
+                        accessor.byte_offset
+                    ),
+                );
+            } else if !buffer_view.byte_offset.is_multiple_of(component_size) {
+                // byteOffset is aligned, the total is aligned when the view's offset is
+                self.error(
+                    "ACCESSOR_TOTAL_OFFSET_ALIGNMENT",
+                    format!("{pointer}/byteOffset"),
+                    format!(
+                        "Accessor's total byteOffset {} isn't a multiple of componentType length {component_size}.",
+                        buffer_view.byte_offset.saturating_add(accessor.byte_offset)
+                    ),
+                );
+            }
+            let stride = buffer_view.byte_stride.unwrap_or(element_size);
+            if stride < element_size {
+                self.error(
+                    "ACCESSOR_SMALL_BYTESTRIDE",
+                    pointer.clone(),
+                    format!(
+                        "Referenced bufferView's byteStride value {stride} is less than accessor element's length {element_size}."
+                    ),
+                );
+                readable = false;
+            }
+            let length = stride
+                .checked_mul(accessor.count.saturating_sub(1))
+                .and_then(|length| length.checked_add(element_size));
+            readable &= self.fits_view(pointer.clone(), view, accessor.byte_offset, length);
+        }
+
+        if let Some(sparse) = &accessor.sparse {
+            let pointer = format!("{pointer}/sparse");
+            if sparse.count > accessor.count {
+                self.error(
+                    "ACCESSOR_SPARSE_COUNT_OUT_OF_RANGE",
+                    format!("{pointer}/count"),
+                    format!(
+                        "Sparse accessor overrides more elements ({}) than the base accessor contains ({}).",
+                        sparse.count, accessor.count
+                    ),
+                );
+            }
+            let indices = &sparse.indices;
+            if indices.component_type == ComponentType::Float
+                || indices.component_type == ComponentType::Byte
+                || indices.component_type == ComponentType::Short
+            {
+                self.error(
+                    "VALUE_NOT_IN_LIST",
+                    format!("{pointer}/indices/componentType"),
+                    format!("Invalid value {:?}.", indices.component_type),
+                );
+                readable = false;
+            }
+            let views = self.gltf.buffer_views.len();
+            if self.reference(
+                format!("{pointer}/indices/bufferView"),
+                indices.buffer_view,
+                views,
+            ) {
+                readable &= self.fits_view(
+                    format!("{pointer}/indices"),
+                    indices.buffer_view,
+                    indices.byte_offset,
+                    sparse.count.checked_mul(indices.component_type.byte_size()),
+                );
+            } else {
+                readable = false;
+            }
+            let values = &sparse.values;
+            if self.reference(
+                format!("{pointer}/values/bufferView"),
+                values.buffer_view,
+                views,
+            ) {
+                readable &= self.fits_view(
+                    format!("{pointer}/values"),
+                    values.buffer_view,
+                    values.byte_offset,
+                    sparse.count.checked_mul(element_size),
+                );
+            } else {
+                readable = false;
+            }
+        }
+
+        readable
+    }
+
+    fn sparse_indices(&mut self, i: usize, accessor: &Accessor) {
+        let Some(sparse) = &accessor.sparse else {
+            return;
+        };
+        let indices = &sparse.indices;
+        let size = indices.component_type.byte_size();
+        let bytes = accessor::buffer_view_bytes(self.gltf, self.buffers, indices.buffer_view);
+        let bytes = &bytes[indices.byte_offset..];
+        let mut previous = None;
+        for k in 0..sparse.count {
+            let index = accessor::read_component_u32(&bytes[k * size..], &indices.component_type);
+            let pointer = format!("/accessors/{i}/sparse");
+            if index as usize >= accessor.count {
+                self.error(
+                    "ACCESSOR_SPARSE_INDEX_OOB",
+                    pointer,
+                    format!(
+                        "Accessor sparse indices element at index {k} has value {index} that is greater than the maximum {}.",
+                        accessor.count.saturating_sub(1)
+                    ),
+                );
+                self.readable_accessors[i] = false;
+                return;
+            }
+            if previous.is_some_and(|previous| index <= previous) {
+                self.error(
+                    "ACCESSOR_SPARSE_INDICES_NON_INCREASING",
+                    pointer,
+                    format!("Accessor sparse indices element at index {k} is less than or equal to previous: {}.", previous.unwrap()),
+                );
+            }
+            previous = Some(index);
+        }
+    }
+
+    /// Declared min/max against the data.
+    fn accessor_bounds(&mut self, positions: &HashSet<usize>) {
+        for (i, a) in self.gltf.accessors.iter().enumerate() {
+            if positions.contains(&i) && (a.min.is_none() || a.max.is_none()) {
+                self.error(
+                    "MESH_PRIMITIVE_POSITION_ACCESSOR_WITHOUT_BOUNDS",
+                    format!("/accessors/{i}"),
+                    "accessor.min and accessor.max must be defined for POSITION attribute accessor."
+                        .to_string(),
+                );
+            }
+            if !self.readable_accessors[i] || (a.min.is_none() && a.max.is_none()) {
+                continue;
+            }
+            let Some((min, max)) = accessor::compute_min_max(self.gltf, self.buffers, i) else {
+                continue;
+            };
+            let float = a.component_type == ComponentType::Float;
+            for (name, code, declared, actual) in [
+                ("min", "ACCESSOR_MIN_MISMATCH", &a.min, min),
+                ("max", "ACCESSOR_MAX_MISMATCH", &a.max, max),
+            ] {
+                let Some(declared) = declared else {
+                    continue;
+                };
+                if declared.len() != actual.len() {
+                    self.error(
+                        "INVALID_ARRAY_LENGTH",
+                        format!("/accessors/{i}/{name}"),
+                        format!(
+                            "Invalid array length {}. Valid lengths are: {}.",
+                            declared.len(),
+                            actual.len()
+                        ),
+                    );
+                    continue;
+                }
+                for (c, (declared, actual)) in declared.iter().zip(&actual).enumerate() {
+                    let same = if float {
+                        *declared as f32 == *actual as f32
+                    } else {
+                        declared == actual
+                    };
+                    if !same {
+                        self.error(
+                            code,
+                            format!("/accessors/{i}/{name}/{c}"),
+                            format!(
+                                "Declared {name} value for this component ({declared}) does not match actual {name} ({actual})."
+                            ),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    fn images(&mut self) {
+        for (i, image) in self.gltf.images.iter().enumerate() {
+            let pointer = format!("/images/{i}");
+            match (&image.uri, image.buffer_view) {
+                (Some(_), Some(_)) | (None, None) => self.error(
+                    "ONE_OF_MISMATCH",
+                    pointer,
+                    "Exactly one of ('bufferView', 'uri') properties must be defined.".to_string(),
+                ),
+                (None, Some(view)) => {
+                    self.reference(
+                        format!("{pointer}/bufferView"),
+                        view,
+                        self.gltf.buffer_views.len(),
+                    );
+                    if image.mime_type.is_none() {
+                        self.error(
+                            "UNSATISFIED_DEPENDENCY",
+                            pointer,
+                            "Dependency failed. 'mimeType' must be defined.".to_string(),
+                        );
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn image_based_lights(&mut self) {
+        for (l, light) in environment::image_based_lights(self.gltf)
+            .iter()
+            .enumerate()
+        {
+            let pointer = format!("/extensions/EXT_lights_image_based/lights/{l}");
+            if light.irradiance_coefficients.len() != 9 {
+                self.error(
+                    "INVALID_ARRAY_LENGTH",
+                    format!("{pointer}/irradianceCoefficients"),
+                    format!(
+                        "Invalid array length {}. Valid lengths are: 9.",
+                        light.irradiance_coefficients.len()
+                    ),
+                );
+            }
+            for (mip, faces) in light.specular_images.iter().enumerate() {
+                for (face, &image) in faces.iter().enumerate() {
+                    let pointer = format!("{pointer}/specularImages/{mip}/{face}");
+                    if self.reference(pointer, image, self.gltf.images.len()) {
+                        self.used_images.insert(image);
+                    }
+                }
+            }
+        }
+    }
+
+    fn textures(&mut self) {
+        for (i, texture) in self.gltf.textures.iter().enumerate() {
+            if let Some(source) = texture.source {
+                let pointer = format!("/textures/{i}/source");
+                if self.reference(pointer, source, self.gltf.images.len()) {
+                    self.used_images.insert(source);
+                }
+            }
+            if let Some(extensions) = &texture.extensions {
+                let sources = [
+                    ("KHR_texture_basisu", &extensions.khr_texture_basisu),
+                    ("EXT_texture_webp", &extensions.ext_texture_webp),
+                    ("EXT_texture_avif", &extensions.ext_texture_avif),
+                ];
+                for (name, extension) in sources {
+                    let Some(extension) = extension else {
+                        continue;
+                    };
+                    let pointer = format!("/textures/{i}/extensions/{name}/source");
+                    if self.reference(pointer, extension.source, self.gltf.images.len()) {
+                        self.used_images.insert(extension.source);
+                    }
+                }
+            }
+            if let Some(sampler) = texture.sampler {
+                let pointer = format!("/textures/{i}/sampler");
+                if self.reference(pointer, sampler, self.gltf.samplers.len()) {
+                    self.used_samplers.insert(sampler);
+                }
+            }
+        }
+    }
+
+    fn materials(&mut self) {
+        for (i, material) in self.gltf.materials.iter().enumerate() {
+            let pointer = format!("/materials/{i}");
+            for texture in material.textures() {
+                let pointer = format!("{pointer}{}/index", texture.path);
+                if self.reference(pointer, texture.index, self.gltf.textures.len()) {
+                    self.used_textures.insert(texture.index);
+                }
+            }
+            for (l, &lod) in material
+                .lod()
+                .map_or(&[][..], |lod| &lod.ids)
+                .iter()
+                .enumerate()
+            {
+                let pointer = format!("{pointer}/extensions/MSFT_lod/ids/{l}");
+                if self.reference(pointer, lod, self.gltf.materials.len()) {
+                    self.used_materials.insert(lod);
+                }
+            }
+
+            if !["", "OPAQUE", "MASK", "BLEND"].contains(&material.alpha_mode.as_str()) {
+                self.error(
+                    "VALUE_NOT_IN_LIST",
+                    format!("{pointer}/alphaMode"),
+                    format!("Invalid value '{}'.", material.alpha_mode),
+                );
+            }
+
+            let pbr = &material.pbr_metallic_roughness;
+            let factors = pbr
+                .base_color_factor
+                .iter()
+                .enumerate()
+                .map(|(c, x)| (format!("/pbrMetallicRoughness/baseColorFactor/{c}"), *x))
+                .chain([
+                    (
+                        "/pbrMetallicRoughness/metallicFactor".to_string(),
+                        pbr.metallic_factor,
+                    ),
+                    (
+                        "/pbrMetallicRoughness/roughnessFactor".to_string(),
+                        pbr.roughness_factor,
+                    ),
+                ])
+                .chain(
+                    material
+                        .emissive_factor
+                        .iter()
+                        .enumerate()
+                        .map(|(c, x)| (format!("/emissiveFactor/{c}"), *x)),
+                );
+            for (path, value) in factors {
+                if !(0.0..=1.0).contains(&value) {
+                    self.error(
+                        "VALUE_NOT_IN_RANGE",
+                        format!("{pointer}{path}"),
+                        format!("Value {value} is out of range."),
+                    );
+                }
+            }
+        }
+    }
+
+    fn meshes(&mut self, positions: &mut HashSet<usize>) {
+        for (m, mesh) in self.gltf.meshes.iter().enumerate() {
+            for (p, primitive) in mesh.primitives.iter().enumerate() {
+                self.primitive(&format!("/meshes/{m}/primitives/{p}"), primitive, positions);
+            }
+
+            let targets = mesh
+                .primitives
+                .first()
+                .and_then(|primitive| primitive.targets.as_ref())
+                .map_or(0, |targets| targets.len());
+            if let Some(weights) = &mesh.weights {
+                if weights.len() != targets {
+                    self.error(
+                        "MESH_INVALID_WEIGHTS_COUNT",
+                        format!("/meshes/{m}/weights"),
+                        format!(
+                            "The length of weights array ({}) does not match the number of morph targets ({targets}).",
+                            weights.len()
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Check attributes map, returns the common vertex count.
+    fn attributes(
+        &mut self,
+        pointer: &str,
+        attributes: &std::collections::HashMap<String, usize>,
+        is_target: bool,
+    ) -> Option<usize> {
+        let mut count = None;
+        let mut names: Vec<&String> = attributes.keys().collect();
+        names.sort();
+
+        for name in names {
+            let index = attributes[name];
+            let pointer = format!("{pointer}/{}", escape(name));
+            if !self.reference(pointer.clone(), index, self.gltf.accessors.len()) {
+                continue;
+            }
+            self.used_accessors.insert(index);
+            let accessor = &self.gltf.accessors[index];
+
+            if !name.starts_with('_') {
+                match attribute_formats(name, is_target, self.quantized) {
+                    None if is_target => {}
+                    None => self.error(
+                        "MESH_PRIMITIVE_INVALID_ATTRIBUTE",
+                        pointer.clone(),
+                        "Invalid attribute name.".to_string(),
+                    ),
+                    Some((types, components)) => {
+                        let valid_type = types.contains(&accessor.type_.as_deref().unwrap_or(""));
+                        let valid_component = components.iter().any(|(ty, normalized)| {
+                            *ty == accessor.component_type && *normalized == accessor.normalized
+                        });
+                        if !valid_type || !valid_component {
+                            self.error(
+                                "MESH_PRIMITIVE_ATTRIBUTES_ACCESSOR_INVALID_FORMAT",
+                                pointer.clone(),
+                                format!(
+                                    "Invalid accessor format '{}' for this attribute semantic.",
+                                    format_name(accessor)
+                                ),
+                            );
+                        }
+                    }
+                }
+            }
+
+            match count {
+                None => count = Some(accessor.count),
+                Some(count) if count != accessor.count => self.error(
+                    "MESH_PRIMITIVE_UNEQUAL_ACCESSOR_COUNT",
+                    pointer,
+                    "All accessors of the same primitive must have the same count.".to_string(),
+                ),
+                _ => {}
+            }
+        }
+        count
+    }
+
+    /// Check the EXT_mesh_gpu_instancing attributes of the node.
+    fn instancing(&mut self, pointer: &str, node: &Node) {
+        let Some(instancing) = node.gpu_instancing() else {
+            return;
+        };
+        let mut count = None;
+        let mut names: Vec<&String> = instancing.attributes.keys().collect();
+        names.sort();
+
+        for name in names {
+            let index = instancing.attributes[name];
+            let pointer = format!(
+                "{pointer}/extensions/EXT_mesh_gpu_instancing/attributes/{}",
+                escape(name)
+            );
+            if !self.reference(pointer.clone(), index, self.gltf.accessors.len()) {
+                continue;
+            }
+            self.used_accessors.insert(index);
+            let accessor = &self.gltf.accessors[index];
+
+            if let Some((types, components)) = instance_attribute_formats(name, self.quantized) {
+                let valid_type = types.contains(&accessor.type_.as_deref().unwrap_or(""));
+                let valid_component = components.iter().any(|(ty, normalized)| {
+                    *ty == accessor.component_type && *normalized == accessor.normalized
+                });
+                if !valid_type || !valid_component {
+                    self.error(
+                        "NODE_GPU_INSTANCING_ACCESSOR_INVALID_FORMAT",
+                        pointer.clone(),
+                        format!(
+                            "Invalid accessor format '{}' for this attribute semantic.",
+                            format_name(accessor)
+                        ),
+                    );
+                }
+            }
+
+            match count {
+                None => count = Some(accessor.count),
+                Some(count) if count != accessor.count => self.error(
+                    "NODE_GPU_INSTANCING_UNEQUAL_ACCESSOR_COUNT",
+                    pointer,
+                    "All accessors of the same instanced node must have the same count."
+                        .to_string(),
+                ),
+                _ => {}
+            }
+        }
+    }
+
+    fn primitive(&mut self, pointer: &str, primitive: &Primitive, positions: &mut HashSet<usize>) {
+        let attributes = &primitive.attributes;
+        let vertex_count = self.attributes(&format!("{pointer}/attributes"), attributes, false);
+
+        match attributes.get("POSITION") {
+            Some(position) => {
+                positions.insert(*position);
+            }
+            None => self.warning(
+                "MESH_PRIMITIVE_NO_POSITION",
+                format!("{pointer}/attributes"),
+                "No POSITION attribute found.".to_string(),
+            ),
+        }
+        if attributes.contains_key("TANGENT") && !attributes.contains_key("NORMAL") {
+            self.warning(
+                "MESH_PRIMITIVE_TANGENT_WITHOUT_NORMAL",
+                format!("{pointer}/attributes/TANGENT"),
+                "TANGENT attribute without NORMAL found.".to_string(),
+            );
+        }
+
+        for (t, target) in primitive.targets.iter().flatten().enumerate() {
+            let target_count = self.attributes(&format!("{pointer}/targets/{t}"), target, true);
+            if let Some(position) = target.get("POSITION") {
+                positions.insert(*position);
+            }
+            if target_count.is_some() && vertex_count.is_some() && target_count != vertex_count {
+                self.error(
+                    "MESH_PRIMITIVE_MORPH_TARGET_INVALID_ATTRIBUTE_COUNT",
+                    format!("{pointer}/targets/{t}"),
+                    "Morph target attributes must have the same count as the base attributes."
+                        .to_string(),
+                );
+            }
+        }
+
+        if let Some(indices) = primitive.indices {
+            self.indices(&format!("{pointer}/indices"), indices, vertex_count);
+        }
+
+        let num_variants = variants::variants(self.gltf).len();
+        for (i, mapping) in primitive.variant_mappings().iter().enumerate() {
+            let pointer = format!("{pointer}/extensions/KHR_materials_variants/mappings/{i}");
+            let material = mapping.material;
+            if self.reference(
+                format!("{pointer}/material"),
+                material,
+                self.gltf.materials.len(),
+            ) {
+                self.used_materials.insert(material);
+            }
+            for (v, &variant) in mapping.variants.iter().enumerate() {
+                self.reference(format!("{pointer}/variants/{v}"), variant, num_variants);
+            }
+        }
+
+        if let Some(material) = primitive.material {
+            let pointer = format!("{pointer}/material");
+            if self.reference(pointer.clone(), material, self.gltf.materials.len()) {
+                self.used_materials.insert(material);
+                let material = &self.gltf.materials[material];
+                for texture in material.textures() {
+                    let tex_coord = texture.effective_tex_coord();
+                    if !attributes.contains_key(&format!("TEXCOORD_{tex_coord}")) {
+                        self.error(
+                            "MESH_PRIMITIVE_TOO_FEW_TEXCOORDS",
+                            pointer.clone(),
+                            format!(
+                                "Material is incompatible with mesh primitive: Texture binding '{}' needs 'TEXCOORD_{tex_coord}' attribute.",
+                                &texture.path[1..]
+                            ),
+                        );
+                    }
+                }
+                if material.normal_texture.is_some() && !attributes.contains_key("TANGENT") {
+                    self.info(
+                        "MESH_PRIMITIVE_GENERATED_TANGENT_SPACE",
+                        pointer,
+                        "Material requires a tangent space but the mesh primitive does not provide it. Runtime-generated tangent space may be non-portable."
+                            .to_string(),
+                    );
+                }
+            }
+        }
+    }
+
+    fn indices(&mut self, pointer: &str, indices: usize, vertex_count: Option<usize>) {
+        if !self.reference(pointer.to_string(), indices, self.gltf.accessors.len()) {
+            return;
+        }
+        self.used_accessors.insert(indices);
+        let accessor = &self.gltf.accessors[indices];
+        let valid_format = accessor.type_.as_deref() == Some("SCALAR")
+            && !accessor.normalized
+            && matches!(
+                accessor.component_type,
+                ComponentType::UnsignedByte
+                    | ComponentType::UnsignedShort
+                    | ComponentType::UnsignedInt
+            );
+        if !valid_format {
+            self.error(
+                "MESH_PRIMITIVE_INDICES_ACCESSOR_INVALID_FORMAT",
+                pointer.to_string(),
+                format!(
+                    "Invalid indices accessor format '{}'.",
+                    format_name(accessor)
+                ),
+            );
+            return;
+        }
+        if !self.readable_accessors[indices] {
+            return;
+        }
+
+        let restart = match accessor.component_type {
+            ComponentType::UnsignedByte => u8::MAX as u32,
+            ComponentType::UnsignedShort => u16::MAX as u32,
+            _ => u32::MAX,
+        };
+        let data = accessor::read_u32(self.gltf, self.buffers, indices);
+        for (k, index) in data.iter().enumerate() {
+            if *index == restart {
+                self.error(
+                    "ACCESSOR_INDEX_PRIMITIVE_RESTART",
+                    format!("/accessors/{indices}"),
+                    format!(
+                        "Indices accessor contains primitive restart value ({index}) at index {k}."
+                    ),
+                );
+                return;
+            }
+            if let Some(vertex_count) = vertex_count {
+                if *index as usize >= vertex_count {
+                    self.error(
+                        "ACCESSOR_INDEX_OOB",
+                        format!("/accessors/{indices}"),
+                        format!(
+                            "Indices accessor element at index {k} has value {index} that is greater than the maximum vertex index available ({}).",
+                            vertex_count as i64 - 1
+                        ),
+                    );
+                    return;
+                }
+            }
+        }
+    }
+
+    fn nodes(&mut self) {
+        let mut parents = vec![None; self.gltf.nodes.len()];
+
+        for (i, node) in self.gltf.nodes.iter().enumerate() {
+            let pointer = format!("/nodes/{i}");
+            let light = node
+                .extensions
+                .as_ref()
+                .and_then(|e| e.khr_lights_punctual.as_ref());
+            if let Some(light) = light {
+                self.reference(
+                    format!("{pointer}/extensions/KHR_lights_punctual/light"),
+                    light.light,
+                    lights::lights(self.gltf).len(),
+                );
+            }
+            self.instancing(&pointer, node);
+            for (l, &lod) in node
+                .lod()
+                .map_or(&[][..], |lod| &lod.ids)
+                .iter()
+                .enumerate()
+            {
+                self.reference(
+                    format!("{pointer}/extensions/MSFT_lod/ids/{l}"),
+                    lod,
+                    self.gltf.nodes.len(),
+                );
+            }
+            if let Some(mesh) = node.mesh {
+                if self.reference(format!("{pointer}/mesh"), mesh, self.gltf.meshes.len()) {
+                    self.used_meshes.insert(mesh);
+                    let targets = self.gltf.meshes[mesh]
+                        .primitives
+                        .first()
+                        .and_then(|primitive| primitive.targets.as_ref())
+                        .map_or(0, |targets| targets.len());
+                    if let Some(weights) = &node.weights {
+                        if weights.len() != targets {
+                            self.error(
+                                "NODE_WEIGHTS_INVALID",
+                                format!("{pointer}/weights"),
+                                format!(
+                                    "The length of weights array ({}) does not match the number of morph targets ({targets}).",
+                                    weights.len()
+                                ),
+                            );
+                        }
+                    }
+                }
+            }
+
+            if node.matrix.is_some()
+                && (node.translation.is_some() || node.rotation.is_some() || node.scale.is_some())
+            {
+                self.error(
+                    "NODE_MATRIX_TRS",
+                    format!("{pointer}/matrix"),
+                    "A node can have either a matrix or any combination of translation/rotation/scale (TRS) properties."
+                        .to_string(),
+                );
+            }
+            if let Some(rotation) = node.rotation {
+                let length = rotation.iter().map(|x| x * x).sum::<f64>().sqrt();
+                if (length - 1.0).abs() > 0.00769 {
+                    self.error(
+                        "ROTATION_NON_UNIT",
+                        format!("{pointer}/rotation"),
+                        "Rotation quaternion must be normalized.".to_string(),
+                    );
+                }
+            }
+
+            for (c, child) in node.children.iter().enumerate() {
+                let pointer = format!("{pointer}/children/{c}");
+                if !self.reference(pointer.clone(), *child, self.gltf.nodes.len()) {
+                    continue;
+                }
+                match parents[*child] {
+                    Some(parent) => self.error(
+                        "NODE_PARENT_OVERRIDE",
+                        pointer,
+                        format!("Value overrides parent of node {child} ({parent})."),
+                    ),
+                    None => parents[*child] = Some(i),
+                }
+            }
+        }
+
+        // with at most one parent per node, a loop is a parent chain coming back to the node
+        for node in 0..self.gltf.nodes.len() {
+            let mut parent = parents[node];
+            let mut steps = 0;
+            while let Some(p) = parent {
+                if p == node {
+                    self.error(
+                        "NODE_LOOP",
+                        format!("/nodes/{node}"),
+                        "Node is a part of a node loop.".to_string(),
+                    );
+                    break;
+                }
+                // a chain leading into a loop without this node in it
+                if steps > self.gltf.nodes.len() {
+                    break;
+                }
+                parent = parents[p];
+                steps += 1;
+            }
+        }
+
+        for (s, scene) in self.gltf.scenes.iter().enumerate() {
+            for (n, node) in scene.nodes.iter().enumerate() {
+                let pointer = format!("/scenes/{s}/nodes/{n}");
+                if self.reference(pointer.clone(), *node, self.gltf.nodes.len())
+                    && parents[*node].is_some()
+                {
+                    self.error(
+                        "SCENE_NON_ROOT_NODE",
+                        pointer,
+                        format!("Node {node} is not a root node."),
+                    );
+                }
+            }
+        }
+        if let Some(scene) = self.gltf.scene {
+            self.reference("/scene".to_string(), scene, self.gltf.scenes.len());
+        }
+        for (s, scene) in self.gltf.scenes.iter().enumerate() {
+            if let Some(light) = scene.image_based_light() {
+                self.reference(
+                    format!("/scenes/{s}/extensions/EXT_lights_image_based/light"),
+                    light,
+                    environment::image_based_lights(self.gltf).len(),
+                );
+            }
+        }
+    }
+
+    fn animations(&mut self) {
+        for (a, anim) in self.gltf.animations.iter().enumerate() {
+            for (c, channel) in anim.channels.iter().enumerate() {
+                let pointer = format!("/animations/{a}/channels/{c}");
+                self.reference(
+                    format!("{pointer}/sampler"),
+                    channel.sampler,
+                    anim.samplers.len(),
+                );
+                let target = &channel.target;
+                if target.path == "pointer" {
+                    let resolved = target
+                        .pointer()
+                        .is_some_and(|_| animation::channel_target(self.gltf, channel).is_some());
+                    if !resolved {
+                        self.error(
+                            "UNRESOLVED_REFERENCE",
+                            format!("{pointer}/target/extensions/KHR_animation_pointer/pointer"),
+                            format!(
+                                "Unresolved animation pointer: {}.",
+                                target.pointer().unwrap_or_default()
+                            ),
+                        );
+                    }
+                } else if let Some(node) = target.node {
+                    self.reference(
+                        format!("{pointer}/target/node"),
+                        node,
+                        self.gltf.nodes.len(),
+                    );
+                }
+            }
+            for (s, sampler) in anim.samplers.iter().enumerate() {
+                let pointer = format!("/animations/{a}/samplers/{s}");
+                for (name, accessor) in [("input", sampler.input), ("output", sampler.output)] {
+                    if self.reference(
+                        format!("{pointer}/{name}"),
+                        accessor,
+                        self.gltf.accessors.len(),
+                    ) {
+                        self.used_accessors.insert(accessor);
+                    }
+                }
+            }
+        }
+    }
+
+    fn xmp(&mut self) {
+        let packets = xmp::packets(self.gltf).len();
+        for reference in xmp::references(self.gltf) {
+            self.reference(
+                format!("{}/extensions/KHR_xmp_json_ld/packet", reference.object),
+                reference.packet,
+                packets,
+            );
+        }
+    }
+
+    fn unused(&mut self) {
+        let gltf = self.gltf;
+        let objects = [
+            (
+                "accessors",
+                gltf.accessors.len(),
+                self.used_accessors.clone(),
+            ),
+            ("images", gltf.images.len(), self.used_images.clone()),
+            (
+                "materials",
+                gltf.materials.len(),
+                self.used_materials.clone(),
+            ),
+            ("meshes", gltf.meshes.len(), self.used_meshes.clone()),
+            ("samplers", gltf.samplers.len(), self.used_samplers.clone()),
+            ("textures", gltf.textures.len(), self.used_textures.clone()),
+        ];
+        for (name, len, used) in objects {
+            for i in (0..len).filter(|i| !used.contains(i)) {
+                self.info(
+                    "UNUSED_OBJECT",
+                    format!("/{name}/{i}"),
+                    "This object may be unused.".to_string(),
+                );
+            }
+        }
+    }
+}
+
+/// Validate the document against the already loaded `buffers`.
+///
+/// Never panics on malformed documents, everything wrong is reported as an `Issue`.
+pub fn validate(gltf: &Gltf, buffers: &[Vec<u8>]) -> Report {
+    let mut validator = Validator {
+        gltf,
+        buffers,
+        report: Report::default(),
+        valid_views: vec![],
+        readable_accessors: vec![],
+        quantized: gltf
+            .extensions_used
+            .iter()
+            .any(|extension| extension == "KHR_mesh_quantization"),
+        used_accessors: HashSet::new(),
+        used_materials: HashSet::new(),
+        used_meshes: HashSet::new(),
+        used_textures: HashSet::new(),
+        used_images: HashSet::new(),
+        used_samplers: HashSet::new(),
+    };
+    let mut positions = HashSet::new();
+
+    validator.asset();
+    validator.extensions();
+    validator.buffers();
+    validator.buffer_views();
+    validator.accessors();
+    validator.images();
+    validator.textures();
+    validator.image_based_lights();
+    validator.materials();
+    validator.meshes(&mut positions);
+    validator.accessor_bounds(&positions);
+    validator.nodes();
+    validator.animations();
+    validator.xmp();
+    validator.unused();
+
+    validator.report
+}
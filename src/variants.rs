@@ -0,0 +1,53 @@
+//! KHR_materials_variants material switching.
+
+use crate::gltf::{MaterialVariant, Primitive};
+use crate::Gltf;
+
+/// All the document's variants.
+pub fn variants(gltf: &Gltf) -> &[MaterialVariant] {
+    gltf.extensions
+        .as_ref()
+        .and_then(|e| e.khr_materials_variants.as_ref())
+        .map_or(&[], |e| &e.variants)
+}
+
+/// Index of the variant with the given name.
+pub fn variant_index(gltf: &Gltf, name: &str) -> Option<usize> {
+    variants(gltf)
+        .iter()
+        .position(|variant| variant.name == name)
+}
+
+/// Material of the primitive for the variant: the mapped one, or `primitive.material`
+/// when the variant is not mapped.
+pub fn primitive_material(primitive: &Primitive, variant: usize) -> Option<usize> {
+    primitive
+        .variant_mappings()
+        .iter()
+        .find(|mapping| mapping.variants.contains(&variant))
+        .map_or(primitive.material, |mapping| Some(mapping.material))
+}
+
+/// Effective material of every primitive with the variant applied,
+/// indexed as `gltf.meshes[mesh].primitives[primitive]`.
+/// `None` for the variant gives the default materials.
+pub fn materials(gltf: &Gltf, variant: Option<usize>) -> Vec<Vec<Option<usize>>> {
+    gltf.meshes
+        .iter()
+        .map(|mesh| {
+            mesh.primitives
+                .iter()
+                .map(|primitive| match variant {
+                    Some(variant) => primitive_material(primitive, variant),
+                    None => primitive.material,
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// `materials` for the variant with the given name, None if there is no such variant.
+pub fn materials_by_name(gltf: &Gltf, name: &str) -> Option<Vec<Vec<Option<usize>>>> {
+    let variant = variant_index(gltf, name)?;
+    Some(materials(gltf, Some(variant)))
+}
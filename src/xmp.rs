@@ -0,0 +1,93 @@
+//! KHR_xmp_json_ld metadata.
+//!
+//! Packets are kept as parsed `JsonValue`s, key order included, so licensing and
+//! authoring metadata like "dc:rights" or "dc:creator" serializes back unchanged.
+//! nanogltf has no glTF writer: `extension_json` gives the root extension object to
+//! put back into a written document, `XmpPacket` serializes the object references.
+
+use nanoserde::SerJson;
+
+use crate::gltf::JsonValue;
+use crate::Gltf;
+
+macro_rules! impl_xmp_packet {
+    ($($ty:ty),*) => {
+        $(
+            impl $ty {
+                /// Index into `packets` of the object's KHR_xmp_json_ld reference.
+                pub fn xmp_packet(&self) -> Option<usize> {
+                    Some(self.extensions.as_ref()?.khr_xmp_json_ld.as_ref()?.packet)
+                }
+            }
+        )*
+    };
+}
+
+impl_xmp_packet!(
+    crate::gltf::Asset,
+    crate::gltf::Node,
+    crate::gltf::Mesh,
+    crate::gltf::Material,
+    crate::gltf::Image,
+    crate::gltf::Animation
+);
+
+/// All the document's XMP packets.
+pub fn packets(gltf: &Gltf) -> &[JsonValue] {
+    gltf.extensions
+        .as_ref()
+        .and_then(|e| e.khr_xmp_json_ld.as_ref())
+        .map_or(&[], |e| &e.packets)
+}
+
+/// The root KHR_xmp_json_ld extension object, `{"packets":[...]}`, for writing the packets
+/// back. None if the document has no packets.
+pub fn extension_json(gltf: &Gltf) -> Option<String> {
+    let extension = gltf.extensions.as_ref()?.khr_xmp_json_ld.as_ref()?;
+    Some(extension.serialize_json())
+}
+
+/// Document object referencing a packet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PacketReference {
+    /// JSON pointer of the object, like "/asset" or "/materials/2".
+    pub object: String,
+    /// Index into `packets`.
+    pub packet: usize,
+}
+
+/// Every asset, node, mesh, material, image and animation referencing a packet.
+pub fn references(gltf: &Gltf) -> Vec<PacketReference> {
+    let mut res = vec![];
+    let mut push = |object: String, packet: Option<usize>| {
+        if let Some(packet) = packet {
+            res.push(PacketReference { object, packet });
+        }
+    };
+    push(
+        "/asset".to_string(),
+        gltf.asset.as_ref().and_then(|a| a.xmp_packet()),
+    );
+    for (i, node) in gltf.nodes.iter().enumerate() {
+        push(format!("/nodes/{i}"), node.xmp_packet());
+    }
+    for (i, mesh) in gltf.meshes.iter().enumerate() {
+        push(format!("/meshes/{i}"), mesh.xmp_packet());
+    }
+    for (i, material) in gltf.materials.iter().enumerate() {
+        push(format!("/materials/{i}"), material.xmp_packet());
+    }
+    for (i, image) in gltf.images.iter().enumerate() {
+        push(format!("/images/{i}"), image.xmp_packet());
+    }
+    for (i, animation) in gltf.animations.iter().enumerate() {
+        push(format!("/animations/{i}"), animation.xmp_packet());
+    }
+    res
+}
+
+/// The asset's packet, the metadata of the whole document.
+pub fn asset_packet(gltf: &Gltf) -> Option<&JsonValue> {
+    let packet = gltf.asset.as_ref()?.xmp_packet()?;
+    packets(gltf).get(packet)
+}
@@ -0,0 +1,120 @@
+mod common;
+
+use nanogltf::accessor::AccessorView;
+use nanogltf::Gltf;
+
+#[test]
+fn unknown_component_type_is_a_parse_error() {
+    let accessor = |sparse_type: u32, type_: u32| {
+        format!(
+            r#"{{
+                "asset": {{"version": "2.0"}},
+                "accessors": [{{
+                    "componentType": {type_}, "count": 1, "type": "SCALAR",
+                    "sparse": {{
+                        "count": 1,
+                        "indices": {{"bufferView": 0, "componentType": {sparse_type}}},
+                        "values": {{"bufferView": 0}}
+                    }}
+                }}]
+            }}"#
+        )
+    };
+    assert!(Gltf::from_json(&accessor(5121, 5126)).is_ok());
+    assert!(Gltf::from_json(&accessor(5124, 5126)).is_err());
+    assert!(Gltf::from_json(&accessor(5121, 1)).is_err());
+}
+
+/// Floats 1 to 8 followed by 4 zero bytes, read tightly packed, strided and misaligned.
+const VIEWS: &str = r#"{
+    "asset": {"version": "2.0"},
+    "buffers": [{
+        "byteLength": 36,
+        "uri": "data:application/octet-stream;base64,AACAPwAAAEAAAEBAAACAQAAAoEAAAMBAAADgQAAAAEEAAAAA"
+    }],
+    "bufferViews": [
+        {"buffer": 0, "byteLength": 32},
+        {"buffer": 0, "byteLength": 32, "byteStride": 8},
+        {"buffer": 0, "byteOffset": 2, "byteLength": 32}
+    ],
+    "accessors": [
+        {"bufferView": 0, "componentType": 5126, "count": 4, "type": "VEC2"},
+        {"bufferView": 1, "componentType": 5126, "count": 4, "type": "SCALAR"},
+        {"bufferView": 1, "byteOffset": 4, "componentType": 5126, "count": 4, "type": "SCALAR"},
+        {"bufferView": 2, "componentType": 5126, "count": 2, "type": "SCALAR"},
+        {"bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3"},
+        {"bufferView": 0, "componentType": 5126, "count": 18446744073709551615, "type": "SCALAR"},
+        {"bufferView": 0, "byteOffset": 18446744073709551615, "componentType": 5126, "count": 1, "type": "SCALAR"},
+        {"bufferView": 0, "componentType": 5121, "count": 1, "type": "MAT2"},
+        {"componentType": 5126, "count": 1, "type": "SCALAR"}
+    ]
+}"#;
+
+#[test]
+fn packed_view() {
+    let (gltf, buffers) = common::load(VIEWS);
+    // the fast path below needs the buffer allocation itself aligned for f32
+    assert!((buffers[0].as_ptr() as *const f32).is_aligned());
+
+    let view = AccessorView::<[f32; 2]>::new(&gltf, &buffers, 0).unwrap();
+    assert_eq!(view.len(), 4);
+    assert_eq!(view.stride(), 8);
+    assert_eq!(view.bytes().len(), 32);
+    assert_eq!(view.get(1), Some([3.0, 4.0]));
+    assert_eq!(view.get(4), None);
+    let elements: Vec<_> = view.iter().collect();
+    assert_eq!(elements, [[1.0, 2.0], [3.0, 4.0], [5.0, 6.0], [7.0, 8.0]]);
+    // borrowed straight from the buffer
+    let slice = view.as_slice().unwrap();
+    assert_eq!(slice, elements);
+    assert_eq!(slice.as_ptr() as *const u8, buffers[0].as_ptr());
+    assert!(matches!(view.to_slice(), std::borrow::Cow::Borrowed(_)));
+
+    // other element types than the accessor's
+    assert!(AccessorView::<[f32; 3]>::new(&gltf, &buffers, 0).is_none());
+    assert!(AccessorView::<f32>::new(&gltf, &buffers, 0).is_none());
+    assert!(AccessorView::<[u16; 2]>::new(&gltf, &buffers, 0).is_none());
+}
+
+#[test]
+fn strided_view() {
+    let (gltf, buffers) = common::load(VIEWS);
+    let view = AccessorView::<f32>::new(&gltf, &buffers, 1).unwrap();
+    assert_eq!(view.stride(), 8);
+    // up to the end of the last element, not the stride
+    assert_eq!(view.bytes().len(), 28);
+    assert_eq!(view.iter().collect::<Vec<_>>(), [1.0, 3.0, 5.0, 7.0]);
+    assert_eq!(view.get(3), Some(7.0));
+    // padded elements can't be a slice
+    assert!(view.as_slice().is_none());
+    assert_eq!(*view.to_slice(), [1.0, 3.0, 5.0, 7.0]);
+
+    let view = AccessorView::<f32>::new(&gltf, &buffers, 2).unwrap();
+    assert_eq!(view.iter().collect::<Vec<_>>(), [2.0, 4.0, 6.0, 8.0]);
+}
+
+#[test]
+fn misaligned_view() {
+    let (gltf, buffers) = common::load(VIEWS);
+    assert!((buffers[0].as_ptr() as *const f32).is_aligned());
+    let view = AccessorView::<f32>::new(&gltf, &buffers, 3).unwrap();
+    assert!(view.as_slice().is_none());
+    let expected = [2, 6].map(|i| f32::from_le_bytes(buffers[0][i..i + 4].try_into().unwrap()));
+    assert_eq!(view.get(0), Some(expected[0]));
+    assert_eq!(*view.to_slice(), expected);
+}
+
+#[test]
+fn invalid_views() {
+    let (gltf, buffers) = common::load(VIEWS);
+    // past the view end
+    assert!(AccessorView::<[f32; 3]>::new(&gltf, &buffers, 4).is_none());
+    // count and offset overflowing the length computation
+    assert!(AccessorView::<f32>::new(&gltf, &buffers, 5).is_none());
+    assert!(AccessorView::<f32>::new(&gltf, &buffers, 6).is_none());
+    // MAT2 of bytes has padded columns
+    assert!(AccessorView::<[u8; 4]>::new(&gltf, &buffers, 7).is_none());
+    // no buffer view, no such accessor
+    assert!(AccessorView::<f32>::new(&gltf, &buffers, 8).is_none());
+    assert!(AccessorView::<f32>::new(&gltf, &buffers, 9).is_none());
+}
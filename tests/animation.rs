@@ -0,0 +1,88 @@
+mod common;
+
+use nanogltf::animation::{self, Property};
+
+#[test]
+fn pointers_are_bounds_checked() {
+    let (gltf, _) = common::fixture("quad.gltf");
+    let resolve = |pointer| Property::from_pointer(&gltf, pointer);
+
+    assert_eq!(
+        resolve("/nodes/1/translation"),
+        Some(Property::NodeTranslation(1))
+    );
+    assert_eq!(resolve("/nodes/2/translation"), None);
+    assert_eq!(
+        resolve("/materials/0/pbrMetallicRoughness/baseColorFactor"),
+        Some(Property::BaseColorFactor(0))
+    );
+    assert_eq!(resolve("/materials/1/emissiveFactor"), None);
+    assert_eq!(resolve("/meshes/0/weights"), Some(Property::MeshWeights(0)));
+
+    // valid objects without a typed property
+    for pointer in [
+        "/accessors/3/min",
+        "/nodes/0/extensions/EXT_unknown/value",
+        "/materials/0/pbrMetallicRoughness/baseColorTexture/extensions/KHR_texture_transform/offset",
+        "/cameras/0/perspective/yfov",
+    ] {
+        assert_eq!(resolve(pointer), Some(Property::Other(pointer.to_string())));
+    }
+    // missing objects in the other root arrays
+    for pointer in [
+        "/accessors/4/min",
+        "/bufferViews/5/byteLength",
+        "/textures/0/extensions/EXT_unknown/value",
+        "/samplers/0/magFilter",
+        "/scenes/x/name",
+        "/extensions/KHR_lights_punctual/lights/0/intensity",
+    ] {
+        assert_eq!(resolve(pointer), None, "{pointer}");
+    }
+}
+
+/// Keyframes at 1, 2 and 3 with values 10, 20 and 30, and keyframes at NaN, 1 and 2.
+const KEYFRAMES: &str = r#"{
+    "asset": {"version": "2.0"},
+    "buffers": [{
+        "byteLength": 36,
+        "uri": "data:application/octet-stream;base64,AACAPwAAAEAAAEBAAAAgQQAAoEEAAPBBAADAfwAAgD8AAABA"
+    }],
+    "bufferViews": [{"buffer": 0, "byteLength": 36}],
+    "accessors": [
+        {"bufferView": 0, "componentType": 5126, "count": 3, "type": "SCALAR"},
+        {"bufferView": 0, "byteOffset": 12, "componentType": 5126, "count": 3, "type": "SCALAR"},
+        {"bufferView": 0, "byteOffset": 24, "componentType": 5126, "count": 3, "type": "SCALAR"}
+    ],
+    "nodes": [{}],
+    "animations": [{
+        "channels": [{"sampler": 0, "target": {"node": 0, "path": "weights"}}],
+        "samplers": [
+            {"input": 0, "output": 1},
+            {"input": 2, "output": 1},
+            {"input": 0, "output": 1, "interpolation": "STEP"}
+        ]
+    }]
+}"#;
+
+#[test]
+fn sample_out_of_range_times() {
+    let (gltf, buffers) = common::load(KEYFRAMES);
+    let samplers = &gltf.animations[0].samplers;
+    let sample =
+        |sampler, time| animation::sample(&gltf, &buffers, &samplers[sampler], time, false);
+
+    assert_eq!(sample(0, 1.5), [15.0]);
+    assert_eq!(sample(2, 2.5), [20.0]);
+    // clamped to the first and last keyframes
+    assert_eq!(sample(0, 0.0), [10.0]);
+    assert_eq!(sample(0, f32::NEG_INFINITY), [10.0]);
+    assert_eq!(sample(0, 4.0), [30.0]);
+    assert_eq!(sample(0, f32::NAN), [10.0]);
+    assert_eq!(sample(2, f32::NAN), [10.0]);
+
+    // a NaN first keyframe compares false with every time
+    assert_eq!(sample(1, 0.5), [10.0]);
+    assert_eq!(sample(1, 1.5), [25.0]);
+    assert_eq!(sample(1, 3.0), [30.0]);
+}
@@ -0,0 +1,33 @@
+#![allow(dead_code)]
+
+use nanogltf::{utils, Gltf};
+
+/// Parse a document and load its buffers, data URIs or files next to the fixtures.
+pub fn load(json: &str) -> (Gltf, Vec<Vec<u8>>) {
+    let gltf = Gltf::from_json(json).unwrap();
+    let buffers = gltf
+        .buffers
+        .iter()
+        .map(|buffer| match buffer.uri.as_deref().map(utils::parse_uri) {
+            Some(utils::UriData::Bytes(bytes)) => bytes,
+            Some(utils::UriData::RelativePath(path)) => std::fs::read(fixture_path(&path)).unwrap(),
+            None => vec![],
+        })
+        .collect();
+    (gltf, buffers)
+}
+
+pub fn fixture_path(name: &str) -> String {
+    format!("{}/tests/fixtures/{name}", env!("CARGO_MANIFEST_DIR"))
+}
+
+pub fn fixture(name: &str) -> (Gltf, Vec<Vec<u8>>) {
+    load(&std::fs::read_to_string(fixture_path(name)).unwrap())
+}
+
+pub fn assert_close(a: &[f32], b: &[f32], epsilon: f32) {
+    assert_eq!(a.len(), b.len(), "{a:?} != {b:?}");
+    for (x, y) in a.iter().zip(b) {
+        assert!((x - y).abs() <= epsilon, "{a:?} != {b:?}");
+    }
+}
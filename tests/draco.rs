@@ -0,0 +1,160 @@
+#![cfg(feature = "draco")]
+
+mod common;
+
+use nanogltf::draco::{AttributeType, Mesh};
+use nanogltf::{accessor, draco, validate};
+
+#[test]
+fn sequential() {
+    let (gltf, buffers) = common::fixture("draco.gltf");
+    let primitive = &gltf.meshes[0].primitives[0];
+    let decoded = draco::decode_primitive(&gltf, &buffers, primitive).unwrap();
+    assert_eq!(decoded.indices, [0, 1, 2, 0, 2, 3]);
+    // quantized positions
+    assert_eq!(
+        decoded.attributes["POSITION"],
+        [0., 0., 0., 1., 0., 0., 1., 1., 0., 0., 1., 0.]
+    );
+    // octahedral normals
+    let normals: Vec<f32> = decoded.attributes["NORMAL"]
+        .iter()
+        .map(|&x| x as f32)
+        .collect();
+    common::assert_close(&normals, &[0., 0., 1.].repeat(4), 1e-6);
+    assert_eq!(decoded.attributes["_ID"], [1000., 1001., 1002., 1003.]);
+}
+
+#[test]
+fn decompress() {
+    let (mut gltf, mut buffers) = common::fixture("draco.gltf");
+    assert!(gltf.meshes[0].primitives[1].indices.is_none());
+    assert_eq!(draco::decompress(&mut gltf, &mut buffers), 2);
+
+    for primitive in &gltf.meshes[0].primitives {
+        // the non indexed primitive got an indices accessor for the decoded faces
+        let indices = primitive.indices.unwrap();
+        assert_eq!(
+            accessor::read_u32(&gltf, &buffers, indices),
+            [0, 1, 2, 0, 2, 3]
+        );
+        assert_eq!(
+            accessor::read_u32(&gltf, &buffers, primitive.attributes["_ID"]),
+            [1000, 1001, 1002, 1003]
+        );
+        assert!(primitive.draco_mesh_compression().is_none());
+    }
+    assert!(validate::validate(&gltf, &buffers)
+        .errors()
+        .next()
+        .is_none());
+}
+
+#[test]
+fn malformed_data() {
+    let (gltf, mut buffers) = common::fixture("draco.gltf");
+    let truncated = gltf.buffer_views[0].byte_length / 2;
+    buffers[0].truncate(truncated);
+    let primitive = &gltf.meshes[0].primitives[0];
+    assert!(draco::decode_primitive(&gltf, &buffers, primitive).is_none());
+    assert!(draco::decode(&buffers[0]).is_none());
+}
+
+#[test]
+fn malformed_document() {
+    let (mut gltf, buffers) = common::fixture("draco.gltf");
+    gltf.buffer_views[0].byte_offset = usize::MAX;
+    let primitive = &gltf.meshes[0].primitives[0];
+    assert!(draco::decode_primitive(&gltf, &buffers, primitive).is_none());
+
+    let (mut gltf, buffers) = common::fixture("draco.gltf");
+    let position = gltf.meshes[0].primitives[0].attributes["POSITION"];
+    gltf.accessors[position].type_ = Some("VEC5".to_string());
+    let primitive = &gltf.meshes[0].primitives[0];
+    assert!(draco::decode_primitive(&gltf, &buffers, primitive).is_none());
+
+    // only the primitive with valid indices is decompressed
+    let (mut gltf, mut buffers) = common::fixture("draco.gltf");
+    gltf.meshes[0].primitives[0].indices = Some(99);
+    assert_eq!(draco::decompress(&mut gltf, &mut buffers), 1);
+    assert!(gltf.meshes[0].primitives[0]
+        .draco_mesh_compression()
+        .is_some());
+}
+
+/// Position, uv and normal of every face corner, in face order.
+fn corners(mesh: &Mesh) -> Vec<f32> {
+    let attributes = [
+        AttributeType::Position,
+        AttributeType::TexCoord,
+        AttributeType::Normal,
+    ]
+    .map(|t| {
+        mesh.attributes
+            .iter()
+            .find(|a| a.attribute_type == t)
+            .unwrap()
+    });
+    let mut res = vec![];
+    for &point in mesh.faces.iter().flatten() {
+        for a in attributes {
+            let value = a.point_to_value[point as usize] as usize * a.num_components;
+            res.extend(
+                a.values[value..value + a.num_components]
+                    .iter()
+                    .map(|&x| x as f32),
+            );
+        }
+    }
+    res
+}
+
+/// Position, uv and normal of every face corner of an obj written by draco_decoder.
+fn obj_corners(obj: &str) -> Vec<f32> {
+    let (mut v, mut vt, mut vn) = (vec![], vec![], vec![]);
+    let mut res = vec![];
+    for line in obj.lines() {
+        let mut words = line.split_whitespace();
+        let values = |words: std::str::SplitWhitespace| -> Vec<f32> {
+            words.map(|x| x.parse().unwrap()).collect()
+        };
+        match words.next() {
+            Some("v") => v.push(values(words)),
+            Some("vt") => vt.push(values(words)),
+            Some("vn") => vn.push(values(words)),
+            Some("f") => {
+                for corner in words {
+                    let indices: Vec<usize> =
+                        corner.split('/').map(|i| i.parse().unwrap()).collect();
+                    res.extend(&v[indices[0] - 1]);
+                    res.extend(&vt[indices[1] - 1]);
+                    res.extend(&vn[indices[2] - 1]);
+                }
+            }
+            _ => {}
+        }
+    }
+    res
+}
+
+/// Meshes encoded by the reference draco_encoder, compared to the reference draco_decoder output.
+#[test]
+#[ignore = "needs the files written by tests/fixtures/draco/reference.sh"]
+fn reference() {
+    for name in [
+        "sequential-raw",
+        "sequential",
+        "edgebreaker-cl1",
+        "edgebreaker-cl5",
+        "edgebreaker-cl7",
+        "edgebreaker-cl10",
+    ] {
+        let path = common::fixture_path(&format!("draco/reference/{name}"));
+        let data = std::fs::read(format!("{path}.drc")).unwrap();
+        let obj = std::fs::read_to_string(format!("{path}.obj")).unwrap();
+        let mesh = draco::decode(&data).unwrap_or_else(|| panic!("{name}"));
+        let expected = obj_corners(&obj);
+        assert_eq!(mesh.faces.len() * 24, expected.len(), "{name}");
+        common::assert_close(&corners(&mesh), &expected, 1e-5);
+    }
+}
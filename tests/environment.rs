@@ -0,0 +1,136 @@
+mod common;
+
+use nanogltf::environment;
+
+/// Light 0 only has the constant band, light 1 only the band 1 x term and is rotated
+/// 90° about Y, light 2 has too few coefficients.
+const SCENE: &str = r#"{
+    "asset": {"version": "2.0"},
+    "extensionsUsed": ["EXT_lights_image_based"],
+    "extensions": {
+        "EXT_lights_image_based": {
+            "lights": [
+                {
+                    "intensity": 2,
+                    "irradianceCoefficients": [
+                        [3.5449077, 0, 1.7724539],
+                        [0, 0, 0], [0, 0, 0], [0, 0, 0], [0, 0, 0],
+                        [0, 0, 0], [0, 0, 0], [0, 0, 0], [0, 0, 0]
+                    ],
+                    "specularImageSize": 4,
+                    "specularImages": [
+                        [0, 1, 2, 3, 4, 5],
+                        [6, 7, 8, 9, 10, 11],
+                        [12, 13, 14, 15, 16, 17],
+                        [18, 19, 20, 21, 22, 23]
+                    ]
+                },
+                {
+                    "rotation": [0, 0.7071067811865476, 0, 0.7071067811865476],
+                    "irradianceCoefficients": [
+                        [0, 0, 0], [0, 0, 0], [0, 0, 0], [1, 1, 1], [0, 0, 0],
+                        [0, 0, 0], [0, 0, 0], [0, 0, 0], [0, 0, 0]
+                    ],
+                    "specularImageSize": 256,
+                    "specularImages": []
+                },
+                {
+                    "irradianceCoefficients": [[1, 1, 1]],
+                    "specularImageSize": 256,
+                    "specularImages": []
+                }
+            ]
+        }
+    },
+    "scenes": [
+        {"nodes": [], "extensions": {"EXT_lights_image_based": {"light": 1}}},
+        {"nodes": [], "extensions": {"EXT_lights_image_based": {"light": 3}}},
+        {"nodes": []}
+    ]
+}"#;
+
+#[test]
+fn scene_light() {
+    let (gltf, _) = common::load(SCENE);
+    let lights = environment::image_based_lights(&gltf);
+    assert_eq!(lights.len(), 3);
+    // defaults
+    assert_eq!(lights[1].intensity, 1.0);
+    assert_eq!(lights[0].rotation, [0.0, 0.0, 0.0, 1.0]);
+
+    let light = environment::scene_image_based_light(&gltf, 0).unwrap();
+    assert_eq!(light, &lights[1]);
+    assert!(environment::scene_image_based_light(&gltf, 1).is_none());
+    assert!(environment::scene_image_based_light(&gltf, 2).is_none());
+}
+
+#[test]
+fn specular_mips() {
+    let (gltf, _) = common::load(SCENE);
+    let mips = environment::specular_mips(&environment::image_based_lights(&gltf)[0]);
+    let sizes: Vec<_> = mips.iter().map(|mip| mip.size).collect();
+    // halved per level, never below 1
+    assert_eq!(sizes, [4, 2, 1, 1]);
+    assert_eq!(mips[1].faces, [6, 7, 8, 9, 10, 11]);
+}
+
+#[test]
+fn rotation() {
+    let (gltf, _) = common::load(SCENE);
+    let lights = environment::image_based_lights(&gltf);
+    let identity = environment::rotation(&lights[0]);
+    common::assert_close(
+        &identity,
+        &[
+            1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0,
+        ],
+        1e-6,
+    );
+    // 90° about Y: +X goes to -Z, +Z to +X
+    let r = environment::rotation(&lights[1]);
+    common::assert_close(&r[0..3], &[0.0, 0.0, -1.0], 1e-6);
+    common::assert_close(&r[8..11], &[1.0, 0.0, 0.0], 1e-6);
+    common::assert_close(&r[12..16], &[0.0, 0.0, 0.0, 1.0], 1e-6);
+}
+
+#[test]
+fn irradiance() {
+    let (gltf, _) = common::load(SCENE);
+    let lights = environment::image_based_lights(&gltf);
+
+    // the constant band is 0.282095 in every direction, scaled by the intensity
+    for normal in [[0.0, 1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, -3.0]] {
+        common::assert_close(
+            &environment::irradiance(&lights[0], normal),
+            &[2.0, 0.0, 1.0],
+            1e-5,
+        );
+    }
+
+    // the x term is sampled in the environment's space: world -Z is its +X
+    let x = 0.488603;
+    let light = &lights[1];
+    common::assert_close(
+        &environment::irradiance(light, [0.0, 0.0, -1.0]),
+        &[x; 3],
+        1e-5,
+    );
+    common::assert_close(
+        &environment::irradiance(light, [1.0, 0.0, 0.0]),
+        &[0.0; 3],
+        1e-5,
+    );
+    // negative irradiance is clamped
+    common::assert_close(
+        &environment::irradiance(light, [0.0, 0.0, 1.0]),
+        &[0.0; 3],
+        1e-5,
+    );
+    let diagonal = environment::irradiance(light, [1.0, 0.0, -1.0]);
+    common::assert_close(&diagonal, &[x / 2f32.sqrt(); 3], 1e-5);
+
+    assert_eq!(
+        environment::irradiance(&lights[2], [0.0, 1.0, 0.0]),
+        [0.0; 3]
+    );
+}
@@ -0,0 +1,67 @@
+use nanogltf::extensions::{Extension, Registry};
+use nanogltf::gltf::JsonValue;
+use nanogltf::Gltf;
+
+#[derive(Debug, PartialEq)]
+struct Tag(String);
+
+impl Extension for Tag {
+    const NAME: &'static str = "ACME_tag";
+
+    fn from_json(value: &JsonValue) -> Option<Tag> {
+        Some(Tag(value.get("tag")?.as_str()?.to_string()))
+    }
+}
+
+fn document(node_tag: &str) -> String {
+    let tag = |tag: &str| format!(r#""extensions": {{"ACME_tag": {{"tag": "{tag}"}}}}"#);
+    format!(
+        r#"{{
+            "asset": {{"version": "2.0"}},
+            "buffers": [{{"byteLength": 4, {buffer}}}],
+            "bufferViews": [{{"buffer": 0, "byteLength": 4, {view}}}],
+            "accessors": [{{"bufferView": 0, "componentType": 5126, "count": 1, "type": "SCALAR", {accessor}}}],
+            "samplers": [{{{sampler}}}],
+            "nodes": [{{{node}}}, {{"extensions": {{"ACME_tag": {{"other": 1}}}}}}]
+        }}"#,
+        buffer = tag("buffer"),
+        view = tag("view"),
+        accessor = tag("accessor"),
+        sampler = tag("sampler"),
+        node = tag(node_tag),
+    )
+}
+
+fn parse(json: &str) -> Gltf {
+    let mut registry = Registry::new();
+    registry.register::<Tag>();
+    Gltf::from_json_with_extensions(json, &registry).unwrap()
+}
+
+#[test]
+fn objects_extensions() {
+    let gltf = parse(&document("node"));
+    let tag = |tag: &str| Some(Tag(tag.to_string()));
+    assert_eq!(gltf.buffers[0].extension::<Tag>(), tag("buffer").as_ref());
+    assert_eq!(
+        gltf.buffer_views[0].extension::<Tag>(),
+        tag("view").as_ref()
+    );
+    assert_eq!(
+        gltf.accessors[0].extension::<Tag>(),
+        tag("accessor").as_ref()
+    );
+    assert_eq!(gltf.samplers[0].extension::<Tag>(), tag("sampler").as_ref());
+    assert_eq!(gltf.nodes[0].extension::<Tag>(), tag("node").as_ref());
+    // malformed payloads are left out
+    assert_eq!(gltf.nodes[1].extension::<Tag>(), None);
+}
+
+#[test]
+fn equality_compares_the_json() {
+    let a = parse(&document("a"));
+    let b = parse(&document("b"));
+    assert_eq!(a.nodes[0], parse(&document("a")).nodes[0]);
+    assert_ne!(a.nodes[0], b.nodes[0]);
+    assert_eq!(a.accessors[0], b.accessors[0]);
+}
@@ -0,0 +1,74 @@
+# Generates draco.gltf next to this directory.
+#
+# A hand written draco 2.2 sequential encoder, no reference draco encoder was available:
+# raw u8 connectivity, positions quantized to 8 bits with difference prediction and the
+# wrap transform, normals with the octahedral transform, u16 generic attribute stored raw.
+# The same compressed quad is used by an indexed and a non indexed primitive.
+
+import base64, json, os, struct
+
+OUT = os.path.join(os.path.dirname(os.path.abspath(__file__)), '..')
+
+def varint(v):
+    out = b''
+    while True:
+        b = v & 0x7f; v >>= 7
+        if v: out += bytes([b | 0x80])
+        else: return out + bytes([b])
+
+def zz(v): return (v << 1) if v >= 0 else ((-v) << 1) - 1
+
+positions = [(0, 0, 0), (1, 0, 0), (1, 1, 0), (0, 1, 0)]
+faces = [(0, 1, 2), (0, 2, 3)]
+
+# header: version 2.2, triangular mesh, sequential, no flags
+out = b'DRACO' + bytes([2, 2, 1, 0]) + struct.pack('<H', 0)
+# connectivity: faces, points, raw u8 indices
+out += varint(len(faces)) + varint(len(positions)) + bytes([1])
+for f in faces: out += bytes(f)
+
+# one attributes decoder with three attributes:
+# type, data type, components, normalized, unique id
+out += bytes([1]) + varint(3)
+out += bytes([0, 9, 3, 0]) + varint(7)  # POSITION float32 x3
+out += bytes([1, 9, 3, 0]) + varint(3)  # NORMAL float32 x3
+out += bytes([4, 4, 1, 0]) + varint(5)  # GENERIC u16
+# decoder types: quantization, normals, integer
+out += bytes([2, 3, 0])
+
+# positions: 8 bits over range 1, difference prediction, wrap transform
+q = [round(c * 255) for p in positions for c in p]
+out += struct.pack('<bb', 0, 1) + bytes([0, 4])
+prev = [0, 0, 0]
+for i in range(len(positions)):
+    v = q[i * 3:i * 3 + 3]
+    out += b''.join(struct.pack('<I', zz(v[c] - prev[c])) for c in range(3)); prev = v
+out += struct.pack('<ii', 0, 255)
+
+# normals: all +z, octahedral (127, 254) with 8 bits, no prediction
+out += struct.pack('<b', -2) + bytes([0, 4])
+for _ in positions: out += struct.pack('<II', zz(127), zz(254))
+
+# generic values, raw
+out += b''.join(struct.pack('<H', 1000 + i) for i in range(len(positions)))
+
+# transform data: quantization origin, range, bits, then the normal bits
+out += struct.pack('<fff', 0, 0, 0) + struct.pack('<f', 1.0) + bytes([8])
+out += bytes([8])
+
+data = out + bytes((4 - len(out) % 4) % 4)
+draco = {"bufferView": 0, "attributes": {"POSITION": 7, "NORMAL": 3, "_ID": 5}}
+g = {"asset": {"version": "2.0"},
+     "extensionsUsed": ["KHR_draco_mesh_compression"],
+     "extensionsRequired": ["KHR_draco_mesh_compression"],
+     "buffers": [{"uri": "data:application/octet-stream;base64," + base64.b64encode(data).decode(), "byteLength": len(data)}],
+     "bufferViews": [{"buffer": 0, "byteLength": len(out)}],
+     "accessors": [{"componentType": 5123, "count": 6, "type": "SCALAR"},
+                   {"componentType": 5126, "count": 4, "type": "VEC3", "min": [0, 0, 0], "max": [1, 1, 0]},
+                   {"componentType": 5126, "count": 4, "type": "VEC3"},
+                   {"componentType": 5123, "count": 4, "type": "SCALAR"}],
+     "meshes": [{"primitives": [{"indices": 0, "attributes": {"POSITION": 1, "NORMAL": 2, "_ID": 3}, "extensions": {"KHR_draco_mesh_compression": draco}},
+                                {"attributes": {"POSITION": 1, "NORMAL": 2, "_ID": 3}, "extensions": {"KHR_draco_mesh_compression": draco}}]}],
+     "nodes": [{"mesh": 0}], "scenes": [{"nodes": [0]}], "scene": 0}
+with open(os.path.join(OUT, 'draco.gltf'), 'w') as f:
+    json.dump(g, f, indent=1); f.write('\n')
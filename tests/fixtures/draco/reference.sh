@@ -0,0 +1,27 @@
+#!/bin/sh
+# Encodes sphere.obj with the reference draco_encoder and decodes the result back with the
+# reference draco_decoder, for the `reference` test of tests/draco.rs:
+#
+#   sh reference.sh <directory with draco_encoder and draco_decoder>
+#
+# -cl 0 selects the sequential connectivity, 1 to 6 the standard edgebreaker and 7 to 10
+# the valence edgebreaker. Positions, normals and uvs are quantized unless the -q* are 0.
+set -e
+
+BIN=$1
+DIR=$(cd "$(dirname "$0")" && pwd)
+mkdir -p "$DIR/reference"
+
+encode() {
+    name=$1
+    shift
+    "$BIN/draco_encoder" -i "$DIR/sphere.obj" -o "$DIR/reference/$name.drc" "$@"
+    "$BIN/draco_decoder" -i "$DIR/reference/$name.drc" -o "$DIR/reference/$name.obj"
+}
+
+encode sequential-raw -cl 0 -qp 0 -qn 0 -qt 0
+encode sequential -cl 0 -qp 11 -qn 8 -qt 10
+encode edgebreaker-cl1 -cl 1 -qp 11 -qn 8 -qt 10
+encode edgebreaker-cl5 -cl 5 -qp 11 -qn 8 -qt 10
+encode edgebreaker-cl7 -cl 7 -qp 14 -qn 10 -qt 12
+encode edgebreaker-cl10 -cl 10 -qp 11 -qn 8 -qt 10
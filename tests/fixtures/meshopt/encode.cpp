@@ -0,0 +1,45 @@
+// Reference encoder CLI for the meshopt fixtures, built against the meshoptimizer sources:
+//   g++ -O2 encode.cpp <meshoptimizer>/src/vertexcodec.cpp <meshoptimizer>/src/indexcodec.cpp
+//   encode vertex <vertex size> < raw > encoded
+//   encode index < u32 indices > encoded
+#include "meshoptimizer.h"
+
+#include <cstdio>
+#include <cstdlib>
+#include <cstring>
+#include <vector>
+
+int main(int argc, char** argv)
+{
+	std::vector<unsigned char> input;
+	unsigned char chunk[4096];
+	size_t read;
+	while ((read = fread(chunk, 1, sizeof(chunk), stdin)) > 0)
+		input.insert(input.end(), chunk, chunk + read);
+
+	std::vector<unsigned char> output;
+	if (argc == 3 && strcmp(argv[1], "vertex") == 0)
+	{
+		size_t size = atoi(argv[2]);
+		size_t count = input.size() / size;
+		output.resize(meshopt_encodeVertexBufferBound(count, size));
+		output.resize(meshopt_encodeVertexBuffer(&output[0], output.size(), &input[0], count, size));
+	}
+	else if (argc == 2 && strcmp(argv[1], "index") == 0)
+	{
+		const unsigned int* indices = reinterpret_cast<const unsigned int*>(&input[0]);
+		size_t count = input.size() / 4;
+		unsigned int vertices = 0;
+		for (size_t i = 0; i < count; ++i)
+			vertices = indices[i] + 1 > vertices ? indices[i] + 1 : vertices;
+		output.resize(meshopt_encodeIndexBufferBound(count, vertices));
+		output.resize(meshopt_encodeIndexBuffer(&output[0], output.size(), indices, count));
+	}
+	else
+	{
+		fprintf(stderr, "usage: encode vertex <size> | encode index\n");
+		return 1;
+	}
+	fwrite(&output[0], 1, output.size(), stdout);
+	return 0;
+}
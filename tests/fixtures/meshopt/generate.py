@@ -0,0 +1,134 @@
+# Generates meshopt.gltf and meshopt_expected.json next to this directory.
+#
+#   python3 generate.py <encode binary built from encode.cpp>
+#
+# ATTRIBUTES streams and the version 0 TRIANGLES stream are encoded by the meshoptimizer
+# reference encoder (vertexcodec.cpp/indexcodec.cpp, v0.12 as vendored by the meshopt 0.1.9
+# crate). That release predates the filters, the index sequence codec and index codec
+# version 1, those are encoded here following the EXT_meshopt_compression spec.
+# The expected values are the source data, not the output of a decoder.
+
+import base64, json, math, os, struct, subprocess, sys
+
+ENCODE = sys.argv[1]
+OUT = os.path.join(os.path.dirname(os.path.abspath(__file__)), '..')
+
+def encode_vertex(data, size):
+    return subprocess.run([ENCODE, 'vertex', str(size)], input=data, capture_output=True, check=True).stdout
+
+def encode_index(idx):
+    return subprocess.run([ENCODE, 'index'], input=struct.pack(f'<{len(idx)}I', *idx), capture_output=True, check=True).stdout
+
+def vbyte(v):
+    out = bytearray()
+    while v >= 128:
+        out.append((v & 127) | 128); v >>= 7
+    out.append(v)
+    return bytes(out)
+
+def zz32(d): return ((d << 1) ^ (d >> 31)) & 0xffffffff
+
+def encode_triangles_v1(idx):
+    # every triangle as 0xff: three explicit vbyte encoded deltas
+    codes = bytearray(); data = bytearray(); last = 0
+    for t in range(0, len(idx), 3):
+        codes.append(0xff); data.append(0xff)
+        for v in idx[t:t+3]:
+            data += vbyte(zz32(v - last)); last = v
+    return bytes([0xe1]) + bytes(codes) + bytes(data) + bytes(16)
+
+def encode_sequence(idx):
+    # alternate between the two baselines
+    data = bytearray([0xd1]); last = [0, 0]
+    for i, v in enumerate(idx):
+        b = i & 1
+        data += vbyte((zz32(v - last[b]) << 1) | b); last[b] = v
+    return bytes(data) + bytes(4)
+
+def encode_oct(n, bits):
+    m = (1 << (bits - 1)) - 1
+    l = sum(abs(c) for c in n); u, v = n[0] / l, n[1] / l
+    if n[2] < 0:
+        u, v = (1 - abs(v)) * math.copysign(1, u), (1 - abs(u)) * math.copysign(1, v)
+    return [round(u * m), round(v * m), m]
+
+def encode_quat(q):
+    qc = max(range(4), key=lambda i: abs(q[i])); s = -1 if q[qc] < 0 else 1
+    d = [round(q[(qc + k) & 3] * s * math.sqrt(2) * 32767) for k in (1, 2, 3)]
+    return d + [(32767 & ~3) | qc], [c * s for c in q]
+
+def f32(x): return struct.unpack('<f', struct.pack('<f', x))[0]
+
+# 20x20 grid, two vertex codec blocks for 12 byte vertices
+N = 20
+pos = b''; nrm = b''; tan = b''; rot = b''; exps = b''
+expected = {"positions": [], "normals": [], "tangents": [], "rotations": [], "scalars": []}
+for y in range(N):
+    for x in range(N):
+        p = [x * 0.5, y * 0.25, math.sin(x) * 0.1]
+        pos += struct.pack('<3f', *p); expected["positions"] += [f32(c) for c in p]
+
+        n = [math.sin(x * 0.3), math.cos(y * 0.2), -0.5 + (x % 3) * 0.4]
+        l = math.sqrt(sum(c * c for c in n)); n = [c / l for c in n]
+        nrm += struct.pack('<4b', *encode_oct(n, 8), 0); expected["normals"] += [round(c, 6) for c in n]
+
+        t = [math.cos(x * 0.2), -0.3, math.sin(y * 0.5) - 0.2]
+        l = math.sqrt(sum(c * c for c in t)); t = [c / l for c in t]
+        w = 1 if (x + y) % 2 else -1
+        tan += struct.pack('<4h', *encode_oct(t, 16), w * 32767); expected["tangents"] += [round(c, 6) for c in t] + [w]
+
+        a = (x + y) * 0.1
+        d, q = encode_quat([math.sin(a) * 0.6, -math.sin(a) * 0.8, 0.0, math.cos(a)])
+        rot += struct.pack('<4h', *d); expected["rotations"] += [round(c, 6) for c in q]
+
+        m = round((x * 1.5 - y) * 1024)
+        exps += struct.pack('<I', ((-10 & 0xff) << 24) | (m & 0xffffff)); expected["scalars"].append(m / 1024)
+idx = []
+for y in range(N - 1):
+    for x in range(N - 1):
+        i = y * N + x
+        idx += [i, i + 1, i + N, i + 1, i + N + 1, i + N]
+expected["indices"] = idx
+
+# (compressed, stride, count, mode, filter)
+streams = [(encode_vertex(pos, 12), 12, N * N, "ATTRIBUTES", "NONE"),
+           (encode_vertex(nrm, 4), 4, N * N, "ATTRIBUTES", "OCTAHEDRAL"),
+           (encode_vertex(tan, 8), 8, N * N, "ATTRIBUTES", "OCTAHEDRAL"),
+           (encode_vertex(rot, 8), 8, N * N, "ATTRIBUTES", "QUATERNION"),
+           (encode_vertex(exps, 4), 4, N * N, "ATTRIBUTES", "EXPONENTIAL"),
+           (encode_index(idx), 2, len(idx), "TRIANGLES", None),
+           (encode_triangles_v1(idx), 4, len(idx), "TRIANGLES", None),
+           (encode_sequence(idx), 4, len(idx), "INDICES", None)]
+buf = b''; views = []; fallback_len = 0
+for data, stride, count, mode, filt in streams:
+    ext = {"buffer": 1, "byteOffset": len(buf), "byteLength": len(data), "byteStride": stride, "count": count, "mode": mode}
+    if filt: ext["filter"] = filt
+    v = {"buffer": 0, "byteOffset": fallback_len, "byteLength": stride * count, "extensions": {"EXT_meshopt_compression": ext}}
+    if mode == "ATTRIBUTES": v["byteStride"] = stride
+    views.append(v); fallback_len += (stride * count + 3) // 4 * 4
+    buf += data
+    while len(buf) % 4: buf += b'\0'
+
+accessors = [{"bufferView": 0, "componentType": 5126, "count": N * N, "type": "VEC3", "min": [min(expected["positions"][c::3]) for c in range(3)], "max": [max(expected["positions"][c::3]) for c in range(3)]},
+             {"bufferView": 1, "componentType": 5120, "normalized": True, "count": N * N, "type": "VEC3"},
+             {"bufferView": 2, "componentType": 5122, "normalized": True, "count": N * N, "type": "VEC4"},
+             {"bufferView": 3, "componentType": 5122, "normalized": True, "count": N * N, "type": "VEC4"},
+             {"bufferView": 4, "componentType": 5126, "count": N * N, "type": "SCALAR"},
+             {"bufferView": 5, "componentType": 5123, "count": len(idx), "type": "SCALAR"},
+             {"bufferView": 6, "componentType": 5125, "count": len(idx), "type": "SCALAR"},
+             {"bufferView": 7, "componentType": 5125, "count": len(idx), "type": "SCALAR"}]
+g = {"asset": {"version": "2.0"},
+     "extensionsUsed": ["EXT_meshopt_compression", "KHR_mesh_quantization"],
+     "extensionsRequired": ["EXT_meshopt_compression", "KHR_mesh_quantization"],
+     "buffers": [{"byteLength": fallback_len, "extensions": {"EXT_meshopt_compression": {"fallback": True}}},
+                 {"uri": "data:application/octet-stream;base64," + base64.b64encode(buf).decode(), "byteLength": len(buf)}],
+     "bufferViews": views,
+     "accessors": accessors,
+     "meshes": [{"primitives": [{"attributes": {"POSITION": 0, "NORMAL": 1, "TANGENT": 2}, "indices": 5},
+                                {"attributes": {"POSITION": 0}, "indices": 6},
+                                {"attributes": {"POSITION": 0}, "indices": 7}]}],
+     "nodes": [{"mesh": 0}], "scenes": [{"nodes": [0]}], "scene": 0}
+with open(os.path.join(OUT, 'meshopt.gltf'), 'w') as f:
+    json.dump(g, f, indent=1); f.write('\n')
+with open(os.path.join(OUT, 'meshopt_expected.json'), 'w') as f:
+    json.dump(expected, f); f.write('\n')
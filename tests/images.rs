@@ -0,0 +1,135 @@
+mod common;
+
+use nanogltf::images::{self, ImageFormat, TextureImage};
+
+const SCENE: &str = r#"{
+    "asset": {"version": "2.0"},
+    "extensionsUsed": ["KHR_texture_basisu", "EXT_texture_webp", "EXT_texture_avif"],
+    "images": [
+        {"uri": "albedo.ktx2"},
+        {"uri": "albedo.webp"},
+        {"uri": "albedo.avif"},
+        {"uri": "albedo", "mimeType": "image/png"},
+        {"uri": "albedo.JPG?version=2#top"},
+        {"uri": "data:image/png;base64,"},
+        {"uri": "albedo"}
+    ],
+    "textures": [
+        {
+            "source": 3,
+            "extensions": {
+                "EXT_texture_avif": {"source": 2},
+                "EXT_texture_webp": {"source": 1},
+                "KHR_texture_basisu": {"source": 0}
+            }
+        },
+        {"source": 4, "extensions": {"EXT_texture_webp": {"source": 1}}},
+        {"source": 5},
+        {"source": 6},
+        {"extensions": {"EXT_texture_avif": {"source": 2}}}
+    ]
+}"#;
+
+fn image(image: usize, format: ImageFormat) -> Option<TextureImage> {
+    Some(TextureImage {
+        image,
+        format: Some(format),
+    })
+}
+
+#[test]
+fn image_format() {
+    let (gltf, _) = common::load(SCENE);
+    let formats: Vec<_> = gltf.images.iter().map(images::image_format).collect();
+    assert_eq!(
+        formats,
+        [
+            Some(ImageFormat::Ktx2),
+            Some(ImageFormat::WebP),
+            Some(ImageFormat::Avif),
+            // mimeType wins over the uri
+            Some(ImageFormat::Png),
+            // case insensitive, query and fragment ignored
+            Some(ImageFormat::Jpeg),
+            // data URI media type
+            Some(ImageFormat::Png),
+            None,
+        ]
+    );
+    assert_eq!(ImageFormat::Ktx2.mime_type(), "image/ktx2");
+    assert_eq!(
+        ImageFormat::from_mime_type("image/avif"),
+        Some(ImageFormat::Avif)
+    );
+    assert_eq!(ImageFormat::from_mime_type("image/gif"), None);
+}
+
+#[test]
+fn texture_images() {
+    let (gltf, _) = common::load(SCENE);
+    // extension images first, in basisu, webp, avif order, then the source
+    let formats: Vec<_> = images::texture_images(&gltf, &gltf.textures[0])
+        .iter()
+        .map(|image| (image.image, image.format))
+        .collect();
+    assert_eq!(
+        formats,
+        [
+            (0, Some(ImageFormat::Ktx2)),
+            (1, Some(ImageFormat::WebP)),
+            (2, Some(ImageFormat::Avif)),
+            (3, Some(ImageFormat::Png)),
+        ]
+    );
+    assert_eq!(
+        images::texture_images(&gltf, &gltf.textures[3]),
+        [TextureImage {
+            image: 6,
+            format: None
+        }]
+    );
+    assert_eq!(images::texture_images(&gltf, &gltf.textures[4]).len(), 1);
+}
+
+#[test]
+fn fallback_order() {
+    use ImageFormat::*;
+
+    let (gltf, _) = common::load(SCENE);
+    // the caller's preference decides, not the order of the extensions
+    assert_eq!(
+        images::texture_image(&gltf, 0, &[Avif, WebP, Ktx2, Png]),
+        image(2, Avif)
+    );
+    assert_eq!(
+        images::texture_image(&gltf, 0, &[WebP, Ktx2]),
+        image(1, WebP)
+    );
+    assert_eq!(
+        images::texture_image(&gltf, 0, &[Ktx2, WebP]),
+        image(0, Ktx2)
+    );
+    assert_eq!(images::texture_image(&gltf, 0, &[Png]), image(3, Png));
+
+    // formats the texture lacks fall through to the source
+    assert_eq!(
+        images::texture_image(&gltf, 1, &[Ktx2, Avif, Jpeg]),
+        image(4, Jpeg)
+    );
+    assert_eq!(images::texture_image(&gltf, 1, &[Ktx2, Avif]), None);
+    assert_eq!(images::texture_image(&gltf, 2, &[WebP, Png]), image(5, Png));
+
+    // a source of unknown format is the last resort for PNG and JPEG
+    assert_eq!(
+        images::texture_image(&gltf, 3, &[Jpeg]),
+        Some(TextureImage {
+            image: 6,
+            format: None
+        })
+    );
+    assert_eq!(images::texture_image(&gltf, 3, &[WebP]), None);
+
+    // no source to fall back to
+    assert_eq!(images::texture_image(&gltf, 4, &[Png, Jpeg]), None);
+    assert_eq!(images::texture_image(&gltf, 4, &[Avif]), image(2, Avif));
+}
@@ -0,0 +1,97 @@
+mod common;
+
+use nanogltf::{instancing, validate};
+
+/// Two instances: translations (1, 0, 0) and (0, 2, 0), rotations 90° about Z and identity,
+/// stored as floats and as normalized shorts, scales 2 and 1.
+const SCENE: &str = r#"{
+    "asset": {"version": "2.0"},
+    "extensionsUsed": ["EXT_mesh_gpu_instancing"],
+    "buffers": [{
+        "byteLength": 96,
+        "uri": "data:application/octet-stream;base64,AACAPwAAAAAAAAAAAAAAAAAAAEAAAAAAAAAAAAAAAADzBDU/8wQ1PwAAAAAAAAAAAAAAAAAAgD8AAABAAAAAQAAAAEAAAIA/AACAPwAAgD8AAAAAglqCWgAAAAAAAP9/"
+    }],
+    "bufferViews": [
+        {"buffer": 0, "byteOffset": 0, "byteLength": 24},
+        {"buffer": 0, "byteOffset": 24, "byteLength": 32},
+        {"buffer": 0, "byteOffset": 56, "byteLength": 24},
+        {"buffer": 0, "byteOffset": 80, "byteLength": 16}
+    ],
+    "accessors": [
+        {"bufferView": 0, "componentType": 5126, "count": 2, "type": "VEC3"},
+        {"bufferView": 1, "componentType": 5126, "count": 2, "type": "VEC4"},
+        {"bufferView": 2, "componentType": 5126, "count": 2, "type": "VEC3"},
+        {"bufferView": 3, "componentType": 5122, "normalized": true, "count": 2, "type": "VEC4"},
+        {"bufferView": 2, "componentType": 5126, "count": 1, "type": "VEC3"},
+        {"bufferView": 0, "componentType": 5126, "count": 2, "type": "VEC3"}
+    ],
+    "nodes": [
+        {"translation": [0, 0, 5], "extensions": {"EXT_mesh_gpu_instancing": {"attributes": {"TRANSLATION": 0, "ROTATION": 1, "SCALE": 2}}}},
+        {"extensions": {"EXT_mesh_gpu_instancing": {"attributes": {"TRANSLATION": 0, "ROTATION": 3}}}},
+        {"extensions": {"EXT_mesh_gpu_instancing": {"attributes": {"TRANSLATION": 0, "SCALE": 4}}}},
+        {"extensions": {"EXT_mesh_gpu_instancing": {"attributes": {"ROTATION": 5}}}},
+        {}
+    ],
+    "scenes": [{"nodes": [0, 1, 2, 3, 4]}]
+}"#;
+
+/// Column major, as the gltf matrices.
+fn transform_point(m: &[f32; 16], p: [f32; 3]) -> [f32; 3] {
+    [0, 1, 2].map(|r| m[r] * p[0] + m[4 + r] * p[1] + m[8 + r] * p[2] + m[12 + r])
+}
+
+#[test]
+fn trs_composition() {
+    let (gltf, buffers) = common::load(SCENE);
+    assert_eq!(instancing::instance_count(&gltf, 0), Some(2));
+    let instances = instancing::instance_transforms(&gltf, &buffers, 0).unwrap();
+    // scaled, then rotated, then translated
+    let p = transform_point(&instances[0], [1.0, 1.0, 0.0]);
+    common::assert_close(&p, &[-1.0, 2.0, 0.0], 1e-5);
+    let p = transform_point(&instances[1], [1.0, 1.0, 0.0]);
+    common::assert_close(&p, &[1.0, 3.0, 0.0], 1e-5);
+
+    // the node transform comes after the instance's
+    let world = instancing::instance_world_transforms(&gltf, &buffers, 0).unwrap();
+    let p = transform_point(&world[0], [1.0, 1.0, 0.0]);
+    common::assert_close(&p, &[-1.0, 2.0, 5.0], 1e-5);
+
+    // normalized shorts rotation, missing SCALE is identity
+    let instances = instancing::instance_transforms(&gltf, &buffers, 1).unwrap();
+    let p = transform_point(&instances[0], [1.0, 1.0, 0.0]);
+    common::assert_close(&p, &[0.0, 1.0, 0.0], 1e-4);
+
+    assert_eq!(instancing::instance_count(&gltf, 4), None);
+    assert!(instancing::instance_transforms(&gltf, &buffers, 4).is_none());
+    let scene = instancing::scene_instances(&gltf, &buffers, 0);
+    assert!(scene.is_empty(), "no node has a mesh");
+}
+
+#[test]
+fn mismatched_counts() {
+    let (gltf, buffers) = common::load(SCENE);
+    assert_eq!(instancing::instance_count(&gltf, 2), None);
+    assert!(instancing::instance_transforms(&gltf, &buffers, 2).is_none());
+    // ROTATION is VEC3
+    assert_eq!(instancing::instance_count(&gltf, 3), Some(2));
+    assert!(instancing::instance_transforms(&gltf, &buffers, 3).is_none());
+
+    let report = validate::validate(&gltf, &buffers);
+    let errors: Vec<_> = report
+        .errors()
+        .map(|issue| (issue.code, issue.pointer.as_str()))
+        .collect();
+    assert_eq!(
+        errors,
+        [
+            (
+                "NODE_GPU_INSTANCING_UNEQUAL_ACCESSOR_COUNT",
+                "/nodes/2/extensions/EXT_mesh_gpu_instancing/attributes/TRANSLATION"
+            ),
+            (
+                "NODE_GPU_INSTANCING_ACCESSOR_INVALID_FORMAT",
+                "/nodes/3/extensions/EXT_mesh_gpu_instancing/attributes/ROTATION"
+            ),
+        ]
+    );
+}
@@ -0,0 +1,88 @@
+mod common;
+
+use nanogltf::{lights, Light};
+
+const SCENE: &str = r#"{
+    "asset": {"version": "2.0"},
+    "extensionsUsed": ["KHR_lights_punctual"],
+    "extensions": {
+        "KHR_lights_punctual": {
+            "lights": [
+                {"type": "directional", "color": [1, 0.5, 0], "intensity": 3},
+                {"type": "point", "range": 10},
+                {"type": "spot", "spot": {"innerConeAngle": 0.25, "outerConeAngle": 0.5}},
+                {"type": "spot", "name": "default cone"}
+            ]
+        }
+    },
+    "nodes": [
+        {"rotation": [0, 0.7071067811865476, 0, 0.7071067811865476], "translation": [1, 0, 0], "children": [1]},
+        {"translation": [0, 0, 2], "extensions": {"KHR_lights_punctual": {"light": 2}}},
+        {"extensions": {"KHR_lights_punctual": {"light": 7}}},
+        {"extensions": {"KHR_lights_punctual": {"light": 0}}}
+    ],
+    "scenes": [{"nodes": [0, 2, 3]}]
+}"#;
+
+#[test]
+fn parse() {
+    let (gltf, _) = common::load(SCENE);
+    let lights = lights::lights(&gltf);
+    assert_eq!(lights.len(), 4);
+    assert!(matches!(lights[0], Light::Directional { .. }));
+    assert_eq!(lights[0].color(), [1.0, 0.5, 0.0]);
+    assert_eq!(lights[0].intensity(), 3.0);
+    assert_eq!(lights[0].range(), None);
+    // white, 1 candela and infinite range unless told otherwise
+    assert_eq!(lights[1].color(), [1.0; 3]);
+    assert_eq!(lights[1].intensity(), 1.0);
+    assert_eq!(lights[1].range(), Some(10.0));
+    assert_eq!(
+        lights[2],
+        Light::Spot {
+            name: None,
+            color: [1.0; 3],
+            intensity: 1.0,
+            range: None,
+            inner_cone_angle: 0.25,
+            outer_cone_angle: 0.5,
+        }
+    );
+    let Light::Spot {
+        inner_cone_angle,
+        outer_cone_angle,
+        ..
+    } = lights[3]
+    else {
+        panic!("{:?}", lights[3]);
+    };
+    assert_eq!(lights[3].name(), Some("default cone"));
+    assert_eq!(
+        (inner_cone_angle, outer_cone_angle),
+        (0.0, std::f64::consts::FRAC_PI_4)
+    );
+
+    let (gltf, _) = common::load(r#"{"asset": {"version": "2.0"}}"#);
+    assert!(lights::lights(&gltf).is_empty());
+    let unknown = SCENE.replace(r#""type": "point""#, r#""type": "area""#);
+    assert!(nanogltf::Gltf::from_json(&unknown).is_err());
+}
+
+#[test]
+fn world_position_and_direction() {
+    let (gltf, _) = common::load(SCENE);
+    let lights = lights::scene_lights(&gltf, 0);
+    // node 2 points at a missing light and is skipped
+    let nodes: Vec<_> = lights.iter().map(|l| (l.node, l.index)).collect();
+    assert_eq!(nodes, [(1, 2), (3, 0)]);
+
+    // the parent turns -Z into -X and moves the spot light along its own +Z
+    let spot = &lights[0];
+    assert!(matches!(spot.light, Light::Spot { .. }));
+    common::assert_close(&spot.position, &[3.0, 0.0, 0.0], 1e-6);
+    common::assert_close(&spot.direction, &[-1.0, 0.0, 0.0], 1e-6);
+
+    let sun = &lights[1];
+    assert_eq!(sun.position, [0.0; 3]);
+    assert_eq!(sun.direction, [0.0, 0.0, -1.0]);
+}
@@ -0,0 +1,18 @@
+use nanogltf::lod;
+
+#[test]
+fn select() {
+    let levels = [0, 5, 6];
+    let coverages = [0.5, 0.2, 0.05];
+    assert_eq!(lod::select(&levels, &coverages, 1.0), Some(0));
+    assert_eq!(lod::select(&levels, &coverages, 0.5), Some(0));
+    assert_eq!(lod::select(&levels, &coverages, 0.3), Some(5));
+    assert_eq!(lod::select(&levels, &coverages, 0.1), Some(6));
+    assert_eq!(lod::select(&levels, &coverages, 0.05), Some(6));
+    // below the last threshold the object is culled
+    assert_eq!(lod::select(&levels, &coverages, 0.01), None);
+
+    // fewer thresholds than levels, the last level is never culled
+    assert_eq!(lod::select(&levels, &[0.5, 0.2], 0.01), Some(6));
+    assert_eq!(lod::select(&levels, &[], 0.01), Some(0));
+}
@@ -0,0 +1,168 @@
+use nanogltf::Material;
+use nanoserde::DeJson;
+
+fn material(json: &str) -> Material {
+    Material::deserialize_json(json).unwrap()
+}
+
+#[test]
+fn core_material() {
+    let m = material(r#"{"pbrMetallicRoughness": {}}"#);
+    assert!(m.extensions.is_none());
+    assert!(!m.is_unlit());
+    assert_eq!(m.emissive_strength(), 1.0);
+    assert_eq!(m.ior(), 1.5);
+    assert!(m.textures().is_empty());
+}
+
+#[test]
+fn extension_values() {
+    let m = material(
+        r#"{
+            "extensions": {
+                "KHR_materials_emissive_strength": {"emissiveStrength": 5},
+                "KHR_materials_ior": {"ior": 1.0},
+                "KHR_materials_unlit": {},
+                "KHR_materials_clearcoat": {"clearcoatFactor": 1, "clearcoatRoughnessFactor": 0.25},
+                "KHR_materials_sheen": {"sheenColorFactor": [1, 0.5, 0]},
+                "KHR_materials_transmission": {"transmissionFactor": 0.75},
+                "KHR_materials_volume": {"thicknessFactor": 2, "attenuationDistance": 0.5},
+                "KHR_materials_specular": {"specularFactor": 0.5},
+                "KHR_materials_iridescence": {"iridescenceFactor": 1},
+                "KHR_materials_anisotropy": {"anisotropyStrength": 0.5, "anisotropyRotation": 1.5}
+            }
+        }"#,
+    );
+    assert!(m.is_unlit());
+    assert_eq!(m.emissive_strength(), 5.0);
+    assert_eq!(m.ior(), 1.0);
+
+    let e = m.extensions.as_ref().unwrap();
+    let clearcoat = e.clearcoat.as_ref().unwrap();
+    assert_eq!(
+        (
+            clearcoat.clearcoat_factor,
+            clearcoat.clearcoat_roughness_factor
+        ),
+        (1.0, 0.25)
+    );
+    let sheen = e.sheen.as_ref().unwrap();
+    assert_eq!(sheen.sheen_color_factor, [1.0, 0.5, 0.0]);
+    assert_eq!(sheen.sheen_roughness_factor, 0.0);
+    assert_eq!(e.transmission.as_ref().unwrap().transmission_factor, 0.75);
+    let volume = e.volume.as_ref().unwrap();
+    assert_eq!(volume.thickness_factor, 2.0);
+    assert_eq!(volume.attenuation_distance, 0.5);
+    assert_eq!(volume.attenuation_color, [1.0; 3]);
+    let specular = e.specular.as_ref().unwrap();
+    assert_eq!(specular.specular_factor, 0.5);
+    assert_eq!(specular.specular_color_factor, [1.0; 3]);
+    let iridescence = e.iridescence.as_ref().unwrap();
+    assert_eq!(
+        [
+            iridescence.iridescence_factor,
+            iridescence.iridescence_ior,
+            iridescence.iridescence_thickness_minimum,
+            iridescence.iridescence_thickness_maximum
+        ],
+        [1.0, 1.3, 100.0, 400.0]
+    );
+    let anisotropy = e.anisotropy.as_ref().unwrap();
+    assert_eq!(
+        (
+            anisotropy.anisotropy_strength,
+            anisotropy.anisotropy_rotation
+        ),
+        (0.5, 1.5)
+    );
+}
+
+#[test]
+fn spec_defaults() {
+    let m = material(
+        r#"{
+            "extensions": {
+                "KHR_materials_emissive_strength": {},
+                "KHR_materials_ior": {},
+                "KHR_materials_volume": {},
+                "KHR_materials_specular": {}
+            }
+        }"#,
+    );
+    assert_eq!(m.emissive_strength(), 1.0);
+    assert_eq!(m.ior(), 1.5);
+    let e = m.extensions.as_ref().unwrap();
+    let volume = e.volume.as_ref().unwrap();
+    // no attenuation at all
+    assert_eq!(volume.attenuation_distance, f64::INFINITY);
+    assert_eq!(e.specular.as_ref().unwrap().specular_factor, 1.0);
+}
+
+#[test]
+fn extension_textures() {
+    let m = material(
+        r#"{
+            "normalTexture": {"index": 0},
+            "extensions": {
+                "KHR_materials_clearcoat": {
+                    "clearcoatTexture": {"index": 1},
+                    "clearcoatNormalTexture": {"index": 2, "texCoord": 1, "scale": 0.5}
+                },
+                "KHR_materials_sheen": {"sheenRoughnessTexture": {"index": 3}},
+                "KHR_materials_transmission": {"transmissionTexture": {"index": 4}},
+                "KHR_materials_volume": {"thicknessTexture": {"index": 5}},
+                "KHR_materials_specular": {"specularColorTexture": {"index": 6}},
+                "KHR_materials_iridescence": {"iridescenceThicknessTexture": {"index": 7}},
+                "KHR_materials_anisotropy": {
+                    "anisotropyTexture": {
+                        "index": 8,
+                        "extensions": {"KHR_texture_transform": {"texCoord": 2}}
+                    }
+                }
+            }
+        }"#,
+    );
+    let textures: Vec<_> = m
+        .textures()
+        .iter()
+        .map(|t| (t.path, t.index, t.effective_tex_coord()))
+        .collect();
+    assert_eq!(
+        textures,
+        [
+            ("/normalTexture", 0, 0),
+            ("/extensions/KHR_materials_clearcoat/clearcoatTexture", 1, 0),
+            (
+                "/extensions/KHR_materials_sheen/sheenRoughnessTexture",
+                3,
+                0
+            ),
+            (
+                "/extensions/KHR_materials_transmission/transmissionTexture",
+                4,
+                0
+            ),
+            ("/extensions/KHR_materials_volume/thicknessTexture", 5, 0),
+            (
+                "/extensions/KHR_materials_specular/specularColorTexture",
+                6,
+                0
+            ),
+            (
+                "/extensions/KHR_materials_iridescence/iridescenceThicknessTexture",
+                7,
+                0
+            ),
+            (
+                "/extensions/KHR_materials_anisotropy/anisotropyTexture",
+                8,
+                2
+            ),
+            (
+                "/extensions/KHR_materials_clearcoat/clearcoatNormalTexture",
+                2,
+                1
+            ),
+        ]
+    );
+}
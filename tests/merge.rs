@@ -0,0 +1,97 @@
+mod common;
+
+use nanogltf::{accessor, merge, validate};
+
+/// A triangle facing +Z, with a tangent along +X, on a node and on its mirror image, plus a
+/// camera and a light.
+const SCENE: &str = r#"{
+    "asset": {"version": "2.0"},
+    "extensionsUsed": ["KHR_lights_punctual"],
+    "extensions": {"KHR_lights_punctual": {"lights": [{"type": "point"}]}},
+    "buffers": [{
+        "uri": "data:application/octet-stream;base64,AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAAAAAAAAAAAAAAAIA/AAAAAAAAAAAAAIA/AAAAAAAAAAAAAIA/AACAPwAAAAAAAAAAAACAPwAAgD8AAAAAAAAAAAAAgD8AAIA/AAAAAAAAAAAAAIA/AAABAAIA",
+        "byteLength": 126
+    }],
+    "bufferViews": [
+        {"buffer": 0, "byteLength": 120},
+        {"buffer": 0, "byteOffset": 120, "byteLength": 6}
+    ],
+    "accessors": [
+        {"bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3", "min": [0, 0, 0], "max": [1, 1, 0]},
+        {"bufferView": 0, "byteOffset": 36, "componentType": 5126, "count": 3, "type": "VEC3"},
+        {"bufferView": 0, "byteOffset": 72, "componentType": 5126, "count": 3, "type": "VEC4"},
+        {"bufferView": 1, "componentType": 5123, "count": 3, "type": "SCALAR"}
+    ],
+    "meshes": [{"primitives": [{"attributes": {"POSITION": 0, "NORMAL": 1, "TANGENT": 2}, "indices": 3}]}],
+    "cameras": [{"type": "perspective", "perspective": {"yfov": 1, "znear": 0.1}}],
+    "nodes": [
+        {"mesh": 0, "translation": [0, 0, 1], "children": [2]},
+        {"mesh": 0, "scale": [1, 1, -1], "translation": [0, 0, -1]},
+        {"camera": 0, "translation": [0, 0, 5]},
+        {"extensions": {"KHR_lights_punctual": {"light": 0}}, "translation": [2, 0, 0]}
+    ],
+    "scenes": [{"nodes": [0, 1, 3]}]
+}"#;
+
+fn sub(a: &[f32], b: &[f32]) -> [f32; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+#[test]
+fn mirrored_nodes() {
+    let (gltf, mut buffers) = common::load(SCENE);
+    let gltf = merge::merge_scene(gltf, &mut buffers, 0, 0);
+
+    // appended to the existing buffer, 4 byte aligned
+    assert_eq!(gltf.buffers.len(), 1);
+    assert_eq!(gltf.buffers[0].byte_length, buffers[0].len());
+    assert!(gltf.buffer_views.iter().all(|v| v.byte_offset >= 128));
+    let report = validate::validate(&gltf, &buffers);
+    assert!(report.is_valid(), "{report:#?}");
+
+    assert_eq!(gltf.meshes.len(), 1);
+    let primitive = &gltf.meshes[0].primitives[0];
+    let read = |semantic| accessor::read_f32(&gltf, &buffers, primitive.attributes[semantic]);
+    let positions = read("POSITION");
+    let normals = read("NORMAL");
+    let tangents = read("TANGENT");
+    let indices = accessor::read_u32(&gltf, &buffers, primitive.indices.unwrap());
+    assert_eq!(indices, [0, 1, 2, 3, 5, 4]);
+
+    common::assert_close(
+        &normals,
+        &[
+            0., 0., 1., 0., 0., 1., 0., 0., 1., 0., 0., -1., 0., 0., -1., 0., 0., -1.,
+        ],
+        1e-6,
+    );
+    // the winding faces the way the normals do
+    for (triangle, normal) in indices.chunks_exact(3).zip(normals.chunks(9)) {
+        let p: Vec<_> = triangle
+            .iter()
+            .map(|&i| &positions[i as usize * 3..][..3])
+            .collect();
+        let (e1, e2) = (sub(p[1], p[0]), sub(p[2], p[0]));
+        let face_z = e1[0] * e2[1] - e1[1] * e2[0];
+        assert!(face_z * normal[2] > 0.0, "{positions:?} {indices:?}");
+    }
+    let w: Vec<f32> = tangents.chunks(4).map(|t| t[3]).collect();
+    assert_eq!(w, [1.0, 1.0, 1.0, -1.0, -1.0, -1.0]);
+}
+
+#[test]
+fn cameras_and_lights_are_kept() {
+    let (gltf, mut buffers) = common::load(SCENE);
+    let gltf = merge::merge_scene(gltf, &mut buffers, 0, 0);
+
+    assert_eq!(gltf.scenes[0].nodes, [0, 1, 2]);
+    assert_eq!(gltf.nodes.len(), 3);
+    let camera = &gltf.nodes[1];
+    assert_eq!(camera.camera, Some(0));
+    assert_eq!(&camera.matrix.unwrap()[12..15], &[0.0, 0.0, 6.0]);
+    let light = &gltf.nodes[2];
+    let extensions = light.extensions.as_ref().unwrap();
+    assert_eq!(extensions.khr_lights_punctual.as_ref().unwrap().light, 0);
+    assert_eq!(&light.matrix.unwrap()[12..15], &[2.0, 0.0, 0.0]);
+    assert_eq!(gltf.extensions_used, ["KHR_lights_punctual"]);
+}
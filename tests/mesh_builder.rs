@@ -0,0 +1,172 @@
+use nanogltf::mesh_builder::{
+    ComponentFormat, IndexFormat, Indices, Layout, MeshBuilder, VertexFormat,
+};
+use nanogltf::Gltf;
+
+/// A triangle with float POSITION and TEXCOORD_0 and an u32 _ID, buffer stored in the vec.
+fn triangle() -> (Gltf, Vec<Vec<u8>>) {
+    let json = r#"{
+        "asset": {"version": "2.0"},
+        "buffers": [{"byteLength": 72}],
+        "bufferViews": [{"buffer": 0, "byteLength": 72}],
+        "accessors": [
+            {"bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3"},
+            {"bufferView": 0, "byteOffset": 36, "componentType": 5126, "count": 3, "type": "VEC2"},
+            {"bufferView": 0, "byteOffset": 60, "componentType": 5125, "count": 3, "type": "SCALAR"}
+        ],
+        "meshes": [{"primitives": [{"attributes": {"POSITION": 0, "TEXCOORD_0": 1, "_ID": 2}}]}]
+    }"#;
+    let floats: [f32; 15] = [
+        0., 0., 0., 1., 0., 0., 0., 1., 0., // POSITION
+        0.5, -0.5, 1.5, 0.25, 0., 1., // TEXCOORD_0
+    ];
+    let mut data: Vec<u8> = floats.iter().flat_map(|f| f.to_le_bytes()).collect();
+    for id in [16_777_217u32, 7, u32::MAX] {
+        data.extend_from_slice(&id.to_le_bytes());
+    }
+    (Gltf::from_json(json).unwrap(), vec![data])
+}
+
+fn builder() -> MeshBuilder {
+    MeshBuilder::new()
+        .attribute("POSITION", VertexFormat::FLOAT32X3)
+        .attribute("NORMAL", VertexFormat::new(ComponentFormat::I8Norm, 4))
+        .attribute("TEXCOORD_0", VertexFormat::new(ComponentFormat::U16Norm, 2))
+}
+
+fn f32_at(bytes: &[u8], at: usize) -> f32 {
+    f32::from_le_bytes(bytes[at..at + 4].try_into().unwrap())
+}
+
+#[test]
+fn interleaved() {
+    let (gltf, buffers) = triangle();
+    let mesh = builder().build(&gltf, &buffers, &gltf.meshes[0].primitives[0]);
+    let layouts: Vec<_> = mesh
+        .attributes
+        .iter()
+        .map(|a| (a.offset, a.stride))
+        .collect();
+    assert_eq!(layouts, [(0, 20), (12, 20), (16, 20)]);
+    assert_eq!(mesh.vertices.len(), 60);
+
+    let vertex = &mesh.vertices[20..40];
+    assert_eq!(f32_at(vertex, 0), 1.0);
+    // missing NORMAL filled with +Z
+    assert!(!mesh.attributes[1].present);
+    assert_eq!(&vertex[12..16], &[0, 0, 127, 0]);
+    // 1.5 clamped, 0.25 * 65535 rounded up
+    assert_eq!(&vertex[16..20], &[0xff, 0xff, 0x00, 0x40]);
+}
+
+#[test]
+fn planar() {
+    let (gltf, buffers) = triangle();
+    let mesh =
+        builder()
+            .layout(Layout::Planar)
+            .build(&gltf, &buffers, &gltf.meshes[0].primitives[0]);
+    let layouts: Vec<_> = mesh
+        .attributes
+        .iter()
+        .map(|a| (a.offset, a.stride))
+        .collect();
+    assert_eq!(layouts, [(0, 12), (36, 4), (48, 4)]);
+    assert_eq!(mesh.vertices.len(), 60);
+    assert_eq!(f32_at(&mesh.vertices, 12), 1.0);
+    assert_eq!(&mesh.vertices[40..44], &[0, 0, 127, 0]);
+    // 0.5 * 65535 rounded up, -0.5 clamped
+    assert_eq!(&mesh.vertices[48..52], &[0x00, 0x80, 0x00, 0x00]);
+}
+
+#[test]
+fn defaults_and_rounding() {
+    let (gltf, buffers) = triangle();
+    let mesh = MeshBuilder::new()
+        .attribute_with_default(
+            "TEXCOORD_0",
+            VertexFormat::new(ComponentFormat::F32, 4),
+            [9.0, 9.0, 7.0, 5.0],
+        )
+        .attribute("TEXCOORD_0", VertexFormat::new(ComponentFormat::U8Norm, 2))
+        .attribute("TEXCOORD_0", VertexFormat::new(ComponentFormat::I8Norm, 2))
+        .attribute("TEXCOORD_0", VertexFormat::new(ComponentFormat::I16Norm, 2))
+        .attribute("COLOR_0", VertexFormat::UNORM8X4)
+        .build(&gltf, &buffers, &gltf.meshes[0].primitives[0]);
+    let vertex = &mesh.vertices[..mesh.attributes[0].stride];
+    // components the accessor doesn't have come from the default
+    let floats: Vec<f32> = (0..4).map(|c| f32_at(vertex, c * 4)).collect();
+    assert_eq!(floats, [0.5, -0.5, 7.0, 5.0]);
+    // 127.5 rounds away from zero, negatives clamp to 0
+    assert_eq!(&vertex[16..18], &[128, 0]);
+    // -63.5 rounds away from zero too
+    assert_eq!(&vertex[20..22], &[64, (-64i8) as u8]);
+    assert_eq!(
+        &vertex[24..28],
+        [16384i16.to_le_bytes(), (-16384i16).to_le_bytes()].concat()
+    );
+    // missing COLOR_0 is opaque white
+    assert_eq!(&vertex[28..32], &[255; 4]);
+}
+
+#[test]
+fn integer_formats_keep_integers() {
+    let (gltf, buffers) = triangle();
+    let mesh = MeshBuilder::new()
+        .attribute("_ID", VertexFormat::new(ComponentFormat::U32, 1))
+        .attribute("_ID", VertexFormat::new(ComponentFormat::F32, 1))
+        .build(&gltf, &buffers, &gltf.meshes[0].primitives[0]);
+    let ids: Vec<u32> = mesh
+        .vertices
+        .chunks(8)
+        .map(|v| u32::from_le_bytes(v[..4].try_into().unwrap()))
+        .collect();
+    assert_eq!(ids, [16_777_217, 7, u32::MAX]);
+    // f32 can't hold 2^24 + 1
+    assert_eq!(f32_at(&mesh.vertices, 4), 16_777_216.0);
+}
+
+#[test]
+fn index_format() {
+    for (vertex_count, format) in [(65535, IndexFormat::U16), (65536, IndexFormat::U32)] {
+        // non indexed, POSITION without a buffer view reads zeros
+        let json = format!(
+            r#"{{
+                "asset": {{"version": "2.0"}},
+                "accessors": [{{"componentType": 5126, "count": {vertex_count}, "type": "VEC3"}}],
+                "meshes": [{{"primitives": [{{"attributes": {{"POSITION": 0}}}}]}}]
+            }}"#
+        );
+        let gltf = Gltf::from_json(&json).unwrap();
+        let primitive = &gltf.meshes[0].primitives[0];
+        let mesh = MeshBuilder::new().build(&gltf, &[], primitive);
+        assert_eq!(mesh.indices.format(), format);
+        assert_eq!(mesh.indices.len(), vertex_count);
+        match mesh.indices {
+            Indices::U16(indices) => assert_eq!(indices.last(), Some(&65534)),
+            Indices::U32(indices) => assert_eq!(indices.last(), Some(&65535)),
+        }
+
+        // U16 is only a preference
+        let mesh = MeshBuilder::new()
+            .index_format(IndexFormat::U16)
+            .build(&gltf, &[], primitive);
+        assert_eq!(mesh.indices.format(), format);
+    }
+}
+
+#[test]
+fn invalid_accessors_use_the_default() {
+    let (mut gltf, buffers) = triangle();
+    gltf.accessors[1].type_ = Some("VEC5".to_string());
+    let attributes = &mut gltf.meshes[0].primitives[0].attributes;
+    attributes.insert("NORMAL".to_string(), 9);
+    let mesh = builder().build(&gltf, &buffers, &gltf.meshes[0].primitives[0]);
+
+    let present: Vec<_> = mesh.attributes.iter().map(|a| a.present).collect();
+    assert_eq!(present, [true, false, false]);
+    let vertex = &mesh.vertices[20..40];
+    assert_eq!(f32_at(vertex, 0), 1.0);
+    assert_eq!(&vertex[12..16], &[0, 0, 127, 0]);
+    assert_eq!(&vertex[16..20], &[0; 4]);
+}
@@ -0,0 +1,165 @@
+mod common;
+
+use nanogltf::{accessor, meshopt, validate};
+use nanoserde::DeJson;
+
+/// Source data of `meshopt.gltf`, see `fixtures/meshopt/generate.py`.
+#[derive(DeJson)]
+struct Expected {
+    positions: Vec<f32>,
+    normals: Vec<f32>,
+    tangents: Vec<f32>,
+    rotations: Vec<f32>,
+    scalars: Vec<f32>,
+    indices: Vec<u32>,
+}
+
+fn expected() -> Expected {
+    let json = std::fs::read_to_string(common::fixture_path("meshopt_expected.json")).unwrap();
+    Expected::deserialize_json(&json).unwrap()
+}
+
+fn decompressed() -> (nanogltf::Gltf, Vec<Vec<u8>>) {
+    let (mut gltf, mut buffers) = common::fixture("meshopt.gltf");
+    assert_eq!(meshopt::decompress(&mut gltf, &mut buffers), 8);
+    (gltf, buffers)
+}
+
+#[test]
+fn valid_document() {
+    let (gltf, buffers) = common::fixture("meshopt.gltf");
+    let report = validate::validate(&gltf, &buffers);
+    assert!(report.is_valid(), "{report:#?}");
+}
+
+#[test]
+fn attributes() {
+    let (gltf, buffers) = decompressed();
+    let expected = expected();
+    assert_eq!(accessor::read_f32(&gltf, &buffers, 0), expected.positions);
+}
+
+#[test]
+fn decompress_removes_the_extension() {
+    let (gltf, _) = decompressed();
+    assert!(gltf
+        .buffer_views
+        .iter()
+        .all(|view| view.meshopt_compression().is_none()));
+}
+
+#[test]
+fn read_without_decompress() {
+    let (gltf, buffers) = common::fixture("meshopt.gltf");
+    let expected = expected();
+    assert_eq!(accessor::read_f32(&gltf, &buffers, 0), expected.positions);
+    assert_eq!(accessor::read_f32(&gltf, &buffers, 4), expected.scalars);
+    assert!(accessor::read_u32(&gltf, &buffers, 7) == expected.indices);
+}
+
+#[test]
+fn view_of_compressed_data() {
+    let (gltf, buffers) = common::fixture("meshopt.gltf");
+    assert!(accessor::AccessorView::<[f32; 3]>::new(&gltf, &buffers, 0).is_none());
+
+    let (gltf, buffers) = decompressed();
+    let view = accessor::AccessorView::<[f32; 3]>::new(&gltf, &buffers, 0).unwrap();
+    let positions: Vec<f32> = view.iter().flatten().collect();
+    assert_eq!(positions, expected().positions);
+}
+
+#[test]
+fn octahedral_filter() {
+    let (gltf, buffers) = decompressed();
+    let expected = expected();
+    // 8 bit normals
+    common::assert_close(
+        &accessor::read_f32(&gltf, &buffers, 1),
+        &expected.normals,
+        0.02,
+    );
+    // 16 bit tangents, w is kept as is
+    common::assert_close(
+        &accessor::read_f32(&gltf, &buffers, 2),
+        &expected.tangents,
+        1e-4,
+    );
+}
+
+#[test]
+fn quaternion_filter() {
+    let (gltf, buffers) = decompressed();
+    common::assert_close(
+        &accessor::read_f32(&gltf, &buffers, 3),
+        &expected().rotations,
+        1e-4,
+    );
+}
+
+#[test]
+fn exponential_filter() {
+    let (gltf, buffers) = decompressed();
+    assert_eq!(accessor::read_f32(&gltf, &buffers, 4), expected().scalars);
+}
+
+/// Triangles rotated to start at their smallest index, the index codec is free to rotate
+/// them, keeping the winding.
+fn canonical(indices: &[u32]) -> Vec<[u32; 3]> {
+    indices
+        .chunks(3)
+        .map(|t| {
+            let first = (0..3).min_by_key(|&k| t[k]).unwrap();
+            [0, 1, 2].map(|k| t[(first + k) % 3])
+        })
+        .collect()
+}
+
+#[test]
+fn triangles() {
+    let (gltf, buffers) = decompressed();
+    let expected = canonical(&expected().indices);
+    // index codec version 0 and 1
+    for accessor in [5, 6] {
+        let triangles = canonical(&accessor::read_u32(&gltf, &buffers, accessor));
+        assert!(triangles == expected, "accessor {accessor}");
+    }
+}
+
+#[test]
+fn index_sequence() {
+    let (gltf, buffers) = decompressed();
+    assert!(accessor::read_u32(&gltf, &buffers, 7) == expected().indices);
+}
+
+#[test]
+fn unknown_filter() {
+    let (mut gltf, buffers) = common::fixture("meshopt.gltf");
+    let extensions = gltf.buffer_views[1].extensions.as_mut().unwrap();
+    // COLOR is a KHR_meshopt_compression filter, EXT_meshopt_compression has no such thing
+    extensions.ext_meshopt_compression.as_mut().unwrap().filter = "COLOR".to_string();
+    assert!(meshopt::decode_buffer_view(&gltf, &buffers, 1).is_none());
+}
+
+#[test]
+fn malformed_data() {
+    let (mut gltf, mut buffers) = common::fixture("meshopt.gltf");
+    let compression = gltf.buffer_views[5].meshopt_compression().unwrap();
+    buffers[1][compression.byte_offset + 1..compression.byte_offset + compression.byte_length]
+        .fill(0xfe);
+
+    let report = validate::validate(&gltf, &buffers);
+    let errors: Vec<_> = report
+        .errors()
+        .map(|issue| (issue.code, issue.pointer.as_str()))
+        .collect();
+    assert_eq!(
+        errors,
+        [(
+            "MESHOPT_INVALID_DATA",
+            "/bufferViews/5/extensions/EXT_meshopt_compression"
+        )]
+    );
+    assert_eq!(meshopt::decompress(&mut gltf, &mut buffers), 7);
+    // the view failing to decode keeps its extension
+    assert!(gltf.buffer_views[5].meshopt_compression().is_some());
+}
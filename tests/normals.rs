@@ -0,0 +1,160 @@
+use std::f32::consts::PI;
+
+use nanogltf::normals::{self, GeneratedNormals};
+
+fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn normalize(a: [f32; 3]) -> [f32; 3] {
+    let l = dot(a, a).sqrt();
+    a.map(|x| x / l)
+}
+
+/// Unit cube around the origin, 8 shared corners, counter-clockwise from the outside.
+fn cube() -> (Vec<[f32; 3]>, Vec<u32>) {
+    let positions = (0..8)
+        .map(|i| [i & 1, (i >> 1) & 1, (i >> 2) & 1].map(|c| c as f32 - 0.5))
+        .collect();
+    #[rustfmt::skip]
+    let quads = [
+        [0, 2, 3, 1], [4, 5, 7, 6], // -Z, +Z
+        [0, 1, 5, 4], [2, 6, 7, 3], // -Y, +Y
+        [0, 4, 6, 2], [1, 3, 7, 5], // -X, +X
+    ];
+    let indices = quads
+        .iter()
+        .flat_map(|q| [q[0], q[1], q[2], q[0], q[2], q[3]])
+        .collect();
+    (positions, indices)
+}
+
+/// The normal of every corner, in the original vertices.
+fn corners(generated: &GeneratedNormals) -> impl Iterator<Item = (u32, [f32; 3])> + '_ {
+    generated
+        .indices
+        .iter()
+        .map(|&v| (generated.remap[v as usize], generated.normals[v as usize]))
+}
+
+#[test]
+fn flat_cube() {
+    let (positions, indices) = cube();
+    let flat = normals::flat_normals(&positions, &indices);
+    // every corner is shared by three faces, each gets its own vertex
+    assert_eq!(flat.normals.len(), 24);
+    assert_eq!(flat.indices.len(), indices.len());
+    for (triangle, new) in indices.chunks(3).zip(flat.indices.chunks(3)) {
+        let center = [0, 1, 2].map(|c| {
+            triangle
+                .iter()
+                .map(|&v| positions[v as usize][c])
+                .sum::<f32>()
+                / 3.0
+        });
+        for &v in new {
+            let normal = flat.normals[v as usize];
+            // an axis, pointing out of the cube
+            assert_eq!(
+                normal.iter().filter(|c| c.abs() == 1.0).count(),
+                1,
+                "{normal:?}"
+            );
+            assert!(dot(normal, center) > 0.0, "{normal:?}");
+        }
+    }
+    for (original, new) in flat.indices.iter().enumerate() {
+        assert_eq!(flat.remap[*new as usize], indices[original]);
+    }
+
+    // a cube has no edge below the crease angle
+    let smooth = normals::smooth_normals(&positions, &indices, PI / 3.0);
+    assert_eq!(smooth.normals.len(), 24);
+    assert!(corners(&smooth).eq(corners(&flat)));
+}
+
+#[test]
+fn smooth_sphere() {
+    let (rings, segments) = (16, 32);
+    let mut positions = vec![[0.0, 1.0, 0.0]];
+    for ring in 1..rings {
+        let theta = ring as f32 * PI / rings as f32;
+        for segment in 0..segments {
+            let phi = segment as f32 * 2.0 * PI / segments as f32;
+            positions.push([
+                theta.sin() * phi.cos(),
+                theta.cos(),
+                -theta.sin() * phi.sin(),
+            ]);
+        }
+    }
+    positions.push([0.0, -1.0, 0.0]);
+    let bottom = positions.len() as u32 - 1;
+    let vertex = |ring: u32, segment: u32| 1 + (ring - 1) * segments + segment % segments;
+    let mut indices = vec![];
+    for s in 0..segments {
+        indices.extend([0, vertex(1, s), vertex(1, s + 1)]);
+        indices.extend([bottom, vertex(rings - 1, s + 1), vertex(rings - 1, s)]);
+        for r in 1..rings - 1 {
+            let quad = [
+                vertex(r, s),
+                vertex(r + 1, s),
+                vertex(r + 1, s + 1),
+                vertex(r, s + 1),
+            ];
+            indices.extend([quad[0], quad[1], quad[2], quad[0], quad[2], quad[3]]);
+        }
+    }
+
+    let smooth = normals::smooth_normals(&positions, &indices, PI);
+    // nothing split
+    assert_eq!(smooth.normals.len(), positions.len());
+    for (vertex, normal) in corners(&smooth) {
+        let radial = positions[vertex as usize];
+        assert!((dot(normal, normal) - 1.0).abs() < 1e-5);
+        assert!(dot(normal, radial) > 0.999, "{vertex} {normal:?}");
+    }
+}
+
+#[test]
+fn crease_angle() {
+    // two unit quads folded at a right angle along the edge between vertices 3 and 2:
+    // a floor facing +Y and a wall facing +Z
+    let positions = [
+        [0., 0., 1.],
+        [1., 0., 1.],
+        [1., 0., 0.],
+        [0., 0., 0.],
+        [0., 1., 0.],
+        [1., 1., 0.],
+    ];
+    let indices = [0, 1, 2, 0, 2, 3, 3, 2, 5, 3, 5, 4];
+
+    let hard = normals::smooth_normals(&positions, &indices, PI / 4.0);
+    // the two vertices of the shared edge are split
+    assert_eq!(hard.normals.len(), 8);
+    for (triangle, new) in indices.chunks(3).zip(hard.indices.chunks(3)) {
+        let expected = if triangle.contains(&0) || triangle.contains(&1) {
+            [0.0, 1.0, 0.0]
+        } else {
+            [0.0, 0.0, 1.0]
+        };
+        for &v in new {
+            assert_eq!(hard.normals[v as usize], expected);
+        }
+    }
+
+    let soft = normals::smooth_normals(&positions, &indices, PI / 2.0 + 0.01);
+    assert_eq!(soft.normals.len(), 6);
+    for (vertex, normal) in corners(&soft) {
+        match vertex {
+            // both faces have 90 degrees at each end of the edge
+            2 | 3 => assert!(
+                dot(normal, normalize([0.0, 1.0, 1.0])) > 0.9999,
+                "{normal:?}"
+            ),
+            0 | 1 => assert_eq!(normal, [0.0, 1.0, 0.0]),
+            _ => assert_eq!(normal, [0.0, 0.0, 1.0]),
+        }
+    }
+}
@@ -0,0 +1,80 @@
+mod common;
+
+use nanogltf::{bounds, scene};
+
+#[test]
+fn world_transforms_compose_parents() {
+    let (gltf, _) = common::load(
+        r#"{
+            "nodes": [
+                {"children": [1], "translation": [1, 0, 0]},
+                {"children": [2], "scale": [2, 2, 2]},
+                {"translation": [0, 1, 0]}
+            ],
+            "scenes": [{"nodes": [0]}]
+        }"#,
+    );
+    let world = scene::world_transforms(&gltf);
+    assert_eq!(&world[2][12..15], &[1.0, 2.0, 0.0]);
+    assert_eq!(scene::scene_nodes(&gltf, 0), vec![0, 1, 2]);
+}
+
+#[test]
+fn cycles_do_not_hang() {
+    let (gltf, buffers) = common::load(
+        r#"{
+            "nodes": [
+                {"children": [1]},
+                {"children": [2], "translation": [1, 0, 0]},
+                {"children": [1], "translation": [0, 1, 0]}
+            ],
+            "scenes": [{"nodes": [0]}]
+        }"#,
+    );
+    assert_eq!(scene::world_transforms(&gltf).len(), 3);
+    assert_eq!(scene::scene_nodes(&gltf, 0), vec![0, 1, 2]);
+    assert_eq!(bounds::node_bounds(&gltf, &buffers), vec![None; 3]);
+    assert!(scene::flatten_scene(&gltf, 0).is_empty());
+}
+
+#[test]
+fn deep_hierarchy() {
+    let depth = 100_000;
+    let nodes: Vec<String> = (0..depth)
+        .map(|i| match i + 1 < depth {
+            true => format!(r#"{{"children": [{}], "translation": [1, 0, 0]}}"#, i + 1),
+            false => r#"{"translation": [1, 0, 0]}"#.to_string(),
+        })
+        .collect();
+    let json = format!(
+        r#"{{"nodes": [{}], "scenes": [{{"nodes": [0]}}]}}"#,
+        nodes.join(",")
+    );
+    let (gltf, _) = common::load(&json);
+    let world = scene::world_transforms(&gltf);
+    assert_eq!(world[depth - 1][12], depth as f32);
+    assert_eq!(scene::scene_nodes(&gltf, 0).len(), depth);
+}
+
+#[test]
+fn morph_weights() {
+    let (gltf, _) = common::load(
+        r#"{
+            "meshes": [
+                {"primitives": [{"attributes": {}, "targets": [{}, {}]}]},
+                {"primitives": [{"attributes": {}, "targets": [{}, {}]}], "weights": [0.5, 1]},
+                {"primitives": [{"attributes": {}}]}
+            ],
+            "nodes": [{"mesh": 0}, {"mesh": 1}, {"mesh": 1, "weights": [1, 0]}, {"mesh": 2}],
+            "scenes": [{"nodes": [0, 1, 2, 3]}]
+        }"#,
+    );
+    let weights: Vec<_> = scene::flatten_scene(&gltf, 0)
+        .into_iter()
+        .map(|item| item.weights)
+        .collect();
+    assert_eq!(
+        weights,
+        [vec![0.0, 0.0], vec![0.5, 1.0], vec![1.0, 0.0], vec![]]
+    );
+}
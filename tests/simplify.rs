@@ -0,0 +1,227 @@
+mod common;
+
+use nanogltf::simplify::{self, Options};
+use nanogltf::{accessor, validate, Gltf};
+
+const N: usize = 9;
+
+/// One primitive with float attributes, indexed or not, in a uri-less buffer.
+fn document(
+    attributes: &[(&str, usize, Vec<f32>)],
+    indices: Option<&[u32]>,
+) -> (Gltf, Vec<Vec<u8>>) {
+    let mut data = vec![];
+    let mut views = vec![];
+    let mut accessors = vec![];
+    let mut names = vec![];
+    for (i, (semantic, components, values)) in attributes.iter().enumerate() {
+        let offset = data.len();
+        data.extend(values.iter().flat_map(|v| v.to_le_bytes()));
+        views.push(format!(
+            r#"{{"buffer": 0, "byteOffset": {offset}, "byteLength": {}}}"#,
+            data.len() - offset
+        ));
+        let bounds = match *semantic {
+            "POSITION" => {
+                let min = (0..3).map(|c| values.chunks(3).map(|p| p[c]).fold(f32::MAX, f32::min));
+                let max = (0..3).map(|c| values.chunks(3).map(|p| p[c]).fold(f32::MIN, f32::max));
+                format!(
+                    r#", "min": {:?}, "max": {:?}"#,
+                    min.collect::<Vec<_>>(),
+                    max.collect::<Vec<_>>()
+                )
+            }
+            _ => String::new(),
+        };
+        let count = values.len() / components;
+        let type_ = ["SCALAR", "VEC2", "VEC3", "VEC4"][components - 1];
+        accessors.push(format!(r#"{{"bufferView": {i}, "componentType": 5126, "count": {count}, "type": "{type_}"{bounds}}}"#));
+        names.push(format!(r#""{semantic}": {i}"#));
+    }
+    let mut primitive_indices = String::new();
+    if let Some(indices) = indices {
+        let offset = data.len();
+        data.extend(indices.iter().flat_map(|i| i.to_le_bytes()));
+        views.push(format!(
+            r#"{{"buffer": 0, "byteOffset": {offset}, "byteLength": {}}}"#,
+            data.len() - offset
+        ));
+        accessors.push(format!(
+            r#"{{"bufferView": {}, "componentType": 5125, "count": {}, "type": "SCALAR"}}"#,
+            views.len() - 1,
+            indices.len()
+        ));
+        primitive_indices = format!(r#", "indices": {}"#, accessors.len() - 1);
+    }
+    let json = format!(
+        r#"{{
+            "asset": {{"version": "2.0"}},
+            "buffers": [{{"byteLength": {}}}],
+            "bufferViews": [{}],
+            "accessors": [{}],
+            "meshes": [{{"primitives": [{{"attributes": {{{}}}{primitive_indices}}}]}}]
+        }}"#,
+        data.len(),
+        views.join(","),
+        accessors.join(","),
+        names.join(",")
+    );
+    let (gltf, _) = common::load(&json);
+    (gltf, vec![data])
+}
+
+/// Flat N x N grid, two triangles per cell.
+fn grid() -> (Vec<f32>, Vec<u32>) {
+    let positions = (0..N * N)
+        .flat_map(|v| [(v % N) as f32, (v / N) as f32, 0.0])
+        .collect();
+    let mut indices = vec![];
+    for y in 0..N - 1 {
+        for x in 0..N - 1 {
+            let v = (y * N + x) as u32;
+            let n = N as u32;
+            indices.extend([v, v + 1, v + n, v + 1, v + n + 1, v + n]);
+        }
+    }
+    (positions, indices)
+}
+
+/// Total signed area of the triangles, they cover the grid without holes or overlaps.
+fn area(positions: &[f32], indices: &[u32]) -> f32 {
+    indices
+        .chunks_exact(3)
+        .map(|t| {
+            let p = |i: usize| &positions[t[i] as usize * 3..][..2];
+            let (a, b, c) = (p(0), p(1), p(2));
+            ((b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])) * 0.5
+        })
+        .sum()
+}
+
+fn options(target_ratio: f32, lock_border: bool) -> Options {
+    Options {
+        target_ratio,
+        target_error: 0.01,
+        lock_border,
+    }
+}
+
+#[test]
+fn lock_border() {
+    let (positions, indices) = grid();
+    let positions: Vec<[f32; 3]> = positions.chunks(3).map(|p| [p[0], p[1], p[2]]).collect();
+    let border = |v: &u32| {
+        let (x, y) = (*v as usize % N, *v as usize / N);
+        x == 0 || y == 0 || x == N - 1 || y == N - 1
+    };
+    let used_border = |options: &Options| {
+        let simplified = simplify::simplify(&positions, &indices, options);
+        assert!(simplified.indices.len() < indices.len());
+        assert_eq!(
+            area(positions.as_flattened(), &simplified.indices),
+            ((N - 1) * (N - 1)) as f32
+        );
+        let mut used: Vec<u32> = simplified.indices.into_iter().filter(border).collect();
+        used.sort();
+        used.dedup();
+        used.len()
+    };
+    assert_eq!(used_border(&options(0.1, true)), 4 * (N - 1));
+    assert!(used_border(&options(0.1, false)) < 4 * (N - 1));
+}
+
+#[test]
+fn uv_seam() {
+    // the middle column is split, the left copies have u 0 and the right ones u 1
+    let seam = N / 2;
+    let (mut positions, grid_indices) = grid();
+    let mut texcoords: Vec<f32> = (0..N * N)
+        .flat_map(|v| [(v % N > seam) as u8 as f32, 0.0])
+        .collect();
+    // right copies after the grid, N * N + y
+    for y in 0..N {
+        positions.extend([seam as f32, y as f32, 0.0]);
+        texcoords.extend([1.0, 0.0]);
+    }
+    let indices: Vec<u32> = grid_indices
+        .chunks(3)
+        .flat_map(|t| {
+            let right = t.iter().any(|&v| v as usize % N > seam);
+            t.iter()
+                .map(move |&v| match right && v as usize % N == seam {
+                    true => N as u32 * N as u32 + v / N as u32,
+                    false => v,
+                })
+        })
+        .collect();
+
+    let (gltf, buffers) = document(
+        &[
+            ("POSITION", 3, positions.clone()),
+            ("TEXCOORD_0", 2, texcoords.clone()),
+        ],
+        Some(&indices),
+    );
+    let primitive = &gltf.meshes[0].primitives[0];
+    let simplified =
+        simplify::simplify_primitive(&gltf, &buffers, primitive, &options(0.25, false)).unwrap();
+    assert!(
+        simplified.indices.len() * 2 <= indices.len(),
+        "{}",
+        simplified.indices.len()
+    );
+    assert_eq!(
+        area(&positions, &simplified.indices),
+        ((N - 1) * (N - 1)) as f32
+    );
+    // every triangle stays on its side of the seam, with a single u
+    for t in simplified.indices.chunks(3) {
+        let u: Vec<f32> = t.iter().map(|&v| texcoords[v as usize * 2]).collect();
+        assert!(u.iter().all(|&x| x == u[0]), "{t:?}");
+    }
+}
+
+#[test]
+fn non_indexed() {
+    let (positions, indices) = grid();
+    let unrolled: Vec<f32> = indices
+        .iter()
+        .flat_map(|&v| positions[v as usize * 3..][..3].to_vec())
+        .collect();
+    let normals: Vec<f32> = (0..indices.len()).flat_map(|_| [0.0, 0.0, 1.0]).collect();
+    let (gltf, buffers) = document(
+        &[("POSITION", 3, unrolled.clone()), ("NORMAL", 3, normals)],
+        None,
+    );
+    let primitive = &gltf.meshes[0].primitives[0];
+    let simplified =
+        simplify::simplify_primitive(&gltf, &buffers, primitive, &options(0.25, false)).unwrap();
+    assert!(
+        simplified.indices.len() * 4 <= indices.len(),
+        "{}",
+        simplified.indices.len()
+    );
+    assert_eq!(
+        area(&unrolled, &simplified.indices),
+        ((N - 1) * (N - 1)) as f32
+    );
+}
+
+#[test]
+fn add_mesh_appends_to_the_buffer() {
+    let (positions, indices) = grid();
+    let (mut gltf, mut buffers) = document(&[("POSITION", 3, positions)], Some(&indices));
+    let simplified = simplify::simplify_mesh(&gltf, &buffers, 0, &options(0.5, false));
+    let mesh = simplify::add_mesh(&mut gltf, &mut buffers, 0, 0, &simplified);
+
+    assert_eq!(gltf.buffers.len(), 1);
+    assert_eq!(gltf.buffers[0].byte_length, buffers[0].len());
+    let report = validate::validate(&gltf, &buffers);
+    assert!(report.is_valid(), "{report:#?}");
+    let primitive = &gltf.meshes[mesh].primitives[0];
+    let indices = accessor::read_u32(&gltf, &buffers, primitive.indices.unwrap());
+    assert_eq!(
+        Some(indices),
+        simplified[0].as_ref().map(|s| s.indices.clone())
+    );
+}
@@ -0,0 +1,111 @@
+mod common;
+
+use nanogltf::spec_gloss::{self, Rgba8Image};
+use nanogltf::{Material, MaterialsPbrSpecularGlossiness};
+use nanoserde::DeJson;
+
+fn sg(json: &str) -> MaterialsPbrSpecularGlossiness {
+    MaterialsPbrSpecularGlossiness::deserialize_json(json).unwrap()
+}
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-6
+}
+
+#[test]
+fn dielectric() {
+    // 4% specular is the dielectric reflectance itself: no metal, diffuse kept as is
+    let (base_color, metallic, roughness) =
+        spec_gloss::convert([0.5, 0.25, 0.125, 0.75], [0.04; 3], 0.25);
+    assert_eq!(metallic, 0.0);
+    assert!(base_color
+        .iter()
+        .zip([0.5, 0.25, 0.125, 0.75])
+        .all(|(&a, b)| close(a, b)));
+    assert_eq!(roughness, 0.75);
+
+    // and below it
+    let (_, metallic, _) = spec_gloss::convert([1.0; 4], [0.01; 3], 1.0);
+    assert_eq!(metallic, 0.0);
+}
+
+#[test]
+fn metal() {
+    // black diffuse, gold specular: all metal, the base color is the specular color
+    let gold = [1.0, 0.766, 0.336];
+    let (base_color, metallic, roughness) = spec_gloss::convert([0.0, 0.0, 0.0, 1.0], gold, 1.0);
+    assert!(close(metallic, 1.0), "{metallic}");
+    assert!(
+        base_color[..3].iter().zip(gold).all(|(&a, b)| close(a, b)),
+        "{base_color:?}"
+    );
+    assert_eq!(base_color[3], 1.0);
+    assert_eq!(roughness, 0.0);
+}
+
+#[test]
+fn in_between() {
+    let (base_color, metallic, _) = spec_gloss::convert([0.5, 0.5, 0.5, 1.0], [0.5; 3], 1.0);
+    assert!(metallic > 0.0 && metallic < 1.0, "{metallic}");
+    assert!(base_color[..3].iter().all(|&c| c > 0.0 && c <= 1.0));
+}
+
+#[test]
+fn material() {
+    let mut material = Material::deserialize_json(
+        r#"{
+            "extensions": {
+                "KHR_materials_pbrSpecularGlossiness": {
+                    "diffuseFactor": [0, 0, 0, 1],
+                    "specularFactor": [1, 1, 1],
+                    "glossinessFactor": 0.5
+                }
+            }
+        }"#,
+    )
+    .unwrap();
+    assert!(spec_gloss::convert_material(&mut material));
+    let pbr = &material.pbr_metallic_roughness;
+    assert!(close(pbr.metallic_factor, 1.0));
+    assert_eq!(pbr.roughness_factor, 0.5);
+    assert!(pbr.base_color_factor.iter().all(|&c| close(c, 1.0)));
+
+    let mut material = Material::deserialize_json("{}").unwrap();
+    assert!(!spec_gloss::convert_material(&mut material));
+
+    // textured materials get neutral factors, the images carry them
+    let textured = sg(r#"{"diffuseFactor": [0.5, 0.5, 0.5, 1], "diffuseTexture": {"index": 0}}"#);
+    let pbr = spec_gloss::convert_factors(&textured);
+    assert_eq!(pbr.base_color_factor, [1.0; 4]);
+    assert_eq!((pbr.metallic_factor, pbr.roughness_factor), (1.0, 1.0));
+}
+
+#[test]
+fn images() {
+    let sg = sg(r#"{"specularFactor": [0.04, 0.04, 0.04], "glossinessFactor": 0.5}"#);
+    let diffuse = Rgba8Image {
+        width: 2,
+        height: 1,
+        data: vec![128, 128, 128, 255, 255, 0, 0, 128],
+    };
+    let specular_glossiness = Rgba8Image {
+        width: 2,
+        height: 1,
+        data: vec![255, 255, 255, 255, 255, 255, 255, 0],
+    };
+    let (base_color, metallic_roughness) =
+        spec_gloss::convert_images(&sg, Some(&diffuse), Some(&specular_glossiness)).unwrap();
+    // dielectric, the diffuse color goes through sRGB and back unchanged
+    assert_eq!(base_color.data, diffuse.data);
+    // roughness in G from the glossiness alpha and factor, metallic in B
+    assert_eq!(metallic_roughness.data, [0, 128, 0, 255, 0, 255, 0, 255]);
+    assert_eq!((base_color.width, base_color.height), (2, 1));
+
+    assert!(spec_gloss::convert_images(&sg, None, None).is_none());
+    let small = Rgba8Image {
+        width: 1,
+        height: 1,
+        data: vec![0; 4],
+    };
+    assert!(spec_gloss::convert_images(&sg, Some(&diffuse), Some(&small)).is_none());
+}
@@ -0,0 +1,54 @@
+mod common;
+
+use nanogltf::tangents;
+
+const POSITIONS: [[f32; 3]; 4] = [[0., 0., 0.], [1., 0., 0.], [1., 1., 0.], [0., 1., 0.]];
+const NORMALS: [[f32; 3]; 4] = [[0., 0., 1.]; 4];
+const INDICES: [u32; 6] = [0, 1, 2, 0, 2, 3];
+
+#[test]
+fn quad() {
+    let uvs = [[0., 0.], [1., 0.], [1., 1.], [0., 1.]];
+    let tangents = tangents::compute_tangents(&POSITIONS, &NORMALS, &uvs, &INDICES);
+    assert_eq!(tangents, [[1.0, 0.0, 0.0, 1.0]; 4]);
+}
+
+#[test]
+fn mirrored_uvs() {
+    // u runs along -X, the bitangent cross(normal, tangent) * w still follows +v
+    let uvs = [[1., 0.], [0., 0.], [0., 1.], [1., 1.]];
+    let tangents = tangents::compute_tangents(&POSITIONS, &NORMALS, &uvs, &INDICES);
+    assert_eq!(tangents, [[-1.0, 0.0, 0.0, -1.0]; 4]);
+}
+
+#[test]
+fn degenerate_uvs() {
+    // a zero area uv triangle gives no direction, any unit tangent perpendicular to the
+    // normal will do
+    let uvs = [[0.5, 0.5]; 4];
+    let tangents = tangents::compute_tangents(&POSITIONS, &NORMALS, &uvs, &INDICES);
+    for t in tangents {
+        assert!(t.iter().all(|c| c.is_finite()), "{t:?}");
+        assert!(
+            (t[0] * t[0] + t[1] * t[1] + t[2] * t[2] - 1.0).abs() < 1e-6,
+            "{t:?}"
+        );
+        assert_eq!(t[2], 0.0);
+        assert_eq!(t[3].abs(), 1.0);
+    }
+}
+
+#[test]
+fn generate_from_primitive() {
+    // the sparse accessor moves vertex 2 to (2, 2, 0), shearing the second triangle
+    let (gltf, buffers) = common::fixture("quad.gltf");
+    let primitive = &gltf.meshes[0].primitives[0];
+    let tangents = tangents::generate_tangents(&gltf, &buffers, primitive, 0).unwrap();
+    assert_eq!(tangents.len(), 4);
+    // vertex 1 is only on the first triangle, vertex 3 only on the second
+    common::assert_close(&tangents[1], &[1.0, 0.0, 0.0, 1.0], 1e-6);
+    let s = 5f32.sqrt();
+    common::assert_close(&tangents[3], &[2.0 / s, 1.0 / s, 0.0, 1.0], 1e-6);
+
+    assert!(tangents::generate_tangents(&gltf, &buffers, primitive, 1).is_none());
+}
@@ -0,0 +1,60 @@
+mod common;
+
+use nanogltf::{texture_transform, Material, TextureTransform};
+use nanoserde::DeJson;
+
+fn transform(json: &str) -> TextureTransform {
+    TextureTransform::deserialize_json(json).unwrap()
+}
+
+#[test]
+fn defaults_are_identity() {
+    let identity = transform("{}");
+    assert_eq!(identity.matrix(), [1., 0., 0., 0., 1., 0., 0., 0., 1.]);
+    assert_eq!(identity.apply([0.25, 0.75]), [0.25, 0.75]);
+}
+
+#[test]
+fn scale_then_rotate_then_translate() {
+    let t =
+        transform(r#"{"offset": [0.5, 0.25], "rotation": 1.5707963267948966, "scale": [2, 3]}"#);
+    // (1, 0) scaled to (2, 0), rotated to (0, -2), then offset
+    common::assert_close(&t.apply([1.0, 0.0]), &[0.5, -1.75], 1e-6);
+    // (0, 1) scaled to (0, 3), rotated to (3, 0)
+    common::assert_close(&t.apply([0.0, 1.0]), &[3.5, 0.25], 1e-6);
+    // column-major, the offset in the last column
+    common::assert_close(&t.matrix(), &[0., -2., 0., 3., 0., 0., 0.5, 0.25, 1.], 1e-6);
+}
+
+#[test]
+fn tex_coord_override() {
+    let material = Material::deserialize_json(
+        r#"{
+            "pbrMetallicRoughness": {"baseColorTexture": {"index": 0, "texCoord": 1}},
+            "normalTexture": {
+                "index": 1,
+                "texCoord": 1,
+                "extensions": {"KHR_texture_transform": {"texCoord": 0, "offset": [1, 0]}}
+            }
+        }"#,
+    )
+    .unwrap();
+    let textures = material.textures();
+    let base_color = &textures[0];
+    assert!(base_color.transform().is_none());
+    assert_eq!(base_color.effective_tex_coord(), 1);
+    let normal = &textures[1];
+    assert_eq!(normal.path, "/normalTexture");
+    assert_eq!(normal.effective_tex_coord(), 0);
+
+    let (mut gltf, buffers) = common::fixture("quad.gltf");
+    gltf.materials[0] = material;
+    let primitive = &gltf.meshes[0].primitives[0];
+    let textures = gltf.materials[0].textures();
+    // the quad only has TEXCOORD_0
+    assert!(
+        texture_transform::baked_tex_coords(&gltf, &buffers, primitive, &textures[0]).is_none()
+    );
+    let baked = texture_transform::baked_tex_coords(&gltf, &buffers, primitive, &textures[1]);
+    assert_eq!(baked.unwrap(), [[1., 0.], [2., 0.], [2., 1.], [1., 1.]]);
+}
@@ -0,0 +1,165 @@
+mod common;
+
+use nanogltf::validate;
+use std::panic::AssertUnwindSafe;
+
+fn errors(gltf: &nanogltf::Gltf, buffers: &[Vec<u8>]) -> Vec<(&'static str, String)> {
+    validate::validate(gltf, buffers)
+        .errors()
+        .map(|issue| (issue.code, issue.pointer.clone()))
+        .collect()
+}
+
+#[test]
+fn valid_document() {
+    let (gltf, buffers) = common::fixture("quad.gltf");
+    let report = validate::validate(&gltf, &buffers);
+    assert!(report.is_valid(), "{report:#?}");
+}
+
+#[test]
+fn unresolved_references() {
+    let (mut gltf, buffers) = common::fixture("quad.gltf");
+    gltf.nodes[1].mesh = Some(7);
+    gltf.meshes[0].primitives[0].material = Some(3);
+    gltf.accessors[1].buffer_view = Some(9);
+    let errors = errors(&gltf, &buffers);
+    for pointer in [
+        "/nodes/1/mesh",
+        "/meshes/0/primitives/0/material",
+        "/accessors/1/bufferView",
+    ] {
+        assert!(
+            errors.contains(&("UNRESOLVED_REFERENCE", pointer.to_string())),
+            "{pointer} not in {errors:?}"
+        );
+    }
+}
+
+#[test]
+fn out_of_bounds_layouts() {
+    let (mut gltf, buffers) = common::fixture("quad.gltf");
+    gltf.buffer_views[1].byte_length = 200;
+    gltf.accessors[3].count = 5;
+    gltf.buffer_views[0].byte_stride = Some(6);
+    let errors = errors(&gltf, &buffers);
+    assert!(errors.contains(&("BUFFER_VIEW_TOO_LONG", "/bufferViews/1".to_string())));
+    assert!(errors.contains(&("ACCESSOR_TOO_LONG", "/accessors/3".to_string())));
+    assert!(errors.contains(&("VALUE_MULTIPLE_OF", "/bufferViews/0/byteStride".to_string())));
+}
+
+#[test]
+fn overflowing_sizes() {
+    let (mut gltf, buffers) = common::fixture("quad.gltf");
+    gltf.buffer_views[1].byte_offset = usize::MAX;
+    gltf.accessors[1].byte_offset = usize::MAX - 3;
+    gltf.accessors[3].count = usize::MAX / 2;
+    gltf.accessors[0].sparse.as_mut().unwrap().count = usize::MAX;
+    let errors = errors(&gltf, &buffers);
+    assert!(errors.contains(&("BUFFER_VIEW_TOO_LONG", "/bufferViews/1".to_string())));
+    assert!(errors.contains(&("ACCESSOR_TOO_LONG", "/accessors/1".to_string())));
+    assert!(errors.contains(&("ACCESSOR_TOO_LONG", "/accessors/3".to_string())));
+    assert!(errors.contains(&(
+        "ACCESSOR_TOO_LONG",
+        "/accessors/0/sparse/values".to_string()
+    )));
+}
+
+/// Byte ranges of the numbers outside of strings.
+fn numbers(json: &str) -> Vec<(usize, usize)> {
+    let bytes = json.as_bytes();
+    let mut res = vec![];
+    let mut in_string = false;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => in_string = !in_string,
+            b'0'..=b'9' if !in_string => {
+                let start = i;
+                while i < bytes.len() && matches!(bytes[i], b'0'..=b'9' | b'.' | b'-' | b'e') {
+                    i += 1;
+                }
+                res.push((start, i));
+                continue;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    res
+}
+
+#[test]
+fn extreme_values_never_panic() {
+    let json = std::fs::read_to_string(common::fixture_path("quad.gltf")).unwrap();
+    let (_, buffers) = common::load(&json);
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(|_| {}));
+    let mut panics = vec![];
+    for (start, end) in numbers(&json) {
+        for value in ["0", "3", "255", "4294967295", "18446744073709551615"] {
+            let mutated = format!("{}{value}{}", &json[..start], &json[end..]);
+            // The parser itself still panics on unknown enum values.
+            let Ok(Ok(gltf)) = std::panic::catch_unwind(|| nanogltf::Gltf::from_json(&mutated))
+            else {
+                continue;
+            };
+            if std::panic::catch_unwind(AssertUnwindSafe(|| validate::validate(&gltf, &buffers)))
+                .is_err()
+            {
+                panics.push(format!("{value} at byte {start}"));
+            }
+        }
+    }
+    std::panic::set_hook(hook);
+    assert!(panics.is_empty(), "validate panicked for {panics:?}");
+}
+
+#[test]
+fn morph_target_texcoord_formats() {
+    // (componentType, normalized): float, normalized (unsigned) byte and short.
+    let core = [
+        (5126, false),
+        (5120, true),
+        (5121, true),
+        (5122, true),
+        (5123, true),
+    ];
+    let quantized_only = [(5120, false), (5122, false)];
+    for quantized in [false, true] {
+        for (component_type, normalized) in core.into_iter().chain(quantized_only) {
+            let (mut gltf, buffers) = common::fixture("quad.gltf");
+            let primitive = &mut gltf.meshes[0].primitives[0];
+            primitive.attributes.remove("NORMAL");
+            primitive.targets = Some(vec![[("TEXCOORD_0".to_string(), 3)].into()]);
+            gltf.accessors[3].type_ = Some("VEC2".to_string());
+            gltf.accessors[3].component_type = component_type.try_into().unwrap();
+            gltf.accessors[3].normalized = normalized;
+            if !quantized {
+                gltf.extensions_used.clear();
+                gltf.extensions_required.clear();
+            }
+
+            let expected_valid = quantized || core.contains(&(component_type, normalized));
+            let invalid = errors(&gltf, &buffers).contains(&(
+                "MESH_PRIMITIVE_ATTRIBUTES_ACCESSOR_INVALID_FORMAT",
+                "/meshes/0/primitives/0/targets/0/TEXCOORD_0".to_string(),
+            ));
+            assert_eq!(
+                invalid, !expected_valid,
+                "{component_type} normalized: {normalized}, quantized: {quantized}"
+            );
+        }
+    }
+}
+
+#[test]
+fn meshopt_view_with_a_missing_buffer() {
+    let (mut gltf, buffers) = common::fixture("meshopt.gltf");
+    gltf.buffer_views[0].buffer = 5;
+    let errors = errors(&gltf, &buffers);
+    assert!(
+        errors.contains(&("UNRESOLVED_REFERENCE", "/bufferViews/0/buffer".to_string())),
+        "{errors:?}"
+    );
+}
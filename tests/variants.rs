@@ -0,0 +1,88 @@
+mod common;
+
+use nanogltf::variants;
+
+const SCENE: &str = r#"{
+    "asset": {"version": "2.0"},
+    "extensionsUsed": ["KHR_materials_variants"],
+    "extensions": {
+        "KHR_materials_variants": {
+            "variants": [{"name": "midnight"}, {"name": "beach"}, {"name": "street"}]
+        }
+    },
+    "materials": [{}, {}, {}, {}, {}],
+    "meshes": [
+        {"primitives": [
+            {
+                "attributes": {},
+                "material": 0,
+                "extensions": {"KHR_materials_variants": {"mappings": [
+                    {"material": 1, "variants": [0]},
+                    {"material": 2, "variants": [1, 2]}
+                ]}}
+            },
+            {
+                "attributes": {},
+                "extensions": {"KHR_materials_variants": {"mappings": [
+                    {"material": 3, "variants": [1]}
+                ]}}
+            }
+        ]},
+        {"primitives": [{"attributes": {}, "material": 4}]}
+    ]
+}"#;
+
+#[test]
+fn variant_names() {
+    let (gltf, _) = common::load(SCENE);
+    let names: Vec<_> = variants::variants(&gltf)
+        .iter()
+        .map(|variant| variant.name.as_str())
+        .collect();
+    assert_eq!(names, ["midnight", "beach", "street"]);
+    assert_eq!(variants::variant_index(&gltf, "street"), Some(2));
+    assert_eq!(variants::variant_index(&gltf, "winter"), None);
+
+    let (gltf, _) = common::load(r#"{"asset": {"version": "2.0"}}"#);
+    assert!(variants::variants(&gltf).is_empty());
+    assert_eq!(variants::variant_index(&gltf, "midnight"), None);
+}
+
+#[test]
+fn primitive_material() {
+    let (gltf, _) = common::load(SCENE);
+    let primitives = &gltf.meshes[0].primitives;
+    assert_eq!(variants::primitive_material(&primitives[0], 0), Some(1));
+    // a mapping may list several variants
+    assert_eq!(variants::primitive_material(&primitives[0], 1), Some(2));
+    assert_eq!(variants::primitive_material(&primitives[0], 2), Some(2));
+    // unmapped variants keep the default material, even when there is none
+    assert_eq!(variants::primitive_material(&primitives[1], 0), None);
+    assert_eq!(variants::primitive_material(&primitives[1], 1), Some(3));
+    // primitives without the extension, out of range variants
+    let primitive = &gltf.meshes[1].primitives[0];
+    assert_eq!(variants::primitive_material(primitive, 1), Some(4));
+    assert_eq!(variants::primitive_material(&primitives[0], 7), Some(0));
+}
+
+#[test]
+fn materials() {
+    let (gltf, _) = common::load(SCENE);
+    assert_eq!(
+        variants::materials(&gltf, None),
+        [vec![Some(0), None], vec![Some(4)]]
+    );
+    assert_eq!(
+        variants::materials(&gltf, Some(0)),
+        [vec![Some(1), None], vec![Some(4)]]
+    );
+    assert_eq!(
+        variants::materials(&gltf, Some(1)),
+        [vec![Some(2), Some(3)], vec![Some(4)]]
+    );
+    assert_eq!(
+        variants::materials_by_name(&gltf, "street"),
+        Some(vec![vec![Some(2), None], vec![Some(4)]])
+    );
+    assert_eq!(variants::materials_by_name(&gltf, "winter"), None);
+}
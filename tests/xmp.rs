@@ -0,0 +1,43 @@
+mod common;
+
+use nanogltf::gltf::{JsonValue, XmpPacket, XmpPackets};
+use nanogltf::xmp;
+use nanoserde::{DeJson, SerJson};
+
+#[test]
+fn packets_round_trip() {
+    let (gltf, _) = common::fixture("xmp.gltf");
+    let packets = xmp::packets(&gltf);
+    // key order, escapes and numbers are kept
+    let expected = [
+        r#"{"@context":{"dc":"http://purl.org/dc/elements/1.1/"},"dc:title":{"@type":"rdf:Alt","rdf:_1":{"@language":"en-us","@value":"Helmet \"X\""}},"dc:creator":{"@list":["Alice","Bob"]},"dc:rights":"CC-BY 4.0","n":[1,2.5,-3,true,null]}"#,
+        r#"{"z":1,"a":2}"#,
+    ];
+    assert_eq!(packets.len(), expected.len());
+    for (packet, expected) in packets.iter().zip(expected) {
+        let json = packet.to_json();
+        assert_eq!(json, expected);
+        assert_eq!(JsonValue::deserialize_json(&json).unwrap(), *packet);
+    }
+    assert_eq!(
+        xmp::asset_packet(&gltf)
+            .and_then(|packet| packet.get("dc:rights"))
+            .unwrap(),
+        &JsonValue::String("CC-BY 4.0".to_string())
+    );
+}
+
+#[test]
+fn write_back() {
+    let (gltf, _) = common::fixture("xmp.gltf");
+    let json = xmp::extension_json(&gltf).unwrap();
+    let written = XmpPackets::deserialize_json(&json).unwrap();
+    assert_eq!(written.packets, xmp::packets(&gltf));
+    assert!(json.starts_with(r#"{"packets":[{"@context":"#), "{json}");
+
+    let reference = XmpPacket { packet: 1 };
+    assert_eq!(reference.serialize_json(), r#"{"packet":1}"#);
+
+    let (gltf, _) = common::load(r#"{"asset": {"version": "2.0"}}"#);
+    assert_eq!(xmp::extension_json(&gltf), None);
+}